@@ -0,0 +1,112 @@
+//! Benchmarks of drawing into a display buffer.
+//! `cargo bench --features="std" --bench render`
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use embedded_graphics::{
+    mono_font::{ascii::FONT_6X10, MonoTextStyle},
+    pixelcolor::Rgb888,
+    prelude::*,
+    primitives::{Circle, PrimitiveStyle, Rectangle},
+    text::Text,
+};
+use epd_spectra::{Display4in17, DisplayRotation, GrayDither, MappedTarget, TriColor};
+
+const ROTATIONS: [DisplayRotation; 4] = [
+    DisplayRotation::Rotate0,
+    DisplayRotation::Rotate90,
+    DisplayRotation::Rotate180,
+    DisplayRotation::Rotate270,
+];
+
+/// Lines of text filling the whole display with the 6x10 font
+fn text(c: &mut Criterion) {
+    let mut display = Display4in17::default();
+    let style = MonoTextStyle::new(&FONT_6X10, TriColor::Black);
+    let columns = display.size().width as usize / 6;
+    let line: String = ('!'..='~').cycle().take(columns).collect();
+    let lines: Vec<&str> = vec![&line; display.size().height as usize / 10];
+    let text = lines.join("\n");
+    c.bench_function("text full screen", |b| {
+        b.iter(|| {
+            Text::new(black_box(&text), Point::new(0, 7), style)
+                .draw(&mut display)
+                .unwrap();
+        });
+    });
+}
+
+fn clear(c: &mut Criterion) {
+    let mut display = Display4in17::default();
+    c.bench_function("clear", |b| {
+        b.iter(|| display.clear(black_box(TriColor::Red)).unwrap());
+    });
+}
+
+/// Horizontal gray gradient over the whole display, mapped with ordered
+/// dithering
+#[allow(
+    clippy::cast_possible_truncation,
+    clippy::cast_possible_wrap,
+    clippy::cast_sign_loss
+)]
+fn dithering(c: &mut Criterion) {
+    let mut display = Display4in17::default();
+    let size = display.size();
+    let gradient: Vec<Pixel<Rgb888>> = size_points(size)
+        .map(|point| {
+            let level = (point.x * 255 / size.width as i32) as u8;
+            Pixel(point, Rgb888::new(level, level, level))
+        })
+        .collect();
+    c.bench_function("dithering full screen", |b| {
+        b.iter(|| {
+            let mut target = MappedTarget::new(&mut display, GrayDither::default());
+            target
+                .draw_iter(black_box(&gradient).iter().copied())
+                .unwrap();
+        });
+    });
+}
+
+/// The same scene drawn in each rotation, and rotation of the rendered
+/// buffer
+fn rotation(c: &mut Criterion) {
+    let mut group = c.benchmark_group("rotation");
+    let mut display = Display4in17::default();
+    for rotation in ROTATIONS {
+        display.set_rotation(rotation);
+        group.bench_function(format!("draw {rotation:?}"), |b| {
+            b.iter(|| {
+                Rectangle::new(Point::new(10, 10), Size::new(200, 150))
+                    .into_styled(PrimitiveStyle::with_fill(TriColor::Black))
+                    .draw(&mut display)
+                    .unwrap();
+                Circle::new(Point::new(50, 50), 120)
+                    .into_styled(PrimitiveStyle::with_stroke(TriColor::Red, 5))
+                    .draw(&mut display)
+                    .unwrap();
+            });
+        });
+    }
+    display.set_rotation(DisplayRotation::Rotate0);
+    Circle::new(Point::new(50, 50), 120)
+        .into_styled(PrimitiveStyle::with_fill(TriColor::Red))
+        .draw(&mut display)
+        .unwrap();
+    group.bench_function("rotate_buffer Rotate180", |b| {
+        b.iter(|| {
+            display
+                .rotate_buffer(black_box(DisplayRotation::Rotate180), &mut [])
+                .unwrap();
+        });
+    });
+    group.finish();
+}
+
+#[allow(clippy::cast_possible_wrap)]
+fn size_points(size: Size) -> impl Iterator<Item = Point> {
+    (0..size.height as i32).flat_map(move |y| (0..size.width as i32).map(move |x| Point::new(x, y)))
+}
+
+criterion_group!(benches, clear, text, dithering, rotation);
+criterion_main!(benches);
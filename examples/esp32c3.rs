@@ -0,0 +1,95 @@
+//! Simple no-std "Hello World" example for ESP32-C3 boards with esp-hal
+//! and a 2.66 inch e-paper display. For the ESP32-S3 change the features
+//! of the esp-hal dependencies and the target.
+//!
+//! Connections:
+//!
+//! | ESP32-C3 | EPD   |
+//! |----------|-------|
+//! | GPIO6    | SCK   |
+//! | GPIO7    | MOSI  |
+//! | GPIO10   | CS    |
+//! | GPIO3    | BUSY  |
+//! | GPIO4    | DC    |
+//! | GPIO5    | RESET |
+//!
+//! If you have another display size, simply replace `Display2in66` with your display.
+//! To run this example with [espflash](https://github.com/esp-rs/espflash) run:
+//! `RUSTFLAGS="-C link-arg=-Tlinkall.x" cargo run --example esp32c3 --features esp-hal --target riscv32imc-unknown-none-elf --config 'target.riscv32imc-unknown-none-elf.runner="espflash flash --monitor"'`
+
+#![no_main]
+#![no_std]
+#![cfg(target_os = "none")]
+
+use embedded_graphics::{
+    mono_font::{iso_8859_1::FONT_10X20, MonoTextStyle},
+    prelude::*,
+    text::Text,
+};
+use epd_spectra::{Display2in66, Epd, TriColor};
+use esp_hal::{
+    delay::Delay,
+    gpio::{Input, InputConfig, Level, Output, OutputConfig},
+    main,
+    spi::{
+        master::{Config, Spi},
+        Mode,
+    },
+    time::Rate,
+};
+
+esp_bootloader_esp_idf::esp_app_desc!();
+
+#[panic_handler]
+fn panic(_info: &core::panic::PanicInfo) -> ! {
+    loop {}
+}
+
+#[main]
+fn main() -> ! {
+    let peripherals = esp_hal::init(esp_hal::Config::default());
+    let mut delay = Delay::new();
+
+    // Configure GPIO pins, all outputs are idle high
+    let busy = Input::new(peripherals.GPIO3, InputConfig::default());
+    let dc = Output::new(peripherals.GPIO4, Level::High, OutputConfig::default());
+    let reset = Output::new(peripherals.GPIO5, Level::High, OutputConfig::default());
+    let cs = Output::new(peripherals.GPIO10, Level::High, OutputConfig::default());
+
+    // Configure SPI, without hardware chip select
+    let config = Config::default()
+        .with_frequency(Rate::from_mhz(4))
+        .with_mode(Mode::_0);
+    let spi = Spi::new(peripherals.SPI2, config)
+        .unwrap()
+        .with_sck(peripherals.GPIO6)
+        .with_mosi(peripherals.GPIO7);
+
+    // create EPD driver
+    let (epd, mut spi_device) = Epd::new_esp(spi, cs, busy, dc, reset);
+    let mut epd = epd.init(&mut spi_device, &mut delay).unwrap();
+
+    let mut display = Display2in66::default();
+    Text::new(
+        "Hello",
+        Point::new(10, 20),
+        MonoTextStyle::new(&FONT_10X20, TriColor::Black),
+    )
+    .draw(&mut display)
+    .unwrap();
+
+    Text::new(
+        "ESP32",
+        Point::new(30, 60),
+        MonoTextStyle::new(&FONT_10X20, TriColor::Red),
+    )
+    .draw(&mut display)
+    .unwrap();
+
+    epd.update(&display, &mut spi_device, &mut delay).unwrap();
+    let _inactive_epd = epd.power_off(&mut spi_device, &mut delay).unwrap();
+
+    loop {
+        delay.delay_millis(1000);
+    }
+}
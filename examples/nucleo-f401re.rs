@@ -83,7 +83,7 @@ fn main() -> ! {
     let mut spi_device = embedded_hal_bus::spi::ExclusiveDevice::new_no_delay(spi, cs);
 
     // create EPD driver
-    let epd = Epd::new(&mut spi_device, busy, dc, reset, &mut delay, 0);
+    let epd = Epd::new(busy, dc, reset, 0);
     let mut epd = epd.init(&mut spi_device, &mut delay).unwrap();
 
     let mut display = Display2in66::default();
@@ -104,7 +104,7 @@ fn main() -> ! {
     .unwrap();
 
     let ferris: ImageRaw<TriColor> = ImageRaw::new(FERRIS_IMG, FERRIS_WIDTH);
-    let ferris: Image<_> = Image::new(&ferris, Point::new(0, 100));
+    let ferris: Image<_> = Image::new(&ferris, Point::new(140, 40));
     ferris.draw(&mut display).unwrap();
 
     epd.update(&display, &mut spi_device, &mut delay).unwrap();
@@ -48,7 +48,7 @@ fn main() -> Result<(), Box<dyn Error>> {
     .draw(&mut display)?;
 
     let ferris: ImageRaw<TriColor> = ImageRaw::new(FERRIS_IMG, FERRIS_WIDTH);
-    let ferris: Image<_> = Image::new(&ferris, Point::new(0, 100));
+    let ferris: Image<_> = Image::new(&ferris, Point::new(140, 40));
     ferris.draw(&mut display).unwrap();
 
     // setup all peripherals needed for EPD driver
@@ -62,7 +62,7 @@ fn main() -> Result<(), Box<dyn Error>> {
     let rst = gpio.get(17)?.into_output();
     let mut delay = Delay {};
 
-    let epd = Epd::new(&mut spi_device, busy, dc, rst, &mut delay, 4096);
+    let epd = Epd::new(busy, dc, rst, 4096);
     let mut epd = epd.init(&mut spi_device, &mut delay)?;
 
     // show the display
@@ -0,0 +1,29 @@
+//! Reconstruct the frame shown on a 2.66 inch e-paper display from a command
+//! stream captured with a logic analyzer and save it as PPM image.
+//! See `epd_spectra::replay::parse_csv` for the expected CSV format.
+//!
+//! If you have another display size, simply replace `Display2in66` with your display.
+//! `cargo run --example replay --features="testing" -- capture.csv frame.ppm`
+
+use epd_spectra::{
+    replay::{parse_csv, reconstruct, write_ppm},
+    Display2in66,
+};
+use std::{
+    error::Error,
+    fs::File,
+    io::{BufReader, BufWriter},
+};
+
+fn main() -> Result<(), Box<dyn Error>> {
+    let mut args = std::env::args().skip(1);
+    let (Some(input), Some(output)) = (args.next(), args.next()) else {
+        return Err("usage: replay <capture.csv> <frame.ppm>".into());
+    };
+
+    let records = parse_csv(BufReader::new(File::open(input)?))?;
+    let display: Display2in66 = reconstruct(&records);
+    write_ppm(&display, BufWriter::new(File::create(output)?))?;
+
+    Ok(())
+}
@@ -0,0 +1,155 @@
+//! Cycling through a sequence of frames
+
+use embedded_hal::{delay::DelayNs, digital::InputPin, digital::OutputPin, spi::SpiDevice};
+
+use crate::{driver::EpdError, AutoEpd, DisplayBuffer, IterSource, Plane};
+
+/// Content of a [`Frame`]
+#[derive(Clone, Copy)]
+enum Content<'a> {
+    Buffer(&'a dyn DisplayBuffer),
+    Generator {
+        generate: fn(Plane, usize) -> u8,
+        len: usize,
+    },
+}
+
+/// Frame of an [`Animator`] with the time it is shown
+#[derive(Clone, Copy)]
+pub struct Frame<'a> {
+    content: Content<'a>,
+    duration_ms: u32,
+}
+
+impl<'a> Frame<'a> {
+    /// Prepared frame, e.g. a `Display` or a [`RawFrame`](crate::RawFrame)
+    /// stored in flash, shown for `duration_ms` after its refresh
+    #[must_use]
+    pub fn buffer(buffer: &'a dyn DisplayBuffer, duration_ms: u32) -> Self {
+        Self {
+            content: Content::Buffer(buffer),
+            duration_ms,
+        }
+    }
+
+    /// Frame generated each time it is shown, without a display buffer.
+    /// `generate` returns the byte at an index of a plane (as returned by
+    /// `DisplayBuffer`), `len` is the length of a plane.
+    #[must_use]
+    pub fn generator(generate: fn(Plane, usize) -> u8, len: usize, duration_ms: u32) -> Self {
+        Self {
+            content: Content::Generator { generate, len },
+            duration_ms,
+        }
+    }
+
+    #[must_use]
+    pub fn duration_ms(&self) -> u32 {
+        self.duration_ms
+    }
+}
+
+/// Shows a list of frames one after another, starting again after the
+/// last one. The e-paper is driven by an [`AutoEpd`], so it is powered off
+/// during frames shown for at least its idle timeout and kept initialized
+/// between frames following each other quickly.
+pub struct Animator<'a> {
+    frames: &'a [Frame<'a>],
+    next: usize,
+}
+
+impl<'a> Animator<'a> {
+    #[must_use]
+    pub fn new(frames: &'a [Frame<'a>]) -> Self {
+        Self { frames, next: 0 }
+    }
+
+    /// Index of the frame shown next
+    #[must_use]
+    pub fn position(&self) -> usize {
+        self.next
+    }
+
+    /// Continue with the frame at `index` (modulo the number of frames)
+    pub fn seek(&mut self, index: usize) {
+        self.next = index.checked_rem(self.frames.len()).unwrap_or(0);
+    }
+
+    /// Show the next frame and advance to the following one, without
+    /// waiting for the duration of the frame. The time until the next
+    /// frame is counted as idle time of `epd`, which is powered off if the
+    /// duration reaches its idle timeout. Returns the duration of the
+    /// shown frame, `None` if there are no frames. This allows the MCU to
+    /// sleep or do other work between the frames.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if there is an error
+    /// with the GPIOs or the SPI device.
+    pub fn show_next<SPI, BUSY, DC, RST>(
+        &mut self,
+        epd: &mut AutoEpd<BUSY, DC, RST>,
+        spi: &mut SPI,
+        delay: &mut impl DelayNs,
+    ) -> Result<Option<u32>, EpdError<SPI, DC, RST>>
+    where
+        SPI: SpiDevice,
+        BUSY: InputPin,
+        DC: OutputPin,
+        RST: OutputPin,
+    {
+        let Some(frame) = self.frames.get(self.next) else {
+            return Ok(None);
+        };
+        self.next = (self.next + 1) % self.frames.len();
+        match frame.content {
+            Content::Buffer(buffer) => {
+                let planes = (buffer.get_buffer_black(), buffer.get_buffer_red());
+                epd.update(&planes, spi, delay)?;
+            }
+            Content::Generator { generate, len } => {
+                let black = (0..len).map(move |i| generate(Plane::Black, i));
+                let red = (0..len).map(move |i| generate(Plane::Red, i));
+                epd.update_from_source(&mut IterSource::new(black, red), spi, delay)?;
+            }
+        }
+        epd.tick(frame.duration_ms, spi, delay)?;
+        Ok(Some(frame.duration_ms))
+    }
+
+    /// Show all frames `passes` times, waiting with `delay` for the
+    /// duration of each frame, and power off the e-paper at the end.
+    /// This function is blocking until the last frame is shown.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if there is an error
+    /// with the GPIOs or the SPI device.
+    pub fn play<SPI, BUSY, DC, RST>(
+        &mut self,
+        passes: u32,
+        epd: &mut AutoEpd<BUSY, DC, RST>,
+        spi: &mut SPI,
+        delay: &mut impl DelayNs,
+    ) -> Result<(), EpdError<SPI, DC, RST>>
+    where
+        SPI: SpiDevice,
+        BUSY: InputPin,
+        DC: OutputPin,
+        RST: OutputPin,
+    {
+        // counted per pass, frames × passes may overflow a 16-bit usize
+        for pass in 1..=passes {
+            for shown in 1..=self.frames.len() {
+                let Some(duration_ms) = self.show_next(epd, spi, delay)? else {
+                    return epd.power_off(spi, delay);
+                };
+                // no need to wait after the last frame
+                if pass < passes || shown < self.frames.len() {
+                    delay.delay_ms(duration_ms);
+                }
+            }
+        }
+        epd.power_off(spi, delay)
+    }
+}
@@ -0,0 +1,174 @@
+//! Automatic power management on top of the typestate driver
+
+use embedded_hal::{delay::DelayNs, digital::InputPin, digital::OutputPin, spi::SpiDevice};
+
+use crate::{driver::EpdError, Active, DisplayBuffer, Epd, FrameSource, Inactive};
+
+enum AutoState<BUSY, DC, RST> {
+    Active(Epd<Active, BUSY, DC, RST>),
+    Inactive(Epd<Inactive, BUSY, DC, RST>),
+}
+
+/// E-paper driver which initializes the e-paper before an update and
+/// powers it off after a configurable idle time. The idle time is
+/// tracked by calling `tick` periodically.
+pub struct AutoEpd<BUSY, DC, RST> {
+    /// always `Some` outside of state transitions
+    state: Option<AutoState<BUSY, DC, RST>>,
+    /// idle time after which the e-paper is powered off (0: after each update)
+    idle_timeout_ms: u32,
+    /// time since the last update
+    idle_ms: u32,
+}
+
+impl<BUSY, DC, RST> AutoEpd<BUSY, DC, RST>
+where
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+{
+    /// Create an automatically managed driver. `idle_timeout_ms` is the idle
+    /// time after which the e-paper is powered off, 0 powers it off directly
+    /// after each update.
+    pub fn new(epd: Epd<Inactive, BUSY, DC, RST>, idle_timeout_ms: u32) -> Self {
+        Self {
+            state: Some(AutoState::Inactive(epd)),
+            idle_timeout_ms,
+            idle_ms: 0,
+        }
+    }
+
+    /// Returns `true` if the e-paper is currently initialized
+    #[must_use]
+    pub fn is_active(&self) -> bool {
+        matches!(self.state, Some(AutoState::Active(_)))
+    }
+
+    /// Show display on e-paper, initializing it first if needed. This
+    /// function is blocking until the update process is complete.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if there is an error
+    /// with the GPIOs or the SPI device.
+    pub fn update<SPI: SpiDevice>(
+        &mut self,
+        display: &impl DisplayBuffer,
+        spi: &mut SPI,
+        delay: &mut impl DelayNs,
+    ) -> Result<(), EpdError<SPI, DC, RST>> {
+        self.with_active(spi, delay, |epd, spi, delay| {
+            epd.update(display, spi, delay)
+        })
+    }
+
+    /// Show a frame streamed from `source` on e-paper (see
+    /// `Epd::update_from_source`), initializing it first if needed. This
+    /// function is blocking until the update process is complete.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if there is an error
+    /// with the GPIOs or the SPI device.
+    pub fn update_from_source<SPI: SpiDevice>(
+        &mut self,
+        source: &mut impl FrameSource,
+        spi: &mut SPI,
+        delay: &mut impl DelayNs,
+    ) -> Result<(), EpdError<SPI, DC, RST>> {
+        self.with_active(spi, delay, |epd, spi, delay| {
+            epd.update_from_source(source, spi, delay)
+        })
+    }
+
+    /// Advance the idle time by `elapsed_ms` and power off the e-paper
+    /// if the idle timeout is reached.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if there is an error
+    /// with the GPIOs or the SPI device.
+    pub fn tick<SPI: SpiDevice>(
+        &mut self,
+        elapsed_ms: u32,
+        spi: &mut SPI,
+        delay: &mut impl DelayNs,
+    ) -> Result<(), EpdError<SPI, DC, RST>> {
+        self.idle_ms = self.idle_ms.saturating_add(elapsed_ms);
+        if self.is_active() && self.idle_ms >= self.idle_timeout_ms {
+            self.deactivate(spi, delay)?;
+        }
+        Ok(())
+    }
+
+    /// Power off the e-paper immediately if it is active.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if there is an error
+    /// with the GPIOs or the SPI device.
+    pub fn power_off<SPI: SpiDevice>(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut impl DelayNs,
+    ) -> Result<(), EpdError<SPI, DC, RST>> {
+        self.deactivate(spi, delay)
+    }
+
+    /// Run `f` with the initialized e-paper and restart the idle time
+    fn with_active<SPI: SpiDevice, D: DelayNs>(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut D,
+        f: impl FnOnce(
+            &mut Epd<Active, BUSY, DC, RST>,
+            &mut SPI,
+            &mut D,
+        ) -> Result<(), EpdError<SPI, DC, RST>>,
+    ) -> Result<(), EpdError<SPI, DC, RST>> {
+        self.activate(spi, delay)?;
+        if let Some(AutoState::Active(epd)) = &mut self.state {
+            f(epd, spi, delay)?;
+        }
+        self.idle_ms = 0;
+        if self.idle_timeout_ms == 0 {
+            self.deactivate(spi, delay)?;
+        }
+        Ok(())
+    }
+
+    fn activate<SPI: SpiDevice>(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut impl DelayNs,
+    ) -> Result<(), EpdError<SPI, DC, RST>> {
+        let (state, result) = match self.state.take() {
+            Some(AutoState::Inactive(mut epd)) => match epd.init_registers(spi, delay) {
+                Ok(()) => (AutoState::Active(epd.into_state()), Ok(())),
+                Err(error) => (AutoState::Inactive(epd), Err(error)),
+            },
+            Some(state) => (state, Ok(())),
+            None => unreachable!(),
+        };
+        self.state = Some(state);
+        result
+    }
+
+    fn deactivate<SPI: SpiDevice>(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut impl DelayNs,
+    ) -> Result<(), EpdError<SPI, DC, RST>> {
+        let (state, result) = match self.state.take() {
+            // the e-paper is reinitialized on the next update even if powering off fails
+            Some(AutoState::Active(mut epd)) => {
+                let result = epd.power_down(spi, delay);
+                (AutoState::Inactive(epd.into_state()), result)
+            }
+            Some(state) => (state, Ok(())),
+            None => unreachable!(),
+        };
+        self.state = Some(state);
+        result
+    }
+}
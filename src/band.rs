@@ -0,0 +1,250 @@
+//! Drawing in horizontal bands for boards with very little RAM, e.g.
+//! ATmega-class MCUs which cannot hold a single plane of the panel
+
+use embedded_graphics::{
+    draw_target::DrawTarget,
+    geometry::{OriginDimensions, Point, Size},
+    Pixel,
+};
+
+use crate::{image_size, Display, DisplayRotation, FrameSource, Plane, TriColor};
+
+/// Draw target holding `BAND_LEN` bytes of one plane, a band of whole
+/// rows of the panel. Instead of keeping the frame in RAM, the scene is
+/// drawn again for every band of each plane while the frame is streamed
+/// with [`BandDisplay::frame`], so drawing has to be deterministic. Pixels
+/// outside of the current band are dropped. `BAND_LEN` must be a multiple
+/// of the bytes per row (`SIZE_H / 8`), e.g.
+/// `BandDisplay::<152, 152, { 19 * 8 }>` needs 152 bytes for a 1.54"
+/// panel instead of the 5776 bytes of a `Display1in54`.
+pub struct BandDisplay<const SIZE_V: u32, const SIZE_H: u32, const BAND_LEN: usize> {
+    buffer: [u8; BAND_LEN],
+    /// plane and first panel row of the band
+    plane: Plane,
+    top: u32,
+    rotation: DisplayRotation,
+}
+
+impl<const SIZE_V: u32, const SIZE_H: u32, const BAND_LEN: usize>
+    BandDisplay<SIZE_V, SIZE_H, BAND_LEN>
+{
+    const ROW_LEN: usize = image_size(1, SIZE_H);
+    const PLANE_LEN: usize = image_size(SIZE_V, SIZE_H);
+    /// Evaluated when a band display is created, so a `BAND_LEN` which
+    /// does not hold whole rows is a compile error
+    const GEOMETRY: () = assert!(
+        BAND_LEN >= Self::ROW_LEN && BAND_LEN % Self::ROW_LEN == 0,
+        "band length must be a multiple of the bytes per row"
+    );
+
+    /// Band display in the natural orientation of the panel
+    #[must_use]
+    pub const fn new() -> Self {
+        let () = Self::GEOMETRY;
+        Self {
+            buffer: [0; BAND_LEN],
+            plane: Plane::Black,
+            top: 0,
+            rotation: DisplayRotation::natural(SIZE_V, SIZE_H),
+        }
+    }
+
+    pub fn set_rotation(&mut self, rotation: DisplayRotation) {
+        self.rotation = rotation;
+    }
+    #[must_use]
+    pub fn rotation(&self) -> DisplayRotation {
+        self.rotation
+    }
+
+    /// Frame source drawing the scene with `draw` band by band, see
+    /// `Epd::update_from_source`. `draw` is called twice per band for
+    /// panels with red film (once per plane) and once otherwise.
+    pub fn frame<F>(&mut self, draw: F) -> BandFrame<'_, SIZE_V, SIZE_H, BAND_LEN, F>
+    where
+        F: FnMut(&mut Self),
+    {
+        BandFrame {
+            band: self,
+            draw,
+            plane: Plane::Black,
+            position: 0,
+            rendered: None,
+        }
+    }
+
+    /// Clear the buffer and draw the band of `plane` starting at byte
+    /// `start` of the plane
+    #[allow(clippy::cast_possible_truncation)]
+    fn render(&mut self, plane: Plane, start: usize, draw: &mut impl FnMut(&mut Self)) {
+        self.buffer.fill(0);
+        self.plane = plane;
+        // the row is below SIZE_V, so it fits into u32
+        self.top = (start / Self::ROW_LEN) as u32;
+        draw(self);
+    }
+
+    #[allow(clippy::cast_sign_loss, clippy::cast_possible_wrap)]
+    fn set_pixel(&mut self, point: Point, color: TriColor) {
+        let (x, y) = Display::<SIZE_V, SIZE_H, 0>::rotate_point(self.rotation, point.x, point.y);
+        if x < 0 || x >= SIZE_H as i32 || y < self.top as i32 {
+            return;
+        }
+        let row = (y as u32 - self.top) as usize;
+        let index = row * Self::ROW_LEN + x as usize / 8;
+        let Some(byte) = self.buffer.get_mut(index) else {
+            return;
+        };
+        let mask: u8 = 1 << (7 - (x % 8));
+        let on = matches!(
+            (self.plane, color),
+            (Plane::Black, TriColor::Black) | (Plane::Red, TriColor::Red)
+        );
+        if on {
+            *byte |= mask;
+        } else {
+            *byte &= !mask;
+        }
+    }
+}
+
+impl<const SIZE_V: u32, const SIZE_H: u32, const BAND_LEN: usize> Default
+    for BandDisplay<SIZE_V, SIZE_H, BAND_LEN>
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<const SIZE_V: u32, const SIZE_H: u32, const BAND_LEN: usize> OriginDimensions
+    for BandDisplay<SIZE_V, SIZE_H, BAND_LEN>
+{
+    fn size(&self) -> Size {
+        Display::<SIZE_V, SIZE_H, 0>::rotated_size(self.rotation)
+    }
+}
+
+impl<const SIZE_V: u32, const SIZE_H: u32, const BAND_LEN: usize> DrawTarget
+    for BandDisplay<SIZE_V, SIZE_H, BAND_LEN>
+{
+    type Color = TriColor;
+    type Error = core::convert::Infallible;
+
+    fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Pixel<Self::Color>>,
+    {
+        for Pixel(point, color) in pixels {
+            self.set_pixel(point, color);
+        }
+        Ok(())
+    }
+}
+
+/// Frame drawn band by band into a [`BandDisplay`], created with
+/// [`BandDisplay::frame`]
+pub struct BandFrame<'a, const SIZE_V: u32, const SIZE_H: u32, const BAND_LEN: usize, F> {
+    band: &'a mut BandDisplay<SIZE_V, SIZE_H, BAND_LEN>,
+    draw: F,
+    /// plane and position of the next byte sent
+    plane: Plane,
+    position: usize,
+    /// start of the band in the buffer, `None` before the first band
+    rendered: Option<usize>,
+}
+
+impl<const SIZE_V: u32, const SIZE_H: u32, const BAND_LEN: usize, F> FrameSource
+    for BandFrame<'_, SIZE_V, SIZE_H, BAND_LEN, F>
+where
+    F: FnMut(&mut BandDisplay<SIZE_V, SIZE_H, BAND_LEN>),
+{
+    fn next_chunk(&mut self, plane: Plane, buf: &mut [u8]) -> usize {
+        if plane != self.plane {
+            self.plane = plane;
+            self.position = 0;
+            self.rendered = None;
+        }
+        let plane_len = BandDisplay::<SIZE_V, SIZE_H, BAND_LEN>::PLANE_LEN;
+        let mut len = 0;
+        while len < buf.len() && self.position < plane_len {
+            let start = self.position - self.position % BAND_LEN;
+            if self.rendered != Some(start) {
+                self.band.render(plane, start, &mut self.draw);
+                self.rendered = Some(start);
+            }
+            let offset = self.position - start;
+            let count = (buf.len() - len)
+                .min(BAND_LEN - offset)
+                .min(plane_len - self.position);
+            buf[len..len + count].copy_from_slice(&self.band.buffer[offset..offset + count]);
+            len += count;
+            self.position += count;
+        }
+        len
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use embedded_graphics::{
+        primitives::{Circle, Line, Primitive, PrimitiveStyle, Rectangle},
+        Drawable,
+    };
+
+    use super::*;
+    use crate::{Display2in66, DisplayBuffer};
+
+    const LEN: usize = image_size(296, 152);
+
+    fn scene<D>(target: &mut D)
+    where
+        D: DrawTarget<Color = TriColor, Error = core::convert::Infallible>,
+    {
+        Rectangle::new(Point::new(5, 3), Size::new(40, 30))
+            .into_styled(PrimitiveStyle::with_fill(TriColor::Black))
+            .draw(target)
+            .unwrap();
+        Circle::new(Point::new(20, 10), 50)
+            .into_styled(PrimitiveStyle::with_stroke(TriColor::Red, 3))
+            .draw(target)
+            .unwrap();
+        Line::new(Point::new(0, 0), Point::new(151, 151))
+            .into_styled(PrimitiveStyle::with_stroke(TriColor::Black, 1))
+            .draw(target)
+            .unwrap();
+    }
+
+    /// Plane streamed in chunks which do not line up with the bands
+    fn stream(frame: &mut impl FrameSource, plane: Plane) -> [u8; LEN] {
+        let mut data = [0; LEN];
+        let mut position = 0;
+        while position < LEN {
+            let end = (position + 100).min(LEN);
+            let len = frame.next_chunk(plane, &mut data[position..end]);
+            assert!(len > 0);
+            position += len;
+        }
+        data
+    }
+
+    #[test]
+    fn bands_match_the_display_buffer() {
+        for rotation in [
+            DisplayRotation::Rotate0,
+            DisplayRotation::Rotate90,
+            DisplayRotation::Rotate180,
+            DisplayRotation::Rotate270,
+        ] {
+            let mut display = Display2in66::new();
+            display.set_rotation(rotation);
+            scene(&mut display);
+
+            // 8 rows of 19 bytes
+            let mut band = BandDisplay::<296, 152, { 19 * 8 }>::new();
+            band.set_rotation(rotation);
+            let mut frame = band.frame(scene);
+            assert_eq!(stream(&mut frame, Plane::Black), display.get_buffer_black());
+            assert_eq!(stream(&mut frame, Plane::Red), display.get_buffer_red());
+        }
+    }
+}
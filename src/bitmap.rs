@@ -0,0 +1,352 @@
+//! Monochrome XBM and PBM images without additional crates
+
+use embedded_graphics::{
+    draw_target::{DrawTarget, DrawTargetExt},
+    geometry::{OriginDimensions, Point, Size},
+    image::ImageDrawable,
+    pixelcolor::BinaryColor,
+    primitives::Rectangle,
+    Pixel,
+};
+
+/// Error while parsing a [`Bitmap`]
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum BitmapError {
+    /// the data is no PBM (P1 or P4) or XBM image
+    UnknownFormat,
+    /// width or height are missing or invalid
+    InvalidHeader,
+    /// the data is shorter than given by the size
+    MissingData,
+}
+
+/// Pixel data of a bitmap, as found in the file
+#[derive(Clone, Copy)]
+enum Data<'a> {
+    /// rows of bits, most significant bit first (PBM P4)
+    Packed(&'a [u8]),
+    /// characters '0' and '1' (PBM P1)
+    Ascii(&'a [u8]),
+    /// comma separated hex bytes of rows, least significant bit first (XBM)
+    Xbm(&'a [u8]),
+}
+
+/// Monochrome image parsed from the data of an XBM or PBM file, e.g. a
+/// logo exported by GIMP and embedded with `include_bytes!`. No copy of the
+/// pixel data is made. Set pixels are drawn as `On`, so the image can be
+/// drawn into the black plane with [`Display::black_layer`](crate::Display::black_layer)
+/// and `embedded_graphics::image::Image`.
+#[derive(Clone, Copy)]
+pub struct Bitmap<'a> {
+    width: u32,
+    height: u32,
+    data: Data<'a>,
+}
+
+impl<'a> Bitmap<'a> {
+    /// Parse the data of a PBM file, in plain (P1) or raw (P4) format
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the data is no valid PBM file.
+    pub fn from_pbm(data: &'a [u8]) -> Result<Self, BitmapError> {
+        let mut header = Header { data, pos: 0 };
+        let magic = header.token().ok_or(BitmapError::UnknownFormat)?;
+        if magic != b"P1" && magic != b"P4" {
+            return Err(BitmapError::UnknownFormat);
+        }
+        let width = header.number()?;
+        let height = header.number()?;
+        // a single whitespace character separates the header from the raster
+        let raster = data.get(header.pos + 1..).unwrap_or_default();
+        let (data, complete) = if magic == b"P4" {
+            let len = raster.len();
+            (
+                Data::Packed(raster),
+                len >= width.div_ceil(8) as usize * height as usize,
+            )
+        } else {
+            let pixels = raster.iter().filter(|&&b| b == b'0' || b == b'1').count();
+            (
+                Data::Ascii(raster),
+                pixels >= width as usize * height as usize,
+            )
+        };
+        let bitmap = Self {
+            width,
+            height,
+            data,
+        };
+        bitmap.validate(complete)
+    }
+
+    /// Parse the data of an XBM file (C source with the defines for width
+    /// and height and the array of pixel bytes)
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the data is no valid XBM file.
+    pub fn from_xbm(data: &'a [u8]) -> Result<Self, BitmapError> {
+        let start = find(data, b"{").ok_or(BitmapError::UnknownFormat)? + 1;
+        let end = start + find(&data[start..], b"}").ok_or(BitmapError::MissingData)?;
+        let header = &data[..start];
+        let width = define(header, b"_width ")?;
+        let height = define(header, b"_height ")?;
+        let bitmap = Self {
+            width,
+            height,
+            data: Data::Xbm(&data[start..end]),
+        };
+        let complete =
+            xbm_bytes(&data[start..end]).count() >= bitmap.bytes_per_row() * height as usize;
+        bitmap.validate(complete)
+    }
+
+    /// Width and height must not be 0 and the data must contain all pixels
+    fn validate(self, complete: bool) -> Result<Self, BitmapError> {
+        if self.width == 0 || self.height == 0 {
+            return Err(BitmapError::InvalidHeader);
+        }
+        if !complete {
+            return Err(BitmapError::MissingData);
+        }
+        Ok(self)
+    }
+
+    fn bytes_per_row(&self) -> usize {
+        self.width.div_ceil(8) as usize
+    }
+
+    /// All pixels of the image, row by row
+    #[allow(clippy::cast_possible_truncation, clippy::cast_possible_wrap)]
+    fn pixels(&self) -> impl Iterator<Item = Pixel<BinaryColor>> + '_ {
+        let (width, height) = (self.width as usize, self.height as usize);
+        let bytes_per_row = self.bytes_per_row();
+        let bits = match self.data {
+            Data::Packed(raster) => Bits::Packed((0..width * height).map(move |i| {
+                let (x, y) = (i % width, i / width);
+                raster[y * bytes_per_row + x / 8] & (0x80 >> (x % 8)) != 0
+            })),
+            Data::Ascii(raster) => Bits::Ascii(
+                raster
+                    .iter()
+                    .filter(|&&b| b == b'0' || b == b'1')
+                    .map(|&b| b == b'1'),
+            ),
+            Data::Xbm(bytes) => {
+                Bits::Xbm(xbm_bytes(bytes).enumerate().flat_map(move |(i, byte)| {
+                    // rows are padded to whole bytes
+                    let padding = if i % bytes_per_row == bytes_per_row - 1 {
+                        bytes_per_row * 8 - width
+                    } else {
+                        0
+                    };
+                    (0..8 - padding).map(move |bit| byte & (1 << bit) != 0)
+                }))
+            }
+        };
+        bits.take(width * height).enumerate().map(move |(i, on)| {
+            let point = Point::new((i % width) as i32, (i / width) as i32);
+            Pixel(point, BinaryColor::from(on))
+        })
+    }
+}
+
+impl OriginDimensions for Bitmap<'_> {
+    fn size(&self) -> Size {
+        Size::new(self.width, self.height)
+    }
+}
+
+impl ImageDrawable for Bitmap<'_> {
+    type Color = BinaryColor;
+
+    fn draw<D>(&self, target: &mut D) -> Result<(), D::Error>
+    where
+        D: DrawTarget<Color = Self::Color>,
+    {
+        target.draw_iter(self.pixels())
+    }
+
+    fn draw_sub_image<D>(&self, target: &mut D, area: &Rectangle) -> Result<(), D::Error>
+    where
+        D: DrawTarget<Color = Self::Color>,
+    {
+        let area = area.intersection(&Rectangle::new(Point::zero(), self.size()));
+        let mut target = target.translated(-area.top_left);
+        let mut target = target.clipped(&Rectangle::new(area.top_left, area.size));
+        self.draw(&mut target)
+    }
+}
+
+/// Pixels of the different formats, one bool for each pixel
+enum Bits<P, A, X> {
+    Packed(P),
+    Ascii(A),
+    Xbm(X),
+}
+
+impl<P, A, X> Iterator for Bits<P, A, X>
+where
+    P: Iterator<Item = bool>,
+    A: Iterator<Item = bool>,
+    X: Iterator<Item = bool>,
+{
+    type Item = bool;
+
+    fn next(&mut self) -> Option<bool> {
+        match self {
+            Bits::Packed(bits) => bits.next(),
+            Bits::Ascii(bits) => bits.next(),
+            Bits::Xbm(bits) => bits.next(),
+        }
+    }
+}
+
+/// Cursor over the whitespace separated tokens of a PBM header
+struct Header<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Header<'a> {
+    /// Next token, skipping whitespace and comments
+    fn token(&mut self) -> Option<&'a [u8]> {
+        loop {
+            match self.data.get(self.pos)? {
+                b if b.is_ascii_whitespace() => self.pos += 1,
+                b'#' => {
+                    while self.data.get(self.pos).is_some_and(|&b| b != b'\n') {
+                        self.pos += 1;
+                    }
+                }
+                _ => break,
+            }
+        }
+        let start = self.pos;
+        while self
+            .data
+            .get(self.pos)
+            .is_some_and(|b| !b.is_ascii_whitespace())
+        {
+            self.pos += 1;
+        }
+        Some(&self.data[start..self.pos])
+    }
+
+    fn number(&mut self) -> Result<u32, BitmapError> {
+        self.token()
+            .and_then(parse_decimal)
+            .ok_or(BitmapError::InvalidHeader)
+    }
+}
+
+/// Position of the first occurrence of `pattern` in `data`
+fn find(data: &[u8], pattern: &[u8]) -> Option<usize> {
+    data.windows(pattern.len())
+        .position(|window| window == pattern)
+}
+
+/// Value of the define whose name ends with `suffix`, e.g. `#define logo_width 32`
+fn define(header: &[u8], suffix: &[u8]) -> Result<u32, BitmapError> {
+    let start = find(header, suffix).ok_or(BitmapError::InvalidHeader)? + suffix.len();
+    header[start..]
+        .split(u8::is_ascii_whitespace)
+        .find(|token| !token.is_empty())
+        .and_then(parse_decimal)
+        .ok_or(BitmapError::InvalidHeader)
+}
+
+fn parse_decimal(token: &[u8]) -> Option<u32> {
+    core::str::from_utf8(token).ok()?.parse().ok()
+}
+
+/// Bytes of the comma separated hex values of an XBM array, e.g. `0x1f, 0x00`
+fn xbm_bytes(data: &[u8]) -> impl Iterator<Item = u8> + '_ {
+    data.split(|&b| b == b',').filter_map(|token| {
+        let token = core::str::from_utf8(token).ok()?.trim();
+        let digits = token
+            .strip_prefix("0x")
+            .or_else(|| token.strip_prefix("0X"))?;
+        u8::from_str_radix(digits, 16).ok()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use embedded_graphics::mock_display::MockDisplay;
+
+    use super::*;
+
+    /// Pixels of a bitmap drawn into a mock display
+    fn draw(bitmap: &Bitmap) -> MockDisplay<BinaryColor> {
+        let mut display = MockDisplay::new();
+        bitmap.draw(&mut display).unwrap();
+        display
+    }
+
+    #[test]
+    fn pbm_plain_with_comment() {
+        let bitmap = Bitmap::from_pbm(b"P1\n# logo\n3 2\n1 0 1\n0 1 0\n").unwrap();
+        draw(&bitmap).assert_pattern(&["#.#", ".#."]);
+    }
+
+    #[test]
+    fn pbm_raw() {
+        let bitmap = Bitmap::from_pbm(b"P4\n10 2\n\xc0\x40\x00\x80").unwrap();
+        draw(&bitmap).assert_pattern(&["##.......#", "........#."]);
+    }
+
+    #[test]
+    fn xbm_rows_are_padded() {
+        let data = b"#define t_width 10\n#define t_height 2\n\
+            static unsigned char t_bits[] = {\n   0x03, 0x02, 0x00, 0x01 };\n";
+        let bitmap = Bitmap::from_xbm(data).unwrap();
+        draw(&bitmap).assert_pattern(&["##.......#", "........#."]);
+    }
+
+    #[test]
+    fn invalid_data_is_rejected() {
+        let error = |result: Result<Bitmap, BitmapError>| result.err();
+        assert_eq!(
+            error(Bitmap::from_pbm(b"P5\n3 2\n")),
+            Some(BitmapError::UnknownFormat)
+        );
+        assert_eq!(
+            error(Bitmap::from_pbm(b"P1\n3\n")),
+            Some(BitmapError::InvalidHeader)
+        );
+        assert_eq!(
+            error(Bitmap::from_pbm(b"P1\n0 2\n")),
+            Some(BitmapError::InvalidHeader)
+        );
+        assert_eq!(
+            error(Bitmap::from_pbm(b"P1\n3 2\n1 0 1\n0 1\n")),
+            Some(BitmapError::MissingData)
+        );
+        assert_eq!(
+            error(Bitmap::from_pbm(b"P4\n10 2\n\xc0\x40\x00")),
+            Some(BitmapError::MissingData)
+        );
+        assert_eq!(
+            error(Bitmap::from_xbm(b"#define t_width 8\n{ 0x01 }")),
+            Some(BitmapError::InvalidHeader)
+        );
+        assert_eq!(
+            error(Bitmap::from_xbm(
+                b"#define t_width 8\n#define t_height 2\n{ 0x01 }"
+            )),
+            Some(BitmapError::MissingData)
+        );
+    }
+
+    #[test]
+    fn sub_image_is_clipped() {
+        let bitmap = Bitmap::from_pbm(b"P1\n3 2\n1 0 1\n0 1 0\n").unwrap();
+        let mut display = MockDisplay::new();
+        // the area extends beyond the image
+        let area = Rectangle::new(Point::new(1, 1), Size::new(5, 5));
+        bitmap.draw_sub_image(&mut display, &area).unwrap();
+        display.assert_pattern(&["#."]);
+    }
+}
@@ -0,0 +1,170 @@
+//! Month calendar widget
+
+use embedded_graphics::{
+    draw_target::DrawTarget,
+    geometry::{Point, Size},
+    primitives::{Circle, Primitive, PrimitiveStyle, Rectangle},
+    text::{
+        renderer::{CharacterStyle, TextRenderer},
+        Alignment, Baseline, Text, TextStyleBuilder,
+    },
+    Drawable,
+};
+
+use crate::TriColor;
+
+/// Default names of the weekdays, starting with Monday
+pub const WEEKDAYS_EN: [&str; 7] = ["Mo", "Tu", "We", "Th", "Fr", "Sa", "Su"];
+
+/// Month grid with a header row of weekday names and one row per week,
+/// filling `bounds` (e.g. the bounding box of the display). The current
+/// day is highlighted in red, days with events get a marker below the
+/// number. Weeks start on Monday.
+pub struct Calendar<'a, S> {
+    year: i32,
+    month: u8,
+    bounds: Rectangle,
+    style: S,
+    today: Option<u8>,
+    events: u32,
+    weekdays: &'a [&'a str; 7],
+}
+
+impl<'a, S> Calendar<'a, S>
+where
+    S: TextRenderer<Color = TriColor> + CharacterStyle<Color = TriColor> + Clone,
+{
+    /// Calendar of `month` (1 to 12) in `year`, the text is drawn with `style`
+    ///
+    /// # Panics
+    ///
+    /// Panics if `month` is not in the range 1 to 12.
+    pub fn new(year: i32, month: u8, bounds: Rectangle, style: S) -> Self {
+        assert!((1..=12).contains(&month), "invalid month {month}");
+        Self {
+            year,
+            month,
+            bounds,
+            style,
+            today: None,
+            events: 0,
+            weekdays: &WEEKDAYS_EN,
+        }
+    }
+
+    /// Highlight `day` of the month as the current day
+    #[must_use]
+    pub fn with_today(mut self, day: Option<u8>) -> Self {
+        self.today = day;
+        self
+    }
+
+    /// Mark the days with events, bit 0 is the first day of the month
+    #[must_use]
+    pub fn with_events(mut self, events: u32) -> Self {
+        self.events = events;
+        self
+    }
+
+    /// Names of the weekdays in the header row, starting with Monday
+    #[must_use]
+    pub fn with_weekdays(mut self, weekdays: &'a [&'a str; 7]) -> Self {
+        self.weekdays = weekdays;
+        self
+    }
+
+    /// Bounds of the cell in `column` (weekday) and `row` (0 is the header)
+    #[allow(clippy::cast_possible_wrap)]
+    fn cell(&self, column: u32, row: u32) -> Rectangle {
+        let size = Size::new(self.bounds.size.width / 7, self.bounds.size.height / 7);
+        let offset = Point::new((column * size.width) as i32, (row * size.height) as i32);
+        Rectangle::new(self.bounds.top_left + offset, size)
+    }
+}
+
+impl<S> Drawable for Calendar<'_, S>
+where
+    S: TextRenderer<Color = TriColor> + CharacterStyle<Color = TriColor> + Clone,
+{
+    type Color = TriColor;
+    type Output = ();
+
+    #[allow(clippy::cast_possible_wrap)]
+    fn draw<D>(&self, target: &mut D) -> Result<Self::Output, D::Error>
+    where
+        D: DrawTarget<Color = Self::Color>,
+    {
+        let text_style = TextStyleBuilder::new()
+            .alignment(Alignment::Center)
+            .baseline(Baseline::Middle)
+            .build();
+        for (column, name) in (0..).zip(self.weekdays) {
+            let center = self.cell(column, 0).center();
+            Text::with_text_style(name, center, self.style.clone(), text_style).draw(target)?;
+        }
+
+        let offset = u32::from(weekday(self.year, self.month, 1));
+        for day in 1..=days_in_month(self.year, self.month) {
+            let index = offset + u32::from(day) - 1;
+            let cell = self.cell(index % 7, index / 7 + 1);
+            let mut style = self.style.clone();
+            let mut marker_color = TriColor::Black;
+            if self.today == Some(day) {
+                cell.offset(-1)
+                    .into_styled(PrimitiveStyle::with_fill(TriColor::Red))
+                    .draw(target)?;
+                style.set_text_color(Some(TriColor::White));
+                marker_color = TriColor::White;
+            }
+            let mut buffer = [0; 2];
+            Text::with_text_style(day_str(day, &mut buffer), cell.center(), style, text_style)
+                .draw(target)?;
+            if self.events & (1 << (day - 1)) != 0 {
+                let diameter = (cell.size.height / 8).max(2);
+                let bottom = cell.center().y + (cell.size.height / 2) as i32;
+                let center = Point::new(cell.center().x, bottom - diameter as i32);
+                Circle::with_center(center, diameter)
+                    .into_styled(PrimitiveStyle::with_fill(marker_color))
+                    .draw(target)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Number of days in `month` (1 to 12)
+#[must_use]
+pub fn days_in_month(year: i32, month: u8) -> u8 {
+    match month {
+        2 if year % 4 == 0 && (year % 100 != 0 || year % 400 == 0) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+/// Weekday of a date, 0 is Monday (Sakamoto's method)
+#[must_use]
+#[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+pub fn weekday(year: i32, month: u8, day: u8) -> u8 {
+    const OFFSETS: [i32; 12] = [0, 3, 2, 5, 0, 3, 5, 1, 4, 6, 2, 4];
+    let year = if month < 3 { year - 1 } else { year };
+    let sunday_based = (year + year.div_euclid(4) - year.div_euclid(100)
+        + year.div_euclid(400)
+        + OFFSETS[usize::from(month - 1)]
+        + i32::from(day))
+    .rem_euclid(7);
+    ((sunday_based + 6) % 7) as u8
+}
+
+/// Decimal representation of a day of the month
+fn day_str(day: u8, buffer: &mut [u8; 2]) -> &str {
+    let len = if day < 10 {
+        buffer[0] = b'0' + day;
+        1
+    } else {
+        *buffer = [b'0' + day / 10, b'0' + day % 10];
+        2
+    };
+    core::str::from_utf8(&buffer[..len]).unwrap_or_default()
+}
@@ -0,0 +1,66 @@
+//! Preview of a display in an HTML canvas, for web based tools built for wasm32
+
+use std::vec::Vec;
+
+use embedded_graphics::{
+    geometry::{OriginDimensions, Point},
+    pixelcolor::{Rgb888, RgbColor},
+};
+use wasm_bindgen::{Clamped, JsCast, JsValue};
+use web_sys::{CanvasRenderingContext2d, HtmlCanvasElement, ImageData};
+
+use crate::{BufferStorage, Display, TriColor};
+
+/// Pixels of `display` in rotated coordinates as RGBA bytes, row by row,
+/// with the colors of `Rgb888::from(TriColor)`
+#[must_use]
+#[allow(clippy::cast_possible_wrap)]
+pub fn to_rgba<const SIZE_V: u32, const SIZE_H: u32, const IMAGE_SIZE: usize, B: BufferStorage>(
+    display: &Display<SIZE_V, SIZE_H, IMAGE_SIZE, B>,
+) -> Vec<u8> {
+    let size = display.size();
+    let mut rgba = Vec::with_capacity(size.width as usize * size.height as usize * 4);
+    for y in 0..size.height as i32 {
+        for x in 0..size.width as i32 {
+            let color = display
+                .get_pixel(Point::new(x, y))
+                .unwrap_or(TriColor::White);
+            let color = Rgb888::from(color);
+            rgba.extend_from_slice(&[color.r(), color.g(), color.b(), u8::MAX]);
+        }
+    }
+    rgba
+}
+
+/// Draw `display` into `canvas`, one canvas pixel per pixel of the
+/// display. The canvas is resized to the (rotated) size of the display,
+/// scale it with CSS (`image-rendering: pixelated`) for a larger preview.
+/// Since the display is drawn with the same code as on the firmware, the
+/// preview shows exactly what the e-paper will show.
+///
+/// # Errors
+///
+/// This function will return an error if the 2D context of the canvas
+/// is not available.
+pub fn draw_to_canvas<
+    const SIZE_V: u32,
+    const SIZE_H: u32,
+    const IMAGE_SIZE: usize,
+    B: BufferStorage,
+>(
+    display: &Display<SIZE_V, SIZE_H, IMAGE_SIZE, B>,
+    canvas: &HtmlCanvasElement,
+) -> Result<(), JsValue> {
+    let size = display.size();
+    canvas.set_width(size.width);
+    canvas.set_height(size.height);
+    let context = canvas
+        .get_context("2d")?
+        .ok_or_else(|| JsValue::from_str("canvas has no 2d context"))?
+        .dyn_into::<CanvasRenderingContext2d>()
+        .map_err(JsValue::from)?;
+    let rgba = to_rgba(display);
+    let image =
+        ImageData::new_with_u8_clamped_array_and_sh(Clamped(&rgba), size.width, size.height)?;
+    context.put_image_data(&image, 0.0, 0.0)
+}
@@ -0,0 +1,313 @@
+//! Low-level access to the COG: command set and pin handling.
+//! [`Epd`](crate::Epd) is built on top of this layer, use it directly only
+//! for commands and sequences not covered by the driver.
+
+use embedded_hal::{delay::DelayNs, digital::InputPin, digital::OutputPin, spi::SpiDevice};
+
+use crate::driver::{EpdError, Error};
+
+/// Commands of the COG. Not all commands are supported by every panel,
+/// check the datasheet and application notes of your panel.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[repr(u8)]
+pub enum Command {
+    /// Panel setting, also used for the soft reset (PSR)
+    Psr = 0x00,
+    /// Drive voltages of the charge pumps (PWR)
+    PowerSetting = 0x01,
+    /// Turn off the charge pumps (POF)
+    PowerOff = 0x02,
+    /// Timing of the power off sequence (PFS)
+    PowerOffSequence = 0x03,
+    /// Turn on the charge pumps (PON)
+    PowerOn = 0x04,
+    /// Turn on the charge pumps and measure the temperature (PMES)
+    PowerOnMeasure = 0x05,
+    /// Booster soft-start settings of the phases A, B and C (BTST)
+    BoosterSoftStart = 0x06,
+    /// Enter deep sleep, only a reset wakes the COG up again (DSLP)
+    DeepSleep = 0x07,
+    /// Start transmission of the black plane (DTM1)
+    BufferBlack = 0x10,
+    /// Stop the data transmission (DSP)
+    DataStop = 0x11,
+    /// Refresh the panel with the uploaded planes (DRF)
+    Refresh = 0x12,
+    /// Start transmission of the red plane (DTM2)
+    BufferRed = 0x13,
+    /// Frame rate control (PLL)
+    Pll = 0x30,
+    /// Calibrate the temperature sensor (TSC)
+    TemperatureCalibration = 0x40,
+    /// Select the internal or an external temperature sensor (TSE)
+    TemperatureEnable = 0x41,
+    /// Write to an external temperature sensor (TSW)
+    TemperatureWrite = 0x42,
+    /// Read from an external temperature sensor (TSR)
+    TemperatureRead = 0x43,
+    /// Check the panel for breakage (PBC)
+    PanelBreakCheck = 0x44,
+    /// VCOM and data interval setting (CDI)
+    Cdi = 0x50,
+    /// Low power detection (LPD)
+    LowPowerDetection = 0x51,
+    /// Gate/source non-overlap period (TCON)
+    Tcon = 0x60,
+    /// Resolution of the panel (TRES)
+    Resolution = 0x61,
+    /// Revision of the COG (REV)
+    Revision = 0x70,
+    /// Status flags of the COG (FLG)
+    GetStatus = 0x71,
+    /// Automatic measurement of the VCOM level (AMV)
+    AutoMeasureVcom = 0x80,
+    /// Read the measured VCOM level (VV)
+    VcomValue = 0x81,
+    /// VCOM DC level (VDCS)
+    VcomDc = 0x82,
+    /// Set the partial window (PTL)
+    PartialWindow = 0x90,
+    /// Enter the partial mode (PTIN)
+    PartialIn = 0x91,
+    /// Leave the partial mode (PTOUT)
+    PartialOut = 0x92,
+    /// Active temperature, settings of the cascade (CCSET)
+    ActiveTemperature = 0xe0,
+    /// Power saving (PWS)
+    PowerSaving = 0xe3,
+    /// Input temperature used for the waveform (TSSET)
+    InputTemperature = 0xe5,
+}
+
+/// Transfer of a command during which an error occurred, see
+/// `Error::Spi` and `Error::GpioDc`
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Phase {
+    /// sending the command byte, including switching the DC pin
+    Command(Command),
+    /// sending or reading the data of the command
+    Data(Command),
+}
+
+impl core::fmt::Display for Phase {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Phase::Command(cmd) => write!(f, "command byte of {cmd:?} (0x{:02x})", *cmd as u8),
+            Phase::Data(cmd) => write!(f, "data of {cmd:?} (0x{:02x})", *cmd as u8),
+        }
+    }
+}
+
+/// Placeholder for the busy pin if the busy line is not connected to the
+/// MCU, see `Epd::set_busy_source`. It never reports the COG as busy.
+pub struct NoBusyPin;
+
+impl embedded_hal::digital::ErrorType for NoBusyPin {
+    type Error = core::convert::Infallible;
+}
+
+impl InputPin for NoBusyPin {
+    fn is_high(&mut self) -> Result<bool, Self::Error> {
+        Ok(true)
+    }
+
+    fn is_low(&mut self) -> Result<bool, Self::Error> {
+        Ok(false)
+    }
+}
+
+/// Pins of the COG together with the basic transfers: commands, data,
+/// reset and busy signal. There is no state handling, the caller is
+/// responsible for sending valid sequences.
+pub struct EpdInterface<BUSY, DC, RST> {
+    /// busy pin, active low
+    pub(crate) busy: BUSY,
+    /// Data/Command control pin (data: high, command: low)
+    pub(crate) dc: DC,
+    /// reset pin, active low
+    pub(crate) rst: RST,
+    /// chunk size used for SPI writes (0: no chunks)
+    pub(crate) spi_chunk_size: usize,
+    /// last command sent, the context of errors while sending its data
+    command: Option<Command>,
+}
+
+impl<BUSY, DC, RST> EpdInterface<BUSY, DC, RST>
+where
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+{
+    /// Create the interface. `spi_chunk_size` determines the data chunk
+    /// size for SPI writes, 0 means no chunks (see [`Epd::new`](crate::Epd::new)).
+    pub fn new(busy: BUSY, dc: DC, rst: RST, spi_chunk_size: usize) -> Self {
+        Self {
+            busy,
+            dc,
+            rst,
+            spi_chunk_size,
+            command: None,
+        }
+    }
+
+    /// Give back the pins (busy, dc, rst)
+    pub fn release(self) -> (BUSY, DC, RST) {
+        (self.busy, self.dc, self.rst)
+    }
+
+    /// Reset the COG with the reset pin
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if there is an error with the reset pin.
+    pub fn reset(&mut self, delay: &mut impl DelayNs) -> Result<(), RST::Error> {
+        delay.delay_ms(1);
+        self.rst.set_high()?;
+        delay.delay_ms(5);
+        self.rst.set_low()?;
+        delay.delay_ms(10);
+        self.rst.set_high()?;
+        delay.delay_ms(5);
+        Ok(())
+    }
+
+    /// The COG is busy (busy pin low)
+    ///
+    /// # Panics
+    ///
+    /// Panics if the busy pin cannot be read.
+    pub fn is_busy(&mut self) -> bool {
+        self.busy.is_low().unwrap()
+    }
+
+    /// Send a command followed by its data
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if there is an error
+    /// with the GPIOs or the SPI device.
+    pub fn send_data<SPI: SpiDevice>(
+        &mut self,
+        spi: &mut SPI,
+        cmd: Command,
+        data: &[u8],
+    ) -> Result<(), EpdError<SPI, DC, RST>> {
+        self.send_command(spi, cmd)?;
+        self.write(spi, data)?;
+        Ok(())
+    }
+
+    /// Send a command byte and read the data returned by the COG
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if there is an error
+    /// with the GPIOs or the SPI device.
+    pub fn read_data<SPI: SpiDevice>(
+        &mut self,
+        spi: &mut SPI,
+        cmd: Command,
+        data: &mut [u8],
+    ) -> Result<(), EpdError<SPI, DC, RST>> {
+        self.send_command(spi, cmd)?;
+        self.read(spi, data)
+    }
+
+    /// Read the data returned by the COG for the last command without
+    /// sending it again, e.g. the result of a measurement after waiting
+    /// for the busy signal
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if there is an error with the SPI device.
+    pub fn read<SPI: SpiDevice>(
+        &mut self,
+        spi: &mut SPI,
+        data: &mut [u8],
+    ) -> Result<(), EpdError<SPI, DC, RST>> {
+        let phase = self.command.map(Phase::Data);
+        spi.read(data)
+            .map_err(|error| Error::Spi { error, phase })?;
+        Ok(())
+    }
+
+    /// Send a command byte, DC stays high afterwards for sending data
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if there is an error
+    /// with the GPIOs or the SPI device.
+    pub fn send_command<SPI: SpiDevice>(
+        &mut self,
+        spi: &mut SPI,
+        cmd: Command,
+    ) -> Result<(), EpdError<SPI, DC, RST>> {
+        let phase = Some(Phase::Command(cmd));
+        self.command = Some(cmd);
+        self.dc
+            .set_low()
+            .map_err(|error| Error::GpioDc { error, phase })?;
+        spi.write(&[cmd as u8])
+            .map_err(|error| Error::Spi { error, phase })?;
+        self.dc
+            .set_high()
+            .map_err(|error| Error::GpioDc { error, phase })?;
+        Ok(())
+    }
+
+    /// Write data bytes, in chunks if a chunk size is set
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if there is an error with the SPI device.
+    pub fn write<SPI: SpiDevice>(
+        &mut self,
+        spi: &mut SPI,
+        data: &[u8],
+    ) -> Result<(), EpdError<SPI, DC, RST>> {
+        let phase = self.command.map(Phase::Data);
+        let error = |error| Error::Spi { error, phase };
+        if self.spi_chunk_size > 0 {
+            for chunk in data.chunks(self.spi_chunk_size) {
+                spi.write(chunk).map_err(error)?;
+            }
+        } else {
+            spi.write(data).map_err(error)?;
+        }
+        Ok(())
+    }
+
+    /// Write `len` zero bytes without a buffer of that size
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if there is an error with the SPI device.
+    pub fn write_zeros<SPI: SpiDevice>(
+        &mut self,
+        spi: &mut SPI,
+        len: usize,
+    ) -> Result<(), EpdError<SPI, DC, RST>> {
+        self.write_repeated(spi, 0, len)
+    }
+
+    /// Write `len` times `byte` without a buffer of that size
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if there is an error with the SPI device.
+    pub fn write_repeated<SPI: SpiDevice>(
+        &mut self,
+        spi: &mut SPI,
+        byte: u8,
+        len: usize,
+    ) -> Result<(), EpdError<SPI, DC, RST>> {
+        let bytes = [byte; 64];
+        let mut remaining = len;
+        while remaining > 0 {
+            let count = remaining.min(bytes.len());
+            self.write(spi, &bytes[..count])?;
+            remaining -= count;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,32 @@
+//! Skipping drawables which lie completely outside of the draw target
+
+use embedded_graphics::{draw_target::DrawTarget, geometry::Dimensions, Drawable};
+
+/// Drawing with a bounding box check. Drawables which do not overlap the
+/// draw target, e.g. widgets scrolled or translated off-screen, are skipped
+/// without iterating their pixels. Implemented for all drawables with
+/// dimensions.
+pub trait DrawVisible: Drawable {
+    /// Draw `self` if its bounding box overlaps `target`. Returns `None`
+    /// if drawing was skipped.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if drawing to `target` fails.
+    fn draw_visible<D>(&self, target: &mut D) -> Result<Option<Self::Output>, D::Error>
+    where
+        D: DrawTarget<Color = Self::Color>;
+}
+
+impl<T: Drawable + Dimensions> DrawVisible for T {
+    fn draw_visible<D>(&self, target: &mut D) -> Result<Option<Self::Output>, D::Error>
+    where
+        D: DrawTarget<Color = Self::Color>,
+    {
+        let visible = self.bounding_box().intersection(&target.bounding_box());
+        if visible.is_zero_sized() {
+            return Ok(None);
+        }
+        self.draw(target).map(Some)
+    }
+}
@@ -0,0 +1,125 @@
+//! Double buffered display for detecting changes between frames
+
+use embedded_graphics::{
+    draw_target::DrawTarget,
+    geometry::{OriginDimensions, Point, Size},
+    primitives::Rectangle,
+    Pixel,
+};
+
+use crate::{Display, DisplayBuffer, TriColor};
+
+/// Display buffer holding the current and the previous frame.
+/// Drawing always goes to the current frame.
+#[derive(Clone, Default)]
+pub struct DoubleDisplay<const SIZE_V: u32, const SIZE_H: u32, const IMAGE_SIZE: usize> {
+    current: Display<SIZE_V, SIZE_H, IMAGE_SIZE>,
+    previous: Display<SIZE_V, SIZE_H, IMAGE_SIZE>,
+}
+
+impl<const SIZE_V: u32, const SIZE_H: u32, const IMAGE_SIZE: usize>
+    DoubleDisplay<SIZE_V, SIZE_H, IMAGE_SIZE>
+{
+    #[must_use]
+    pub fn current(&self) -> &Display<SIZE_V, SIZE_H, IMAGE_SIZE> {
+        &self.current
+    }
+
+    pub fn current_mut(&mut self) -> &mut Display<SIZE_V, SIZE_H, IMAGE_SIZE> {
+        &mut self.current
+    }
+
+    /// Frame which was current at the last call to `mark_shown`,
+    /// i.e. usually the frame shown on the e-paper
+    #[must_use]
+    pub fn previous(&self) -> &Display<SIZE_V, SIZE_H, IMAGE_SIZE> {
+        &self.previous
+    }
+
+    /// Copy the current frame into the previous one, typically after it was
+    /// sent to the e-paper. The frames are not exchanged: the current frame
+    /// keeps its content, so it can be modified incrementally for the next
+    /// update.
+    pub fn mark_shown(&mut self) {
+        self.previous.clone_from(&self.current);
+    }
+
+    /// Bounding box of all pixels that differ between the current and the
+    /// previous frame, in panel coordinates (i.e. without rotation).
+    /// The rectangle is empty if both frames are equal.
+    #[allow(clippy::cast_possible_truncation, clippy::cast_possible_wrap)]
+    #[must_use]
+    pub fn diff(&self) -> Rectangle {
+        let mut min = Point::new(i32::MAX, i32::MAX);
+        let mut max = Point::new(i32::MIN, i32::MIN);
+        let rows = self
+            .current
+            .black_rows()
+            .zip(self.current.red_rows())
+            .zip(self.previous.black_rows().zip(self.previous.red_rows()));
+        for (y, ((black, red), (prev_black, prev_red))) in rows.enumerate() {
+            let bytes = black.iter().zip(red).zip(prev_black.iter().zip(prev_red));
+            for (i, ((b, r), (pb, pr))) in bytes.enumerate() {
+                let changed = (b ^ pb) | (r ^ pr);
+                if changed == 0 {
+                    continue;
+                }
+                let x = i as i32 * 8;
+                let y = y as i32;
+                min.x = min.x.min(x + changed.leading_zeros() as i32);
+                max.x = max.x.max(x + 7 - changed.trailing_zeros() as i32);
+                min.y = min.y.min(y);
+                max.y = max.y.max(y);
+            }
+        }
+        if min.x > max.x {
+            Rectangle::zero()
+        } else {
+            Rectangle::with_corners(min, max)
+        }
+    }
+}
+
+impl<const SIZE_V: u32, const SIZE_H: u32, const IMAGE_SIZE: usize> DisplayBuffer
+    for DoubleDisplay<SIZE_V, SIZE_H, IMAGE_SIZE>
+{
+    fn get_buffer_black(&self) -> &[u8] {
+        self.current.get_buffer_black()
+    }
+    fn get_buffer_red(&self) -> &[u8] {
+        self.current.get_buffer_red()
+    }
+}
+
+impl<const SIZE_V: u32, const SIZE_H: u32, const IMAGE_SIZE: usize> OriginDimensions
+    for DoubleDisplay<SIZE_V, SIZE_H, IMAGE_SIZE>
+{
+    fn size(&self) -> Size {
+        self.current.size()
+    }
+}
+
+impl<const SIZE_V: u32, const SIZE_H: u32, const IMAGE_SIZE: usize> DrawTarget
+    for DoubleDisplay<SIZE_V, SIZE_H, IMAGE_SIZE>
+{
+    type Color = TriColor;
+    type Error = core::convert::Infallible;
+
+    fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Pixel<Self::Color>>,
+    {
+        self.current.draw_iter(pixels)
+    }
+
+    fn fill_contiguous<I>(&mut self, area: &Rectangle, colors: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Self::Color>,
+    {
+        self.current.fill_contiguous(area, colors)
+    }
+
+    fn fill_solid(&mut self, area: &Rectangle, color: Self::Color) -> Result<(), Self::Error> {
+        self.current.fill_solid(area, color)
+    }
+}
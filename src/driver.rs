@@ -3,73 +3,387 @@
 use core::marker::PhantomData;
 use embedded_hal::{delay::DelayNs, digital::InputPin, digital::OutputPin, spi::SpiDevice};
 
-use crate::DisplayBuffer;
-
-enum Command {
-    Psr = 0x00,
-    PowerOff = 0x02,
-    PowerOn = 0x04,
-    BufferBlack = 0x10,
-    Refresh = 0x12,
-    BufferRed = 0x13,
-    ActiveTemperature = 0xe0,
-    InputTemperature = 0xe5,
-}
+#[cfg(feature = "graphics")]
+use embedded_graphics::primitives::Rectangle;
+
+#[cfg(feature = "graphics")]
+use crate::DoubleDisplay;
+use crate::{
+    temperature_setting, Command, DisplayBuffer, DisplaySpec, EpdEvents, EpdInterface, FrameSource,
+    IterSource, PanelModel, PanelVariant, Phase, Plane, RateLimit, RateLimitPolicy,
+    TemperatureSetting, SPECTRA_TEMPERATURE_TABLE, WIDE_TEMPERATURE_TABLE,
+};
 
-/// Config register data for sizes other than 4.2"
-const REG_DATA_SOFT_RESET: &[u8] = &[0x0e];
-const REG_DATA_INPUT_TEMP: &[u8] = &[0x19];
-const REG_DATA_ACTIVE_TEMP: &[u8] = &[0x02];
-const REG_DATA_PSR: &[u8] = &[0xcf, 0x8d];
+/// Config register data used until a panel is selected with `set_panel`,
+/// the same for all supported sizes (see [`DisplaySpec`])
+const DEFAULT_PANEL: PanelSettings = PanelSettings {
+    soft_reset: 0x0e,
+    input_temperature: 0x19,
+    active_temperature: 0x02,
+    psr: [0xcf, 0x8d],
+    temperature_table: SPECTRA_TEMPERATURE_TABLE,
+    wide_temperature_psr: [0xcf, 0x8d],
+    wide_temperature_table: WIDE_TEMPERATURE_TABLE,
+    tcon: None,
+};
 
 /// Timeout value when waiting for busy signal
 const TIMEOUT_MS: i32 = 60_000;
+/// Timeout value of wide temperature panels below 0 °C, the refresh takes
+/// several times longer
+const TIMEOUT_COLD_MS: i32 = 180_000;
+
+/// Size of the stack buffer used to stream a `FrameSource`, smaller on
+/// MCUs with a 16-bit address space which only have a few KB of RAM
+#[cfg(not(target_pointer_width = "16"))]
+const SOURCE_CHUNK_LEN: usize = 256;
+#[cfg(target_pointer_width = "16")]
+const SOURCE_CHUNK_LEN: usize = 32;
 
 // Sadly we cannot use #[from] more than once.
 // See here for similiar problem: https://stackoverflow.com/questions/37347311/how-is-there-a-conflicting-implementation-of-from-when-using-a-generic-type
 #[cfg(feature = "std")]
 #[derive(thiserror::Error, Debug)]
 pub enum Error<SpiError, DcError, RstError> {
-    #[error("SPI error: {0}")]
-    Spi(#[source] SpiError),
-    #[error("Error with GPIO 'DC': {0}")]
-    GpioDc(#[source] DcError),
+    #[error("SPI error{}: {error}", during(*.phase))]
+    Spi {
+        #[source]
+        error: SpiError,
+        phase: Option<Phase>,
+    },
+    #[error("Error with GPIO 'DC'{}: {error}", during(*.phase))]
+    GpioDc {
+        #[source]
+        error: DcError,
+        phase: Option<Phase>,
+    },
     #[error("Error with GPIO 'RESET': {0}")]
     GpioRst(#[source] RstError),
     #[error("Timeout while waiting for busy signal")]
     Timeout,
+    #[error("Aborted while waiting for busy signal")]
+    Aborted,
+    #[error("Refresh vetoed by the refresh guard")]
+    RefreshVetoed,
+    #[error("Refresh requested before the minimum interval elapsed")]
+    TooSoon,
+    #[error("Buffer has {actual} bytes, expected {expected} bytes")]
+    SizeMismatch { expected: usize, actual: usize },
+    #[error("Detected panel {detected:?} instead of panel {expected}")]
+    PanelMismatch { expected: u8, detected: Option<u8> },
+    #[error("I/O error: {0}")]
+    Io(#[source] std::io::Error),
 }
 
 #[cfg(not(feature = "std"))]
 #[derive(Debug)]
 pub enum Error<SpiError, DcError, RstError> {
-    Spi(SpiError),
-    GpioDc(DcError),
+    /// `phase` is the transfer which failed, `None` outside of commands
+    Spi {
+        error: SpiError,
+        phase: Option<Phase>,
+    },
+    GpioDc {
+        error: DcError,
+        phase: Option<Phase>,
+    },
     GpioRst(RstError),
     Timeout,
+    Aborted,
+    RefreshVetoed,
+    TooSoon,
+    SizeMismatch {
+        expected: usize,
+        actual: usize,
+    },
+    PanelMismatch {
+        expected: u8,
+        detected: Option<u8>,
+    },
 }
 
-type EpdError<SPI, DC, RST> = Error<
+/// Context of an error for the message, e.g. " while sending the data of Refresh"
+#[cfg(feature = "std")]
+fn during(phase: Option<Phase>) -> std::string::String {
+    phase
+        .map(|phase| std::format!(" while sending the {phase}"))
+        .unwrap_or_default()
+}
+
+pub(crate) type EpdError<SPI, DC, RST> = Error<
     <SPI as embedded_hal::spi::ErrorType>::Error,
     <DC as embedded_hal::digital::ErrorType>::Error,
     <RST as embedded_hal::digital::ErrorType>::Error,
 >;
 
-type EpdResult<STATE, SPI, BUSY, DC, RST, DELAY> =
-    Result<Epd<STATE, SPI, BUSY, DC, RST, DELAY>, EpdError<SPI, DC, RST>>;
+type EpdResult<STATE, SPI, BUSY, DC, RST> =
+    Result<Epd<STATE, BUSY, DC, RST>, EpdError<SPI, DC, RST>>;
+
+type RefreshResult<'a, SPI, BUSY, DC, RST> =
+    Result<RefreshHandle<'a, SPI, BUSY, DC, RST>, EpdError<SPI, DC, RST>>;
+
+/// Error returned by `init`. It gives back the driver in the inactive
+/// state, so initialisation can be retried without losing the pins.
+#[cfg_attr(feature = "std", derive(thiserror::Error))]
+#[cfg_attr(feature = "std", error("Initialisation failed: {error}"))]
+pub struct InitError<EPD, E> {
+    /// driver in the inactive state
+    pub epd: EPD,
+    /// error that occurred during initialisation
+    #[cfg_attr(feature = "std", source)]
+    pub error: E,
+}
+
+impl<EPD, E: core::fmt::Debug> core::fmt::Debug for InitError<EPD, E> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("InitError")
+            .field("error", &self.error)
+            .finish_non_exhaustive()
+    }
+}
+
+type InitResult<SPI, BUSY, DC, RST> = Result<
+    Epd<Active, BUSY, DC, RST>,
+    InitError<Epd<Inactive, BUSY, DC, RST>, EpdError<SPI, DC, RST>>,
+>;
+
+/// Decoded status flags of the COG, see `Epd::check_panel`
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct PanelStatus {
+    /// result of the panel break check, `false` for a cracked or disconnected panel
+    pub panel_ok: bool,
+    /// the charge pumps are powered on
+    pub powered_on: bool,
+    /// the COG is not busy
+    pub ready: bool,
+    /// raw value of the status register
+    pub raw: u8,
+}
+
+impl PanelStatus {
+    fn from_registers(status: u8, panel_break: u8) -> Self {
+        Self {
+            panel_ok: panel_break & 0x01 != 0,
+            powered_on: status & 0x04 != 0,
+            ready: status & 0x01 != 0,
+            raw: status,
+        }
+    }
+}
+
+/// Panel identified by `Epd::detect_panel`
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct DetectedPanel {
+    /// supported panel with the reported resolution
+    pub model: &'static PanelModel,
+    /// revision of the COG, distinguishing its generations
+    pub revision: u8,
+}
+
+/// Result of `Epd::self_test`
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct SelfTestReport {
+    /// panel status read before the refreshes
+    pub status: PanelStatus,
+    /// duration of the all-white refresh in ms
+    pub white_ms: u32,
+    /// duration of the all-black refresh in ms
+    pub black_ms: u32,
+    /// duration of the checker pattern refresh in ms
+    pub checker_ms: u32,
+}
+
+impl SelfTestReport {
+    /// The panel break check passed and all refreshes took some time.
+    /// A refresh finishing immediately indicates a problem with the busy
+    /// signal or a COG which did not accept the commands.
+    #[must_use]
+    pub fn passed(&self) -> bool {
+        self.status.panel_ok && self.white_ms > 0 && self.black_ms > 0 && self.checker_ms > 0
+    }
+}
+
+/// Film of the panel, which determines the update flow
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum Film {
+    /// Spectra black, white and red film
+    #[default]
+    Spectra,
+    /// Older Aurora Ma monochrome film. The red plane is not used and the
+    /// charge pumps have to be turned off after each refresh.
+    AuroraMa,
+    /// Aurora Mb monochrome film. The red plane is not used.
+    AuroraMb,
+}
+
+impl Film {
+    /// The film shows the red plane
+    #[must_use]
+    pub fn has_red(self) -> bool {
+        self == Film::Spectra
+    }
+}
+
+/// Signal polled while waiting for the COG, see `Epd::set_busy_source`
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum BusySource {
+    /// The busy pin of the panel
+    #[default]
+    Pin,
+    /// The busy flag of the status register, for designs where the busy
+    /// line is not connected to the MCU. Each poll sends `GetStatus` (FLG,
+    /// 0x71) and reads one byte, also while a refresh is running. The
+    /// UC81xx based COGs of the Spectra panels answer it during the
+    /// refresh, other COGs may ignore SPI while they are busy, check the
+    /// datasheet of your panel. Like `check_panel`, this requires the data
+    /// line of the panel to be readable by the SPI device.
+    Status,
+}
+
+/// Meaning of the bits of a buffer plane for the COG, see `Epd::set_polarity`
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum BitPolarity {
+    /// A set bit is a black (or red) pixel, as in the display buffers
+    #[default]
+    Normal,
+    /// A cleared bit is a black (or red) pixel, the bits are inverted
+    /// during the upload
+    Inverted,
+}
+
+/// Settings of `Epd::stress_test`
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct StressConfig {
+    /// number of refreshes
+    pub cycles: u32,
+    /// pause between the end of a refresh and the start of the next one in ms
+    pub interval_ms: u32,
+}
+
+/// Result of `Epd::stress_test`
+#[derive(Debug)]
+pub struct StressReport<E> {
+    /// number of successful refreshes
+    pub completed: u32,
+    /// number of failed refreshes
+    pub failures: u32,
+    /// shortest and longest duration of a successful refresh in ms
+    pub min_refresh_ms: u32,
+    pub max_refresh_ms: u32,
+    /// first error of the run
+    pub first_error: Option<E>,
+}
+
+impl<E> StressReport<E> {
+    /// All refreshes succeeded
+    #[must_use]
+    pub fn passed(&self) -> bool {
+        self.failures == 0
+    }
+}
+
+/// Register data of a panel, see [`DisplaySpec`]
+#[derive(Clone, Copy)]
+struct PanelSettings {
+    soft_reset: u8,
+    input_temperature: u8,
+    active_temperature: u8,
+    psr: [u8; 2],
+    temperature_table: &'static [TemperatureSetting],
+    wide_temperature_psr: [u8; 2],
+    wide_temperature_table: &'static [TemperatureSetting],
+    tcon: Option<u8>,
+}
+
+impl PanelSettings {
+    fn of<D: DisplaySpec>() -> Self {
+        Self {
+            soft_reset: D::SOFT_RESET,
+            input_temperature: D::INPUT_TEMPERATURE,
+            active_temperature: D::ACTIVE_TEMPERATURE,
+            psr: D::PSR,
+            temperature_table: D::TEMPERATURE_TABLE,
+            wide_temperature_psr: D::WIDE_TEMPERATURE_PSR,
+            wide_temperature_table: D::WIDE_TEMPERATURE_TABLE,
+            tcon: D::TCON,
+        }
+    }
+
+    /// Panel settings and temperature table of `variant`
+    fn of_variant(&self, variant: PanelVariant) -> ([u8; 2], &'static [TemperatureSetting]) {
+        match variant {
+            PanelVariant::Standard => (self.psr, self.temperature_table),
+            PanelVariant::WideTemperature => {
+                (self.wide_temperature_psr, self.wide_temperature_table)
+            }
+        }
+    }
+}
+
+impl Default for PanelSettings {
+    fn default() -> Self {
+        DEFAULT_PANEL
+    }
+}
+
+/// Optional register settings, `None` keeps the power-on default of the COG
+#[derive(Clone, Copy, Default)]
+struct Registers {
+    /// register data of the panel, for registers which are always sent
+    panel: PanelSettings,
+    cdi: Option<u8>,
+    tcon: Option<u8>,
+    pll: Option<u8>,
+    booster_soft_start: Option<[u8; 3]>,
+    power_setting: Option<[u8; 5]>,
+    /// use the internal temperature sensor for the input temperature
+    internal_temperature: bool,
+    /// settings for the measured temperature, `None` for the ones of the
+    /// panel
+    temperature_table: Option<&'static [TemperatureSetting]>,
+    /// temperature variant of the panel
+    variant: PanelVariant,
+    /// panel settings, `None` sends the ones of the panel
+    psr: Option<[u8; 2]>,
+    /// film of the panel
+    film: Film,
+    /// polarity of the black and the red plane
+    polarity: [BitPolarity; 2],
+}
 
 /// Actual driver for e-paper display
-pub struct Epd<STATE: EpdState, SPI, BUSY, DC, RST, DELAY> {
-    /// busy pin, active low
-    busy: BUSY,
-    /// Data/Command control pin (data: high, command: low)
-    dc: DC,
-    /// reset pin, active low
-    rst: RST,
-    /// chunk size used for SPI writes (0: no chunks)
-    spi_chunk_size: usize,
-    spi: PhantomData<SPI>,
-    delay: PhantomData<DELAY>,
+pub struct Epd<STATE: EpdState, BUSY, DC, RST> {
+    /// pins and basic transfers
+    interface: EpdInterface<BUSY, DC, RST>,
+    /// called instead of the delay while waiting for the busy signal
+    sleep_hook: Option<fn()>,
+    /// called periodically while waiting for the busy signal (hook, interval in ms)
+    watchdog_hook: Option<(fn(), u32)>,
+    /// polled while waiting for the busy signal, returning true aborts waiting
+    abort_hook: Option<fn() -> bool>,
+    /// called before each refresh, returning false vetoes the refresh
+    refresh_guard: Option<fn() -> bool>,
+    /// called with the estimated progress of a refresh (hook, typical duration in ms)
+    progress_hook: Option<(fn(u8), u32)>,
+    /// lifecycle instrumentation
+    events: Option<&'static dyn EpdEvents>,
+    /// minimum interval between refreshes
+    rate_limit: Option<RateLimit>,
+    /// time of the start of the last refresh, from the clock of the rate limit
+    last_refresh_ms: Option<u32>,
+    /// optional register settings sent during `init`
+    registers: Registers,
+    /// expected length of the buffer planes, checked by `upload`
+    buffer_len: Option<usize>,
+    /// skip powering on before a refresh if the charge pumps are still on
+    keep_powered: bool,
+    /// how to find out whether the COG is busy
+    busy_source: BusySource,
+    /// the charge pumps are powered on
+    powered: bool,
+    /// the temperature may be below 0 °C: the last measured one was, or
+    /// the temperature of a wide temperature panel is not measured
+    cold: bool,
     state: PhantomData<STATE>,
 }
 
@@ -80,32 +394,42 @@ pub trait EpdState {}
 impl EpdState for Active {}
 impl EpdState for Inactive {}
 
-impl<SPI, BUSY, DC, RST, DELAY> Epd<Inactive, SPI, BUSY, DC, RST, DELAY>
+// The driver can be moved to and shared with other threads if the pins
+// can, checked at compile time
+#[allow(dead_code)]
+fn assert_send_sync<BUSY: Send + Sync, DC: Send + Sync, RST: Send + Sync>() {
+    fn check<T: Send + Sync>() {}
+    check::<Epd<Active, BUSY, DC, RST>>();
+    check::<Epd<Inactive, BUSY, DC, RST>>();
+    check::<crate::AutoEpd<BUSY, DC, RST>>();
+}
+
+impl<BUSY, DC, RST> Epd<Inactive, BUSY, DC, RST>
 where
-    SPI: SpiDevice,
     BUSY: InputPin,
     DC: OutputPin,
     RST: OutputPin,
-    DELAY: DelayNs,
 {
     /// Create a new e-paper driver. You have to call `init` before sending pages to the e-paper via `update`.
     /// `spi_chunk_size` determines the data chunk size for SPI writes, 0 means no chunks.
     /// E.g. Linux has a default buffer size of 4096. So `spi_chunk_size` must be equal to or smaller than 4096.
-    pub fn new(
-        _spi: &mut SPI,
-        busy: BUSY,
-        dc: DC,
-        rst: RST,
-        _delay: &mut DELAY,
-        spi_chunk_size: usize,
-    ) -> Self {
+    pub fn new(busy: BUSY, dc: DC, rst: RST, spi_chunk_size: usize) -> Self {
         Self {
-            busy,
-            dc,
-            rst,
-            spi_chunk_size,
-            spi: PhantomData,
-            delay: PhantomData,
+            interface: EpdInterface::new(busy, dc, rst, spi_chunk_size),
+            sleep_hook: None,
+            watchdog_hook: None,
+            abort_hook: None,
+            refresh_guard: None,
+            progress_hook: None,
+            events: None,
+            rate_limit: None,
+            last_refresh_ms: None,
+            registers: Registers::default(),
+            buffer_len: None,
+            keep_powered: false,
+            busy_source: BusySource::Pin,
+            powered: false,
+            cold: false,
             state: PhantomData::<Inactive>,
         }
     }
@@ -117,37 +441,141 @@ where
     /// # Errors
     ///
     /// This function will return an error if there is an error
-    /// with the GPIOs or the SPI device.
-    pub fn init(
+    /// with the GPIOs or the SPI device. The error contains the driver
+    /// in the inactive state, so `init` can be retried.
+    #[allow(clippy::result_large_err)] // the error gives back the driver
+    pub fn init<SPI: SpiDevice>(
         mut self,
         spi: &mut SPI,
-        delay: &mut DELAY,
-    ) -> EpdResult<Active, SPI, BUSY, DC, RST, DELAY> {
-        self.dc.set_high().map_err(Error::GpioDc)?;
-        self.reset(delay)?;
-        self.soft_reset(spi, delay)?;
-        self.send_data(spi, Command::InputTemperature, REG_DATA_INPUT_TEMP)?;
-        self.send_data(spi, Command::ActiveTemperature, REG_DATA_ACTIVE_TEMP)?;
-        self.send_data(spi, Command::Psr, REG_DATA_PSR)?;
-        Ok(Epd {
-            busy: self.busy,
-            dc: self.dc,
-            rst: self.rst,
-            spi_chunk_size: self.spi_chunk_size,
-            spi: PhantomData,
-            delay: PhantomData,
-            state: PhantomData::<Active>,
-        })
+        delay: &mut impl DelayNs,
+    ) -> InitResult<SPI, BUSY, DC, RST> {
+        match self.init_registers(spi, delay) {
+            Ok(()) => Ok(self.into_state()),
+            Err(error) => Err(InitError { epd: self, error }),
+        }
+    }
+
+    /// Set the VCOM and data interval setting (CDI register) sent during
+    /// `init`. It controls the border behaviour as well as the timing of
+    /// source and VCOM, see the application notes of your panel.
+    /// `None` (the default) keeps the power-on value of the COG.
+    pub fn set_cdi(&mut self, cdi: Option<u8>) {
+        self.registers.cdi = cdi;
+    }
+
+    /// Set the gate/source non-overlap period (TCON register) sent during
+    /// `init`, as listed in the register tables of the panel datasheets.
+    /// `None` (the default) sends [`DisplaySpec::TCON`] of the panel (see
+    /// `set_panel`), or keeps the power-on value of the COG if the panel
+    /// has none.
+    pub fn set_tcon(&mut self, tcon: Option<u8>) {
+        self.registers.tcon = tcon;
+    }
+
+    /// Set the PLL control register sent during `init`, which determines the
+    /// internal frame rate. Lowering the frame rate e.g. reduces audible
+    /// noise of the boost converter during the refresh.
+    /// `None` (the default) keeps the power-on value of the COG.
+    pub fn set_pll(&mut self, pll: Option<u8>) {
+        self.registers.pll = pll;
+    }
+
+    /// Set the booster soft-start register sent during `init`, one byte for
+    /// each of the phases A, B and C. Each byte typically contains the
+    /// soft-start period (bits 7..6), the driving strength (bits 5..3) and
+    /// the minimum off time (bits 2..0). A weaker setting helps to avoid
+    /// brown-outs with weak supplies at the start of the refresh.
+    /// `None` (the default) keeps the power-on value of the COG.
+    pub fn set_booster_soft_start(&mut self, phases: Option<[u8; 3]>) {
+        self.registers.booster_soft_start = phases;
+    }
+
+    /// Set the power setting register sent during `init`, for panels which
+    /// specify non-default drive voltages. The bytes typically are: internal
+    /// source/gate power enable, VGH/VGL level selection, VDH level, VDL level
+    /// and VDHR (red) level, see the datasheet of your panel.
+    /// `None` (the default) keeps the power-on value of the COG.
+    pub fn set_power_setting(&mut self, power_setting: Option<[u8; 5]>) {
+        self.registers.power_setting = power_setting;
+    }
+
+    /// Use the temperature measured by the internal sensor of the COG (see
+    /// `read_temperature`) for the input temperature register sent during
+    /// `init`, instead of the fixed default of 25 °C. Only enable this for
+    /// COGs with an internal temperature sensor, check the datasheet of
+    /// your panel.
+    pub fn set_internal_temperature(&mut self, enabled: bool) {
+        self.registers.internal_temperature = enabled;
+    }
+
+    /// Set the table of temperature ranges applied to the temperature
+    /// measured by the internal sensor (see `set_internal_temperature`),
+    /// e.g. `Display2in66::TEMPERATURE_TABLE` from [`DisplaySpec`](crate::DisplaySpec).
+    /// The input and active temperature registers are sent as given by
+    /// the range of the measured temperature. By default the table of the
+    /// panel and variant (see `set_panel` and `set_variant`) is used,
+    /// [`SPECTRA_TEMPERATURE_TABLE`](crate::SPECTRA_TEMPERATURE_TABLE) for a
+    /// standard panel without `set_panel`. An empty table sends the measured temperature and
+    /// the active temperature of the panel.
+    pub fn set_temperature_table(&mut self, table: &'static [TemperatureSetting]) {
+        self.registers.temperature_table = Some(table);
+    }
+
+    /// Select the temperature variant of the panel, the default is
+    /// [`PanelVariant::Standard`]. During `init` the panel settings and the
+    /// temperature table of the variant are sent for the panel (see
+    /// `set_panel` and [`DisplaySpec::WIDE_TEMPERATURE_PSR`]), unless they
+    /// are set with `set_psr` or `set_temperature_table`. For wide
+    /// temperature panels negative temperatures measured by the internal
+    /// sensor are sent to the COG. The timeout for the busy signal is
+    /// extended below 0 °C, or always if the temperature is not measured.
+    pub fn set_variant(&mut self, variant: PanelVariant) {
+        self.registers.variant = variant;
+    }
+
+    /// Select the panel `D`, e.g. `set_panel::<Panel2in66>()` or
+    /// `set_panel::<Display2in66>()`. Its
+    /// register data (see [`DisplaySpec`]) is sent during `init` for the
+    /// soft reset, the input and active temperature and the panel settings
+    /// and its temperature table is applied to the measured temperature,
+    /// registers set explicitly (e.g. with `set_psr`) take precedence.
+    /// Without a panel the data of the standard Spectra panels is sent.
+    pub fn set_panel<D: DisplaySpec>(&mut self) {
+        self.registers.panel = PanelSettings::of::<D>();
+    }
+
+    /// Set the panel settings (PSR register) sent during `init`, e.g. for
+    /// panel variants with other settings. `None` (the default) sends the
+    /// settings of the panel and variant (see `set_panel` and `set_variant`).
+    pub fn set_psr(&mut self, psr: Option<[u8; 2]>) {
+        self.registers.psr = psr;
+    }
+
+    /// Set the film of the panel, the default is [`Film::Spectra`]. For the
+    /// monochrome Aurora films the red plane is sent as zeros, whatever the
+    /// display buffer contains. With [`Film::AuroraMa`] the charge pumps are
+    /// turned off after each blocking refresh, so `set_keep_powered` has no
+    /// effect. After `start_refresh` the pumps stay on until `power_off`.
+    pub fn set_film(&mut self, film: Film) {
+        self.registers.film = film;
+    }
+
+    /// Set the polarity of `plane` expected by the panel, the default is
+    /// [`BitPolarity::Normal`]. Some panel revisions take inverted planes,
+    /// with this setting the same display buffers are shown correctly on
+    /// both. The bits are inverted while uploading (including planes sent
+    /// as white, e.g. the red plane of the Aurora films), the display
+    /// buffers are left untouched.
+    pub fn set_polarity(&mut self, plane: Plane, polarity: BitPolarity) {
+        self.registers.polarity[plane as usize] = polarity;
     }
 }
 
-impl<SPI, BUSY, DC, RST, DELAY> Epd<Active, SPI, BUSY, DC, RST, DELAY>
+impl<BUSY, DC, RST> Epd<Active, BUSY, DC, RST>
 where
-    SPI: SpiDevice,
     BUSY: InputPin,
     DC: OutputPin,
     RST: OutputPin,
-    DELAY: DelayNs,
 {
     /// Show display on e-paper. This function is blocking until the update
     /// process is complete.
@@ -155,20 +583,489 @@ where
     /// # Errors
     ///
     /// This function will return an error if there is an error
+    /// with the GPIOs or the SPI device or if the length of the buffers
+    /// does not match (see `set_buffer_len`).
+    pub fn update<SPI: SpiDevice>(
+        &mut self,
+        display: &impl DisplayBuffer,
+        spi: &mut SPI,
+        delay: &mut impl DelayNs,
+    ) -> Result<(), EpdError<SPI, DC, RST>> {
+        self.upload(display, spi)?;
+        self.refresh(spi, delay)?;
+        Ok(())
+    }
+
+    /// Show a frame streamed from the iterators `black` and `red`, which
+    /// yield the bytes of the planes as returned by `DisplayBuffer`. This
+    /// allows procedurally generated frames without a display buffer in
+    /// RAM. The iterators should yield one complete plane each. This
+    /// function is blocking until the update process is complete.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if there is an error
     /// with the GPIOs or the SPI device.
-    pub fn update(
+    pub fn update_from_iter<SPI: SpiDevice>(
+        &mut self,
+        black: impl Iterator<Item = u8>,
+        red: impl Iterator<Item = u8>,
+        spi: &mut SPI,
+        delay: &mut impl DelayNs,
+    ) -> Result<(), EpdError<SPI, DC, RST>> {
+        self.update_from_source(&mut IterSource::new(black, red), spi, delay)
+    }
+
+    /// Show a frame streamed from `source`, see [`FrameSource`]. The data
+    /// is sent in chunks of a small buffer, so no display buffer is needed.
+    /// This function is blocking until the update process is complete.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if there is an error
+    /// with the GPIOs or the SPI device.
+    pub fn update_from_source<SPI: SpiDevice>(
+        &mut self,
+        source: &mut impl FrameSource,
+        spi: &mut SPI,
+        delay: &mut impl DelayNs,
+    ) -> Result<(), EpdError<SPI, DC, RST>> {
+        self.timed_update(source, spi, delay)?;
+        Ok(())
+    }
+
+    /// Show a frame streamed from `source` and return the duration of
+    /// the refresh in ms
+    fn timed_update<SPI: SpiDevice>(
+        &mut self,
+        source: &mut impl FrameSource,
+        spi: &mut SPI,
+        delay: &mut impl DelayNs,
+    ) -> Result<u32, EpdError<SPI, DC, RST>> {
+        self.event(EpdEvents::on_upload_start);
+        self.interface.send_command(spi, Command::BufferBlack)?;
+        let len = self.write_source(spi, source, Plane::Black)?;
+        self.interface.send_command(spi, Command::BufferRed)?;
+        if self.registers.film.has_red() {
+            self.write_source(spi, source, Plane::Red)?;
+        } else {
+            self.write_white(spi, Plane::Red, len)?;
+        }
+        self.event(EpdEvents::on_upload_end);
+        self.check_refresh_guard::<SPI>()?;
+        self.check_rate_limit::<SPI>(delay)?;
+        self.power_on(spi, delay)?;
+        self.display_refresh(spi, delay)
+    }
+
+    /// Show a frame read from `black` and `red`, e.g. pre-rendered planes
+    /// in files or received over a socket. The data is streamed in chunks,
+    /// so the planes are never loaded completely into memory. Each reader
+    /// should provide one complete plane. This function is blocking until
+    /// the update process is complete.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if there is an error
+    /// with the GPIOs, the SPI device or one of the readers.
+    #[cfg(feature = "std")]
+    pub fn update_from_reader<SPI: SpiDevice>(
+        &mut self,
+        black: impl std::io::Read,
+        red: impl std::io::Read,
+        spi: &mut SPI,
+        delay: &mut impl DelayNs,
+    ) -> Result<(), EpdError<SPI, DC, RST>> {
+        self.event(EpdEvents::on_upload_start);
+        self.interface.send_command(spi, Command::BufferBlack)?;
+        let len = self.write_reader(spi, black, Plane::Black)?;
+        self.interface.send_command(spi, Command::BufferRed)?;
+        if self.registers.film.has_red() {
+            self.write_reader(spi, red, Plane::Red)?;
+        } else {
+            self.write_white(spi, Plane::Red, len)?;
+        }
+        self.event(EpdEvents::on_upload_end);
+        self.refresh(spi, delay)?;
+        Ok(())
+    }
+
+    /// Send the display buffers to the e-paper without refreshing it.
+    /// Call `refresh` afterwards to show them, or `abort` to discard them.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if there is an error
+    /// with the GPIOs or the SPI device or if the length of the buffers
+    /// does not match (see `set_buffer_len`).
+    pub fn upload<SPI: SpiDevice>(
         &mut self,
         display: &impl DisplayBuffer,
         spi: &mut SPI,
-        delay: &mut DELAY,
     ) -> Result<(), EpdError<SPI, DC, RST>> {
-        self.send_data(spi, Command::BufferBlack, display.get_buffer_black())?;
-        self.send_data(spi, Command::BufferRed, display.get_buffer_red())?;
+        let black = display.get_buffer_black();
+        let red = display.get_buffer_red();
+        let expected = self.buffer_len.unwrap_or(black.len());
+        if black.len() != expected {
+            let actual = black.len();
+            return Err(Error::SizeMismatch { expected, actual });
+        }
+        if !red.is_empty() && red.len() != expected {
+            let actual = red.len();
+            return Err(Error::SizeMismatch { expected, actual });
+        }
+        self.event(EpdEvents::on_upload_start);
+        self.interface.send_command(spi, Command::BufferBlack)?;
+        self.write_plane(spi, Plane::Black, black)?;
+        self.interface.send_command(spi, Command::BufferRed)?;
+        if red.is_empty() || !self.registers.film.has_red() {
+            self.write_white(spi, Plane::Red, black.len())?;
+        } else {
+            self.write_plane(spi, Plane::Red, red)?;
+        }
+        self.event(EpdEvents::on_upload_end);
+        Ok(())
+    }
+
+    /// Show the previously uploaded buffers on the e-paper. This function is
+    /// blocking until the refresh is complete or aborted by the abort hook
+    /// (see `set_abort_hook`).
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if there is an error
+    /// with the GPIOs or the SPI device, if the refresh was aborted,
+    /// vetoed by the refresh guard (see `set_refresh_guard`) or rejected
+    /// by the rate limit (see `set_rate_limit`).
+    pub fn refresh<SPI: SpiDevice>(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut impl DelayNs,
+    ) -> Result<(), EpdError<SPI, DC, RST>> {
+        self.check_refresh_guard::<SPI>()?;
+        self.check_rate_limit::<SPI>(delay)?;
         self.power_on(spi, delay)?;
         self.display_refresh(spi, delay)?;
         Ok(())
     }
 
+    /// Start showing the previously uploaded buffers without waiting for
+    /// the refresh to complete. Poll the returned handle with `is_done`,
+    /// e.g. once per iteration of a superloop, or block with `wait`. The
+    /// handle borrows the SPI device for reading the status register (see
+    /// `set_busy_source`). Powering on is still blocking, but only takes a
+    /// few milliseconds.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if there is an error
+    /// with the GPIOs or the SPI device or if the refresh was vetoed by
+    /// the refresh guard (see `set_refresh_guard`) or rejected by the rate
+    /// limit (see `set_rate_limit`).
+    pub fn start_refresh<'a, SPI: SpiDevice>(
+        &'a mut self,
+        spi: &'a mut SPI,
+        delay: &mut impl DelayNs,
+    ) -> RefreshResult<'a, SPI, BUSY, DC, RST> {
+        self.check_refresh_guard::<SPI>()?;
+        self.check_rate_limit::<SPI>(delay)?;
+        self.power_on(spi, delay)?;
+        self.start_display_refresh(spi)?;
+        Ok(RefreshHandle { epd: self, spi })
+    }
+
+    /// Show only the changes of the current frame of `display` compared to
+    /// its previous frame. The changed area is extended to byte boundaries
+    /// horizontally, uploaded via the partial window commands and refreshed.
+    /// Afterwards the current frame is copied into the previous one (see
+    /// [`DoubleDisplay::mark_shown`]). Returns `false` if nothing has changed and
+    /// no refresh was done. This function is blocking until the update
+    /// process is complete.
+    ///
+    /// Partial windows are not supported by all COGs, check the datasheet
+    /// of your panel.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if there is an error
+    /// with the GPIOs or the SPI device or if the refresh was vetoed by the
+    /// refresh guard or rejected by the rate limit. The previous frame is
+    /// kept in this case.
+    #[cfg(feature = "graphics")]
+    #[allow(clippy::cast_sign_loss)]
+    pub fn update_changed<
+        SPI: SpiDevice,
+        const SIZE_V: u32,
+        const SIZE_H: u32,
+        const IMAGE_SIZE: usize,
+    >(
+        &mut self,
+        display: &mut DoubleDisplay<SIZE_V, SIZE_H, IMAGE_SIZE>,
+        spi: &mut SPI,
+        delay: &mut impl DelayNs,
+    ) -> Result<bool, EpdError<SPI, DC, RST>> {
+        let window = display.diff();
+        let Some(bottom_right) = window.bottom_right() else {
+            return Ok(false);
+        };
+        self.check_refresh_guard::<SPI>()?;
+        self.check_rate_limit::<SPI>(delay)?;
+        let x_start = window.top_left.x as usize / 8;
+        let x_end = bottom_right.x as usize / 8;
+        let y_start = window.top_left.y as usize;
+        let y_end = bottom_right.y as usize;
+        let frame = display.current();
+
+        self.event(EpdEvents::on_upload_start);
+        self.interface.send_data(spi, Command::PartialIn, &[])?;
+        self.interface
+            .send_data(spi, Command::PartialWindow, &partial_window_data(&window))?;
+        self.interface.send_command(spi, Command::BufferBlack)?;
+        for row in frame.black_rows().take(y_end + 1).skip(y_start) {
+            self.write_plane(spi, Plane::Black, &row[x_start..=x_end])?;
+        }
+        self.interface.send_command(spi, Command::BufferRed)?;
+        if self.registers.film.has_red() {
+            for row in frame.red_rows().take(y_end + 1).skip(y_start) {
+                self.write_plane(spi, Plane::Red, &row[x_start..=x_end])?;
+            }
+        } else {
+            let len = (x_end - x_start + 1) * (y_end - y_start + 1);
+            self.write_white(spi, Plane::Red, len)?;
+        }
+        self.event(EpdEvents::on_upload_end);
+        self.power_on(spi, delay)?;
+        self.display_refresh(spi, delay)?;
+        self.interface.send_data(spi, Command::PartialOut, &[])?;
+        display.mark_shown();
+        Ok(true)
+    }
+
+    /// Run the panel break check of the COG and read its status register.
+    /// This requires the data line of the panel to be readable by the
+    /// SPI device (e.g. MISO connected for 3-wire panels), otherwise the
+    /// result is meaningless.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if there is an error
+    /// with the GPIOs or the SPI device.
+    pub fn check_panel<SPI: SpiDevice>(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut impl DelayNs,
+    ) -> Result<PanelStatus, EpdError<SPI, DC, RST>> {
+        let mut panel_break = [0];
+        self.interface.send_command(spi, Command::PanelBreakCheck)?;
+        self.wait_busy(spi, delay)?;
+        self.interface
+            .read_data(spi, Command::PanelBreakCheck, &mut panel_break)?;
+        let mut status = [0];
+        self.interface
+            .read_data(spi, Command::GetStatus, &mut status)?;
+        Ok(PanelStatus::from_registers(status[0], panel_break[0]))
+    }
+
+    /// Read the temperature in °C measured by the internal temperature
+    /// sensor of the COG. Like `check_panel`, this requires the data line
+    /// of the panel to be readable by the SPI device. Not all COGs have an
+    /// internal temperature sensor, check the datasheet of your panel.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if there is an error
+    /// with the GPIOs or the SPI device.
+    pub fn read_temperature<SPI: SpiDevice>(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut impl DelayNs,
+    ) -> Result<i8, EpdError<SPI, DC, RST>> {
+        self.measure_temperature(spi, delay)
+    }
+
+    /// Identify the connected panel from the resolution and revision
+    /// reported by the COG and check it against the display type `D` the
+    /// firmware was built for, e.g. `detect_panel::<Display2in66, _>`. For
+    /// a matching panel, the panel settings and temperature table of `D`
+    /// (see [`DisplaySpec`]) are selected and sent to the COG, replacing
+    /// the ones set before `init`. Like `check_panel`, this requires the
+    /// data line of the panel to be readable by the SPI device.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if there is an error with the
+    /// GPIOs or the SPI device, or `Error::PanelMismatch` if the panel
+    /// is unknown or another one than `D`. The settings are kept in this
+    /// case.
+    pub fn detect_panel<D: DisplaySpec, SPI: SpiDevice>(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut impl DelayNs,
+    ) -> Result<DetectedPanel, EpdError<SPI, DC, RST>> {
+        let mut resolution = [0; 4];
+        self.interface
+            .read_data(spi, Command::Resolution, &mut resolution)?;
+        let mut revision = [0];
+        self.interface
+            .read_data(spi, Command::Revision, &mut revision)?;
+        // horizontal and vertical resolution, 16 bit big endian each
+        let width = u32::from(u16::from_be_bytes([resolution[0], resolution[1]]));
+        let height = u32::from(u16::from_be_bytes([resolution[2], resolution[3]]));
+        let model = PanelModel::find(width, height);
+        let Some(model) = model.filter(|model| model.id == D::PANEL_ID) else {
+            return Err(Error::PanelMismatch {
+                expected: D::PANEL_ID,
+                detected: model.map(|model| model.id),
+            });
+        };
+        self.registers.panel = PanelSettings::of::<D>();
+        self.registers.psr = None;
+        self.registers.temperature_table = None;
+        self.configure(spi, delay)?;
+        Ok(DetectedPanel {
+            model,
+            revision: revision[0],
+        })
+    }
+
+    /// Run a test sequence for manufacturing test fixtures: read the panel
+    /// status (see `check_panel`), then show an all-white frame, an
+    /// all-black frame and a checker pattern of black and red 8x8 squares.
+    /// The size of the panel is given by `SIZE_V` and `SIZE_H` as for the
+    /// display types, e.g. `self_test::<_, 296, 152>` for 2.66". The frames
+    /// are streamed, no display buffer is needed. Check the panel visually
+    /// for defects in addition to the returned report.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if there is an error
+    /// with the GPIOs or the SPI device or if a refresh failed.
+    pub fn self_test<SPI: SpiDevice, const SIZE_V: u32, const SIZE_H: u32>(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut impl DelayNs,
+    ) -> Result<SelfTestReport, EpdError<SPI, DC, RST>> {
+        let row_len = SIZE_H as usize / 8;
+        let len = SIZE_V as usize * row_len;
+        let status = self.check_panel(spi, delay)?;
+        let plane = |value: u8| core::iter::repeat(value).take(len);
+        let white_ms = self.timed_update(&mut IterSource::new(plane(0), plane(0)), spi, delay)?;
+        let black_ms =
+            self.timed_update(&mut IterSource::new(plane(0xff), plane(0)), spi, delay)?;
+        // squares are one byte wide and 8 rows high
+        let checker = |odd: usize| {
+            (0..len).map(move |i| {
+                if (i % row_len + i / row_len / 8) % 2 == odd {
+                    0xff
+                } else {
+                    0
+                }
+            })
+        };
+        let checker_ms =
+            self.timed_update(&mut IterSource::new(checker(0), checker(1)), spi, delay)?;
+        Ok(SelfTestReport {
+            status,
+            white_ms,
+            black_ms,
+            checker_ms,
+        })
+    }
+
+    /// Run a stress test for the qualification of panels and power
+    /// supplies: refresh the panel `config.cycles` times, alternating
+    /// all-white, all-black and all-red frames, with a pause of
+    /// `config.interval_ms` between the refreshes (and the rate limit, see
+    /// `set_rate_limit`). Failed refreshes are counted and the run goes on,
+    /// only an abort by the abort hook ends it early. The size of the panel
+    /// is given as for `self_test`.
+    pub fn stress_test<SPI: SpiDevice, const SIZE_V: u32, const SIZE_H: u32>(
+        &mut self,
+        config: StressConfig,
+        spi: &mut SPI,
+        delay: &mut impl DelayNs,
+    ) -> StressReport<EpdError<SPI, DC, RST>> {
+        let len = SIZE_V as usize * (SIZE_H as usize / 8);
+        let plane = |value: u8| core::iter::repeat(value).take(len);
+        let mut report = StressReport {
+            completed: 0,
+            failures: 0,
+            min_refresh_ms: u32::MAX,
+            max_refresh_ms: 0,
+            first_error: None,
+        };
+        for cycle in 0..config.cycles {
+            if cycle > 0 {
+                self.pause(delay, config.interval_ms);
+            }
+            let (black, red) = match cycle % 3 {
+                0 => (0, 0),
+                1 => (0xff, 0),
+                _ => (0, 0xff),
+            };
+            let source = &mut IterSource::new(plane(black), plane(red));
+            match self.timed_update(source, spi, delay) {
+                Ok(elapsed_ms) => {
+                    report.completed += 1;
+                    report.min_refresh_ms = report.min_refresh_ms.min(elapsed_ms);
+                    report.max_refresh_ms = report.max_refresh_ms.max(elapsed_ms);
+                }
+                Err(error) => {
+                    report.failures += 1;
+                    let aborted = matches!(error, Error::Aborted);
+                    report.first_error.get_or_insert(error);
+                    if aborted {
+                        break;
+                    }
+                }
+            }
+        }
+        report.min_refresh_ms = report.min_refresh_ms.min(report.max_refresh_ms);
+        report
+    }
+
+    /// Reset the COG with the reset pin and initialize it again, e.g. to
+    /// recover after an external fault condition like a brown-out. All
+    /// registers set before `init` are sent again. This function is
+    /// blocking until initialisation is complete.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if there is an error
+    /// with the GPIOs or the SPI device. The error contains the driver
+    /// in the inactive state, so `init` can be retried.
+    #[allow(clippy::result_large_err)] // the error gives back the driver
+    pub fn reset<SPI: SpiDevice>(
+        mut self,
+        spi: &mut SPI,
+        delay: &mut impl DelayNs,
+    ) -> InitResult<SPI, BUSY, DC, RST> {
+        match self.init_registers(spi, delay) {
+            Ok(()) => Ok(self),
+            Err(error) => Err(InitError {
+                epd: self.into_state(),
+                error,
+            }),
+        }
+    }
+
+    /// Soft reset the COG via the panel settings register and send all
+    /// registers set before `init` again, without toggling the reset pin.
+    /// The uploaded buffers are lost and the charge pumps are powered on
+    /// again before the next refresh.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if there is an error
+    /// with the GPIOs or the SPI device.
+    pub fn soft_reset<SPI: SpiDevice>(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut impl DelayNs,
+    ) -> Result<(), EpdError<SPI, DC, RST>> {
+        self.configure(spi, delay)
+    }
+
     /// Power off the e-paper. This function is blocking until the e-paper
     /// is powered off. The return value is an e-paper driver in
     /// the inactive state. You have to call `init` again before
@@ -178,113 +1075,623 @@ where
     ///
     /// This function will return an error if there is an error
     /// with the GPIOs or the SPI device.
-    pub fn power_off(
+    pub fn power_off<SPI: SpiDevice>(
         mut self,
         spi: &mut SPI,
-        delay: &mut DELAY,
-    ) -> EpdResult<Inactive, SPI, BUSY, DC, RST, DELAY> {
-        self.send_data(spi, Command::PowerOff, &[0x0])?;
-        self.wait_busy(delay)?;
-        self.dc.set_low().map_err(Error::GpioDc)?;
+        delay: &mut impl DelayNs,
+    ) -> EpdResult<Inactive, SPI, BUSY, DC, RST> {
+        self.power_down(spi, delay)?;
+        Ok(self.into_state())
+    }
+
+    pub(crate) fn power_down<SPI: SpiDevice>(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut impl DelayNs,
+    ) -> Result<(), EpdError<SPI, DC, RST>> {
+        self.interface.send_data(spi, Command::PowerOff, &[0x0])?;
+        self.wait_busy(spi, delay)?;
+        self.interface
+            .dc
+            .set_low()
+            .map_err(|error| Error::GpioDc { error, phase: None })?;
         delay.delay_ms(150);
-        self.rst.set_low().map_err(Error::GpioRst)?;
-        Ok(Epd {
-            busy: self.busy,
-            dc: self.dc,
-            rst: self.rst,
-            spi_chunk_size: self.spi_chunk_size,
-            spi: PhantomData,
-            delay: PhantomData,
-            state: PhantomData::<Inactive>,
-        })
+        self.interface.rst.set_low().map_err(Error::GpioRst)?;
+        self.event(EpdEvents::on_power_off);
+        Ok(())
+    }
+
+    /// Abandon an update, e.g. after an upload or an aborted refresh. Unlike
+    /// `power_off` no commands are sent, the e-paper is put into reset
+    /// immediately. The return value is an e-paper driver in the inactive
+    /// state. You have to call `init` again before sending pages to the
+    /// e-paper via `update`.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if there is an error with the GPIOs.
+    /// No SPI device is used, the type parameter `SPI` only determines the
+    /// error type and may have to be given explicitly, e.g.
+    /// `epd.abort::<MySpi>(&mut delay)`.
+    pub fn abort<SPI: SpiDevice>(
+        mut self,
+        delay: &mut impl DelayNs,
+    ) -> EpdResult<Inactive, SPI, BUSY, DC, RST> {
+        self.interface
+            .dc
+            .set_low()
+            .map_err(|error| Error::GpioDc { error, phase: None })?;
+        self.interface.rst.set_low().map_err(Error::GpioRst)?;
+        delay.delay_ms(10);
+        Ok(self.into_state())
     }
 }
 
-impl<STATE, SPI, BUSY, DC, RST, DELAY> Epd<STATE, SPI, BUSY, DC, RST, DELAY>
+impl<STATE, BUSY, DC, RST> Epd<STATE, BUSY, DC, RST>
 where
     STATE: EpdState,
-    SPI: SpiDevice,
     BUSY: InputPin,
     DC: OutputPin,
     RST: OutputPin,
-    DELAY: DelayNs,
 {
-    fn reset(&mut self, delay: &mut DELAY) -> Result<(), EpdError<SPI, DC, RST>> {
-        delay.delay_ms(1);
-        self.rst.set_high().map_err(Error::GpioRst)?;
-        delay.delay_ms(5);
-        self.rst.set_low().map_err(Error::GpioRst)?;
-        delay.delay_ms(10);
-        self.rst.set_high().map_err(Error::GpioRst)?;
-        delay.delay_ms(5);
-        Ok(())
+    /// Set a hook which is called repeatedly while waiting for the busy
+    /// signal, replacing the default 1 ms delay between the polls. This allows
+    /// the MCU to sleep during long operations like the refresh, e.g. with
+    /// `cortex_m::asm::wfi`. The hook should return after about 1 ms
+    /// (or earlier when the busy signal changes) since each call is counted
+    /// as 1 ms for the timeout.
+    pub fn set_sleep_hook(&mut self, hook: Option<fn()>) {
+        self.sleep_hook = hook;
     }
 
-    fn power_on(&mut self, spi: &mut SPI, delay: &mut DELAY) -> Result<(), EpdError<SPI, DC, RST>> {
-        self.send_data(spi, Command::PowerOn, &[0x0])?;
-        self.wait_busy(delay)?;
-        Ok(())
+    /// Set a hook which is called every `interval_ms` milliseconds while
+    /// waiting for the busy signal, e.g. to feed a watchdog during the
+    /// refresh. An interval of 0 is treated as 1 ms.
+    pub fn set_watchdog_hook(&mut self, hook: Option<fn()>, interval_ms: u32) {
+        self.watchdog_hook = hook.map(|hook| (hook, interval_ms.max(1)));
+    }
+
+    /// Set a hook which is polled while waiting for the busy signal. If it
+    /// returns `true` (e.g. on a low battery or shutdown event), waiting is
+    /// stopped and the operation fails with `Error::Aborted`. Call `abort`
+    /// afterwards to bring the e-paper into a defined state.
+    pub fn set_abort_hook(&mut self, hook: Option<fn() -> bool>) {
+        self.abort_hook = hook;
+    }
+
+    /// Set a guard which is called before each refresh. If it returns
+    /// `false` (e.g. because the supply voltage is too low for a complete
+    /// refresh), the refresh is not started and the update fails with
+    /// `Error::RefreshVetoed`. Uploaded buffers are kept, so `refresh`
+    /// can be retried later.
+    pub fn set_refresh_guard(&mut self, guard: Option<fn() -> bool>) {
+        self.refresh_guard = guard;
+    }
+
+    /// Set a hook which reports the progress of a refresh in percent, e.g.
+    /// to show "updating... 40%" in a host UI. The COG gives no feedback
+    /// during the refresh, so the progress is estimated from the elapsed
+    /// time and `typical_ms`, the typical refresh duration of the panel at
+    /// the current temperature (see `DisplaySpec::estimate_refresh_duration`).
+    /// The hook is called with 0 at the start, on each change of the
+    /// estimate (which stays below 100 while the refresh is running) and
+    /// with 100 once the refresh is complete.
+    pub fn set_progress_hook(&mut self, hook: Option<fn(u8)>, typical_ms: u32) {
+        self.progress_hook = hook.map(|hook| (hook, typical_ms.max(1)));
+    }
+
+    /// Set the lifecycle hooks, see [`EpdEvents`]
+    pub fn set_events(&mut self, events: Option<&'static dyn EpdEvents>) {
+        self.events = events;
+    }
+
+    /// Enforce a minimum interval between the start of two refreshes, see
+    /// [`RateLimit`]. The time of the last refresh is kept across
+    /// `power_off` and `init`. `None` (the default) disables the limit.
+    pub fn set_rate_limit(&mut self, rate_limit: Option<RateLimit>) {
+        self.rate_limit = rate_limit;
+    }
+
+    /// Time in milliseconds until the rate limit allows the next refresh,
+    /// 0 if a refresh can start immediately or no limit is set
+    #[must_use]
+    pub fn refresh_blocked_ms(&self) -> u32 {
+        let (Some(limit), Some(last)) = (self.rate_limit, self.last_refresh_ms) else {
+            return 0;
+        };
+        let elapsed = limit.clock.now_ms().wrapping_sub(last);
+        limit.min_interval_ms.saturating_sub(elapsed)
+    }
+
+    /// Set the expected length of the buffer planes, e.g.
+    /// `Panel2in66::BUFFER_LEN` from [`DisplaySpec`](crate::DisplaySpec).
+    /// Buffers of another length are rejected by `update` and `upload`
+    /// with `Error::SizeMismatch` instead of sending a truncated or
+    /// oversized frame. An empty red plane is always accepted. With `None`
+    /// (the default) only the lengths of the two planes are compared.
+    pub fn set_buffer_len(&mut self, buffer_len: Option<usize>) {
+        self.buffer_len = buffer_len;
+    }
+
+    /// Keep the charge pumps powered on between back-to-back updates. If
+    /// enabled, powering on is skipped for all updates after the first one,
+    /// reducing latency and noise. The charge pumps are powered off as usual
+    /// with `power_off`, so call it once no further frames are coming soon.
+    pub fn set_keep_powered(&mut self, keep_powered: bool) {
+        self.keep_powered = keep_powered;
+    }
+
+    /// Select how to find out whether the COG is busy, the default is
+    /// [`BusySource::Pin`]. With [`BusySource::Status`] the busy pin is not
+    /// used, e.g. [`NoBusyPin`](crate::NoBusyPin) can be passed to `new`.
+    pub fn set_busy_source(&mut self, busy_source: BusySource) {
+        self.busy_source = busy_source;
+    }
+
+    /// Low-level interface to the COG, e.g. to send commands not covered
+    /// by the driver. Sequences sent this way may bring the COG into a
+    /// state the driver does not expect.
+    pub fn interface(&mut self) -> &mut EpdInterface<BUSY, DC, RST> {
+        &mut self.interface
     }
 
-    fn send_data(
+    pub(crate) fn into_state<NEXT: EpdState>(self) -> Epd<NEXT, BUSY, DC, RST> {
+        Epd {
+            interface: self.interface,
+            sleep_hook: self.sleep_hook,
+            watchdog_hook: self.watchdog_hook,
+            abort_hook: self.abort_hook,
+            refresh_guard: self.refresh_guard,
+            progress_hook: self.progress_hook,
+            events: self.events,
+            rate_limit: self.rate_limit,
+            last_refresh_ms: self.last_refresh_ms,
+            registers: self.registers,
+            buffer_len: self.buffer_len,
+            keep_powered: self.keep_powered,
+            busy_source: self.busy_source,
+            powered: false,
+            cold: self.cold,
+            state: PhantomData::<NEXT>,
+        }
+    }
+
+    /// Write all data of `plane` provided by `source`, returns the number
+    /// of bytes written
+    fn write_source<SPI: SpiDevice>(
         &mut self,
         spi: &mut SPI,
-        cmd: Command,
+        source: &mut impl FrameSource,
+        plane: Plane,
+    ) -> Result<usize, EpdError<SPI, DC, RST>> {
+        let mut buffer = [0; SOURCE_CHUNK_LEN];
+        let mut written = 0;
+        loop {
+            let len = source.next_chunk(plane, &mut buffer).min(buffer.len());
+            if len == 0 {
+                return Ok(written);
+            }
+            self.apply_polarity(plane, &mut buffer[..len]);
+            self.interface.write(spi, &buffer[..len])?;
+            written += len;
+        }
+    }
+
+    /// Write all data of `plane` provided by `reader` in chunks, returns
+    /// the number of bytes written
+    #[cfg(feature = "std")]
+    fn write_reader<SPI: SpiDevice>(
+        &mut self,
+        spi: &mut SPI,
+        mut reader: impl std::io::Read,
+        plane: Plane,
+    ) -> Result<usize, EpdError<SPI, DC, RST>> {
+        let mut buffer = [0; 4096];
+        let mut written = 0;
+        loop {
+            let len = match reader.read(&mut buffer) {
+                Ok(0) => return Ok(written),
+                Ok(len) => len,
+                Err(error) if error.kind() == std::io::ErrorKind::Interrupted => continue,
+                Err(error) => return Err(Error::Io(error)),
+            };
+            self.apply_polarity(plane, &mut buffer[..len]);
+            self.interface.write(spi, &buffer[..len])?;
+            written += len;
+        }
+    }
+
+    /// Write `data` of `plane` with the polarity of the panel, inverted
+    /// data is sent in chunks of a small buffer
+    fn write_plane<SPI: SpiDevice>(
+        &mut self,
+        spi: &mut SPI,
+        plane: Plane,
         data: &[u8],
     ) -> Result<(), EpdError<SPI, DC, RST>> {
-        self.dc.set_low().map_err(Error::GpioDc)?;
-        self.write(spi, &[cmd as u8])?;
-        self.dc.set_high().map_err(Error::GpioDc)?;
-        self.write(spi, data)?;
+        if self.registers.polarity[plane as usize] == BitPolarity::Normal {
+            return self.interface.write(spi, data);
+        }
+        let mut buffer = [0; SOURCE_CHUNK_LEN];
+        for chunk in data.chunks(SOURCE_CHUNK_LEN) {
+            let buffer = &mut buffer[..chunk.len()];
+            buffer.copy_from_slice(chunk);
+            self.apply_polarity(plane, buffer);
+            self.interface.write(spi, buffer)?;
+        }
         Ok(())
     }
 
-    fn write(&mut self, spi: &mut SPI, data: &[u8]) -> Result<(), EpdError<SPI, DC, RST>> {
-        if self.spi_chunk_size > 0 {
-            for chunk in data.chunks(self.spi_chunk_size) {
-                spi.write(chunk).map_err(Error::Spi)?;
+    /// Write `len` bytes of white pixels of `plane`
+    fn write_white<SPI: SpiDevice>(
+        &mut self,
+        spi: &mut SPI,
+        plane: Plane,
+        len: usize,
+    ) -> Result<(), EpdError<SPI, DC, RST>> {
+        match self.registers.polarity[plane as usize] {
+            BitPolarity::Normal => self.interface.write_zeros(spi, len),
+            BitPolarity::Inverted => self.interface.write_repeated(spi, 0xff, len),
+        }
+    }
+
+    /// Invert the bytes of `plane` in place if the panel expects it
+    fn apply_polarity(&self, plane: Plane, data: &mut [u8]) {
+        if self.registers.polarity[plane as usize] == BitPolarity::Inverted {
+            for byte in data {
+                *byte = !byte;
             }
+        }
+    }
+
+    /// Call `hook` with the lifecycle hooks if set
+    fn event(&self, hook: impl FnOnce(&'static dyn EpdEvents)) {
+        if let Some(events) = self.events {
+            hook(events);
+        }
+    }
+
+    fn check_refresh_guard<SPI: SpiDevice>(&self) -> Result<(), EpdError<SPI, DC, RST>> {
+        if self.refresh_guard.is_some_and(|allow| !allow()) {
+            return Err(Error::RefreshVetoed);
+        }
+        Ok(())
+    }
+
+    /// Wait or fail if the last refresh started less than the minimum
+    /// interval of the rate limit ago
+    fn check_rate_limit<SPI: SpiDevice>(
+        &mut self,
+        delay: &mut impl DelayNs,
+    ) -> Result<(), EpdError<SPI, DC, RST>> {
+        let remaining = self.refresh_blocked_ms();
+        if remaining == 0 {
+            return Ok(());
+        }
+        if self
+            .rate_limit
+            .is_some_and(|limit| limit.policy == RateLimitPolicy::Reject)
+        {
+            return Err(Error::TooSoon);
+        }
+        self.pause(delay, remaining);
+        Ok(())
+    }
+
+    /// Wait for `ms` milliseconds, feeding the watchdog at least once per second
+    fn pause(&self, delay: &mut impl DelayNs, ms: u32) {
+        let mut remaining = ms;
+        while remaining > 0 {
+            let step = remaining.min(1000);
+            delay.delay_ms(step);
+            remaining -= step;
+            if let Some((feed, _)) = self.watchdog_hook {
+                feed();
+            }
+        }
+    }
+
+    /// Send the refresh command and remember its time for the rate limit
+    fn start_display_refresh<SPI: SpiDevice>(
+        &mut self,
+        spi: &mut SPI,
+    ) -> Result<(), EpdError<SPI, DC, RST>> {
+        self.event(EpdEvents::on_refresh_start);
+        self.interface.send_data(spi, Command::Refresh, &[0x0])?;
+        if let Some(limit) = self.rate_limit {
+            self.last_refresh_ms = Some(limit.clock.now_ms());
+        }
+        Ok(())
+    }
+
+    pub(crate) fn init_registers<SPI: SpiDevice>(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut impl DelayNs,
+    ) -> Result<(), EpdError<SPI, DC, RST>> {
+        self.interface
+            .dc
+            .set_high()
+            .map_err(|error| Error::GpioDc { error, phase: None })?;
+        self.interface.reset(delay).map_err(Error::GpioRst)?;
+        self.configure(spi, delay)?;
+        self.event(EpdEvents::on_init);
+        Ok(())
+    }
+
+    /// Soft reset the COG and send all registers set during `init`
+    fn configure<SPI: SpiDevice>(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut impl DelayNs,
+    ) -> Result<(), EpdError<SPI, DC, RST>> {
+        self.send_soft_reset(spi, delay)?;
+        self.powered = false;
+        let (variant_psr, variant_table) = self.registers.panel.of_variant(self.registers.variant);
+        if self.registers.internal_temperature {
+            let measured = self.measure_temperature(spi, delay)?;
+            self.cold = measured < 0;
+            let table = self.registers.temperature_table.unwrap_or(variant_table);
+            let setting = temperature_setting(table, measured);
+            // the register takes whole degrees, negative values are only
+            // supported by wide temperature panels
+            let measured = if self.registers.variant == PanelVariant::WideTemperature {
+                measured.to_be_bytes()[0]
+            } else {
+                measured.max(0).unsigned_abs()
+            };
+            let temperature = setting
+                .and_then(|setting| setting.input_temperature)
+                .unwrap_or(measured);
+            let active = setting.map_or(self.registers.panel.active_temperature, |setting| {
+                setting.active_temperature
+            });
+            self.interface
+                .send_data(spi, Command::InputTemperature, &[temperature])?;
+            self.interface
+                .send_data(spi, Command::ActiveTemperature, &[active])?;
         } else {
-            spi.write(data).map_err(Error::Spi)?;
+            // the actual temperature is unknown
+            self.cold = self.registers.variant == PanelVariant::WideTemperature;
+            let panel = self.registers.panel;
+            self.interface
+                .send_data(spi, Command::InputTemperature, &[panel.input_temperature])?;
+            self.interface.send_data(
+                spi,
+                Command::ActiveTemperature,
+                &[panel.active_temperature],
+            )?;
+        }
+        let psr = self.registers.psr.unwrap_or(variant_psr);
+        self.interface.send_data(spi, Command::Psr, &psr)?;
+        if let Some(power_setting) = self.registers.power_setting {
+            self.interface
+                .send_data(spi, Command::PowerSetting, &power_setting)?;
+        }
+        if let Some(cdi) = self.registers.cdi {
+            self.interface.send_data(spi, Command::Cdi, &[cdi])?;
+        }
+        if let Some(tcon) = self.registers.tcon.or(self.registers.panel.tcon) {
+            self.interface.send_data(spi, Command::Tcon, &[tcon])?;
+        }
+        if let Some(pll) = self.registers.pll {
+            self.interface.send_data(spi, Command::Pll, &[pll])?;
+        }
+        if let Some(phases) = self.registers.booster_soft_start {
+            self.interface
+                .send_data(spi, Command::BoosterSoftStart, &phases)?;
         }
         Ok(())
     }
 
-    fn soft_reset(
+    /// Measure the temperature with the internal sensor, the first byte
+    /// of the result contains the whole degrees as signed value
+    fn measure_temperature<SPI: SpiDevice>(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut impl DelayNs,
+    ) -> Result<i8, EpdError<SPI, DC, RST>> {
+        let mut temperature = [0; 2];
+        self.interface
+            .send_command(spi, Command::TemperatureCalibration)?;
+        self.wait_busy(spi, delay)?;
+        // sending the command again would start a new measurement
+        self.interface.read(spi, &mut temperature)?;
+        Ok(i8::from_be_bytes([temperature[0]]))
+    }
+
+    fn power_on<SPI: SpiDevice>(
         &mut self,
         spi: &mut SPI,
-        delay: &mut DELAY,
+        delay: &mut impl DelayNs,
     ) -> Result<(), EpdError<SPI, DC, RST>> {
-        self.send_data(spi, Command::Psr, REG_DATA_SOFT_RESET)?;
-        self.wait_busy(delay)?;
+        if self.keep_powered && self.powered {
+            return Ok(());
+        }
+        self.interface.send_data(spi, Command::PowerOn, &[0x0])?;
+        self.wait_busy(spi, delay)?;
+        self.powered = true;
         Ok(())
     }
 
-    fn display_refresh(
+    fn send_soft_reset<SPI: SpiDevice>(
         &mut self,
         spi: &mut SPI,
-        delay: &mut DELAY,
+        delay: &mut impl DelayNs,
     ) -> Result<(), EpdError<SPI, DC, RST>> {
-        self.send_data(spi, Command::Refresh, &[0x0])?;
-        self.wait_busy(delay)?;
+        self.interface
+            .send_data(spi, Command::Psr, &[self.registers.panel.soft_reset])?;
+        self.wait_busy(spi, delay)?;
         Ok(())
     }
 
-    fn wait_busy(&mut self, delay: &mut DELAY) -> Result<(), EpdError<SPI, DC, RST>> {
+    /// Refresh and return its duration in ms
+    fn display_refresh<SPI: SpiDevice>(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut impl DelayNs,
+    ) -> Result<u32, EpdError<SPI, DC, RST>> {
+        self.start_display_refresh(spi)?;
+        let progress_hook = self.progress_hook;
+        if let Some((report, _)) = progress_hook {
+            report(0);
+        }
+        let elapsed_ms = self.wait_busy_reporting(spi, delay, progress_hook)?;
+        if let Some((report, _)) = progress_hook {
+            report(100);
+        }
+        if self.registers.film == Film::AuroraMa {
+            self.interface.send_data(spi, Command::PowerOff, &[0x0])?;
+            self.wait_busy(spi, delay)?;
+            self.powered = false;
+        }
+        self.event(|events| events.on_refresh_end(elapsed_ms));
+        Ok(elapsed_ms)
+    }
+
+    fn wait_busy<SPI: SpiDevice>(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut impl DelayNs,
+    ) -> Result<(), EpdError<SPI, DC, RST>> {
+        self.wait_busy_reporting(spi, delay, None)?;
+        Ok(())
+    }
+
+    /// The COG is busy, as signalled by the busy pin or read from the
+    /// status register (see `set_busy_source`)
+    fn is_busy<SPI: SpiDevice>(&mut self, spi: &mut SPI) -> Result<bool, EpdError<SPI, DC, RST>> {
+        match self.busy_source {
+            BusySource::Pin => Ok(self.interface.is_busy()),
+            BusySource::Status => {
+                let mut status = [0];
+                self.interface
+                    .read_data(spi, Command::GetStatus, &mut status)?;
+                // bit 0 is set while the COG is idle, see `PanelStatus`
+                Ok(status[0] & 0x01 == 0)
+            }
+        }
+    }
+
+    /// Wait for the busy signal, reporting the progress estimated from
+    /// the elapsed time and the typical duration with `progress_hook`.
+    /// Returns the time waited in ms.
+    fn wait_busy_reporting<SPI: SpiDevice>(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut impl DelayNs,
+        progress_hook: Option<(fn(u8), u32)>,
+    ) -> Result<u32, EpdError<SPI, DC, RST>> {
         let delay_ms = 1;
-        let mut timeout = TIMEOUT_MS;
-        while self.busy.is_low().unwrap() && timeout > 0 {
-            delay.delay_ms(delay_ms);
+        let mut progress = 0;
+        let mut timeout = if self.cold && self.registers.variant == PanelVariant::WideTemperature {
+            TIMEOUT_COLD_MS
+        } else {
+            TIMEOUT_MS
+        };
+        let mut elapsed_ms = 0;
+        while self.is_busy(spi)? && timeout > 0 {
+            if self.abort_hook.is_some_and(|abort| abort()) {
+                return Err(Error::Aborted);
+            }
+            match self.sleep_hook {
+                Some(sleep) => sleep(),
+                None => delay.delay_ms(delay_ms),
+            }
             timeout -= i32::try_from(delay_ms).unwrap();
+            elapsed_ms += delay_ms;
+            if let Some((feed, interval_ms)) = self.watchdog_hook {
+                if elapsed_ms % interval_ms == 0 {
+                    feed();
+                }
+            }
+            if let Some((report, typical_ms)) = progress_hook {
+                let estimate = u64::from(elapsed_ms) * 100 / u64::from(typical_ms);
+                let estimate = u8::try_from(estimate.min(99)).unwrap_or(99);
+                if estimate != progress {
+                    progress = estimate;
+                    report(progress);
+                }
+            }
         }
         if timeout <= 0 {
             Err(Error::Timeout)
         } else {
-            Ok(())
+            Ok(elapsed_ms)
         }
     }
 }
 
+/// Running refresh started with `Epd::start_refresh`
+pub struct RefreshHandle<'a, SPI, BUSY, DC, RST> {
+    epd: &'a mut Epd<Active, BUSY, DC, RST>,
+    /// SPI device of the refresh, used to read the status register if
+    /// there is no busy pin
+    spi: &'a mut SPI,
+}
+
+impl<SPI, BUSY, DC, RST> RefreshHandle<'_, SPI, BUSY, DC, RST>
+where
+    SPI: SpiDevice,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+{
+    /// The refresh is complete (busy signal released)
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the status register cannot
+    /// be read with [`BusySource::Status`].
+    pub fn is_done(&mut self) -> Result<bool, EpdError<SPI, DC, RST>> {
+        Ok(!self.epd.is_busy(&mut *self.spi)?)
+    }
+
+    /// Block until the refresh is complete, using the hooks of the driver
+    /// like `refresh` does. The progress hook reports the progress from
+    /// the call of `wait`, time spent before is not included.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error on a timeout or if waiting was
+    /// aborted by the abort hook.
+    pub fn wait(self, delay: &mut impl DelayNs) -> Result<(), EpdError<SPI, DC, RST>> {
+        let progress_hook = self.epd.progress_hook;
+        if let Some((report, _)) = progress_hook {
+            report(0);
+        }
+        let elapsed_ms = self
+            .epd
+            .wait_busy_reporting(self.spi, delay, progress_hook)?;
+        if let Some((report, _)) = progress_hook {
+            report(100);
+        }
+        self.epd.event(|events| events.on_refresh_end(elapsed_ms));
+        Ok(())
+    }
+}
+
+/// Register data for the partial window command: horizontal start and end
+/// (byte aligned), vertical start and end, each as big endian 16 bit values,
+/// followed by the scan setting (scan inside and outside of the window).
+#[cfg(feature = "graphics")]
+#[allow(clippy::cast_sign_loss, clippy::cast_possible_truncation)]
+fn partial_window_data(window: &Rectangle) -> [u8; 9] {
+    let top_left = window.top_left;
+    let bottom_right = window.bottom_right().unwrap_or(top_left);
+    let x_start = (top_left.x as u16) & !0x07;
+    let x_end = (bottom_right.x as u16) | 0x07;
+    let y_start = top_left.y as u16;
+    let y_end = bottom_right.y as u16;
+    let [x_start_h, x_start_l] = x_start.to_be_bytes();
+    let [x_end_h, x_end_l] = x_end.to_be_bytes();
+    let [y_start_h, y_start_l] = y_start.to_be_bytes();
+    let [y_end_h, y_end_l] = y_end.to_be_bytes();
+    [
+        x_start_h, x_start_l, x_end_h, x_end_l, y_start_h, y_start_l, y_end_h, y_end_l, 0x01,
+    ]
+}
+
 /// SPI mode needed for EPD driver
 /// Mode0: CPOL 0, CPHA 0
 pub const SPI_MODE: embedded_hal::spi::Mode = embedded_hal::spi::Mode {
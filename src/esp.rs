@@ -0,0 +1,40 @@
+//! Wiring of the driver with the SPI and GPIO drivers of esp-hal (ESP32-C3/S3)
+
+use embedded_hal_bus::spi::ExclusiveDevice;
+use esp_hal::{
+    delay::Delay,
+    gpio::{Input, Output},
+    spi::master::Spi,
+    Blocking,
+};
+
+use crate::{Epd, Inactive};
+
+/// SPI device of the e-paper: the SPI bus of esp-hal with the chip select
+/// driven as GPIO by `embedded-hal-bus`
+pub type EspSpiDevice<'d> = ExclusiveDevice<Spi<'d, Blocking>, Output<'d>, Delay>;
+
+/// E-paper driver with the GPIO types of esp-hal
+pub type EspEpd<'d> = Epd<Inactive, Input<'d>, Output<'d>, Output<'d>>;
+
+impl<'d> Epd<Inactive, Input<'d>, Output<'d>, Output<'d>> {
+    /// Create the driver and its SPI device from the peripherals of esp-hal.
+    /// The `Spi` of esp-hal is a bus, not a device, so it is wrapped together
+    /// with `cs` into an [`EspSpiDevice`]. Configure the bus with mode 0 and
+    /// at most 8 MHz, and do not attach a CS pin to it with `with_cs`: the
+    /// hardware chip select may be released between the FIFO transfers of
+    /// 64 bytes, which splits the buffer data and corrupts the frame. `cs`,
+    /// `dc` and `rst` should be created with `Level::High`. Call `init` with
+    /// the returned SPI device afterwards.
+    #[must_use]
+    pub fn new_esp(
+        spi: Spi<'d, Blocking>,
+        cs: Output<'d>,
+        busy: Input<'d>,
+        dc: Output<'d>,
+        rst: Output<'d>,
+    ) -> (Self, EspSpiDevice<'d>) {
+        let spi_device = ExclusiveDevice::new(spi, cs, Delay::new());
+        (Self::new(busy, dc, rst, 0), spi_device)
+    }
+}
@@ -0,0 +1,30 @@
+//! Instrumentation of the driver lifecycle, e.g. for metrics or tracing
+
+/// Hooks called by [`Epd`](crate::Epd) at the steps of its lifecycle, see
+/// `Epd::set_events`. All methods do nothing by default, so only the
+/// steps of interest have to be implemented. The end hooks are called
+/// only if the step succeeded. The hooks are called with a shared
+/// reference, use interior mutability (e.g. atomics) to record data.
+/// Implementations have to be `Sync` (as every `static` is) to keep the
+/// driver `Send`.
+pub trait EpdEvents: Sync {
+    /// The COG was reset and configured (`init`, `reset`)
+    fn on_init(&self) {}
+
+    /// The transfer of a frame to the COG starts
+    fn on_upload_start(&self) {}
+
+    /// The transfer of a frame to the COG is complete
+    fn on_upload_end(&self) {}
+
+    /// A refresh of the panel starts
+    fn on_refresh_start(&self) {}
+
+    /// A refresh of the panel is complete. `elapsed_ms` is the time
+    /// waited for the busy signal. For refreshes started with
+    /// `start_refresh` this is only called by `RefreshHandle::wait`.
+    fn on_refresh_end(&self, _elapsed_ms: u32) {}
+
+    /// The charge pumps were powered off (`power_off`)
+    fn on_power_off(&self) {}
+}
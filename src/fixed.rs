@@ -0,0 +1,95 @@
+//! Rotation fixed at compile time, for fast rendering on small cores
+
+use embedded_graphics::{
+    draw_target::DrawTarget,
+    geometry::{OriginDimensions, Size},
+    primitives::Rectangle,
+    Pixel,
+};
+
+use crate::{BufferStorage, Display, DisplayRotation, TriColor};
+
+/// A [`Display`] as draw target with the rotation given by `DEGREES`
+/// (0, 90, 180 or 270 clockwise) instead of its rotation setting. The
+/// rotation is resolved at compile time, so no rotation is matched per
+/// pixel. Transparent color and draw mode of the display are used as
+/// usual.
+pub struct FixedRotation<
+    'a,
+    const SIZE_V: u32,
+    const SIZE_H: u32,
+    const IMAGE_SIZE: usize,
+    B,
+    const DEGREES: u16,
+> {
+    display: &'a mut Display<SIZE_V, SIZE_H, IMAGE_SIZE, B>,
+}
+
+impl<
+        const SIZE_V: u32,
+        const SIZE_H: u32,
+        const IMAGE_SIZE: usize,
+        B: BufferStorage,
+        const DEGREES: u16,
+    > FixedRotation<'_, SIZE_V, SIZE_H, IMAGE_SIZE, B, DEGREES>
+{
+    /// Rotation given by `DEGREES`, other angles are a compile error
+    pub const ROTATION: DisplayRotation = match DEGREES {
+        0 => DisplayRotation::Rotate0,
+        90 => DisplayRotation::Rotate90,
+        180 => DisplayRotation::Rotate180,
+        270 => DisplayRotation::Rotate270,
+        _ => panic!("rotation must be 0, 90, 180 or 270 degrees"),
+    };
+}
+
+impl<const SIZE_V: u32, const SIZE_H: u32, const IMAGE_SIZE: usize, B: BufferStorage>
+    Display<SIZE_V, SIZE_H, IMAGE_SIZE, B>
+{
+    /// Draw target with a rotation fixed at compile time, e.g.
+    /// `display.fixed_rotation::<90>()`, see [`FixedRotation`]
+    pub fn fixed_rotation<const DEGREES: u16>(
+        &mut self,
+    ) -> FixedRotation<'_, SIZE_V, SIZE_H, IMAGE_SIZE, B, DEGREES> {
+        let _ = FixedRotation::<SIZE_V, SIZE_H, IMAGE_SIZE, B, DEGREES>::ROTATION;
+        FixedRotation { display: self }
+    }
+}
+
+impl<
+        const SIZE_V: u32,
+        const SIZE_H: u32,
+        const IMAGE_SIZE: usize,
+        B: BufferStorage,
+        const DEGREES: u16,
+    > OriginDimensions for FixedRotation<'_, SIZE_V, SIZE_H, IMAGE_SIZE, B, DEGREES>
+{
+    fn size(&self) -> Size {
+        Display::<SIZE_V, SIZE_H, IMAGE_SIZE, B>::rotated_size(Self::ROTATION)
+    }
+}
+
+impl<
+        const SIZE_V: u32,
+        const SIZE_H: u32,
+        const IMAGE_SIZE: usize,
+        B: BufferStorage,
+        const DEGREES: u16,
+    > DrawTarget for FixedRotation<'_, SIZE_V, SIZE_H, IMAGE_SIZE, B, DEGREES>
+{
+    type Color = TriColor;
+    type Error = core::convert::Infallible;
+
+    fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Pixel<Self::Color>>,
+    {
+        self.display.draw_rotated(Self::ROTATION, pixels);
+        Ok(())
+    }
+
+    fn fill_solid(&mut self, area: &Rectangle, color: Self::Color) -> Result<(), Self::Error> {
+        self.display.fill_rotated(Self::ROTATION, area, color);
+        Ok(())
+    }
+}
@@ -0,0 +1,110 @@
+//! Adapter for flush callbacks of GUI libraries like LVGL (lvgl-rs)
+
+use embedded_graphics::{
+    draw_target::DrawTarget,
+    geometry::Point,
+    pixelcolor::{PixelColor, Rgb888},
+    primitives::{PointsIter, Rectangle},
+    Pixel,
+};
+use embedded_hal::{delay::DelayNs, digital::InputPin, digital::OutputPin, spi::SpiDevice};
+
+use crate::{driver::EpdError, Active, ColorMapper, DisplayBuffer, Epd, TriColor};
+
+/// Collects the flushed areas of a GUI library in a display buffer and
+/// batches them into a single e-paper refresh. Colors are converted with
+/// a [`ColorMapper`].
+///
+/// With lvgl-rs, call `flush_pixels(refresh.as_pixels())` (or `flush_area`
+/// with the area and colors of the refresh) in the flush callback and
+/// `update` in the main loop once LVGL has finished rendering.
+pub struct FlushAdapter<D, M> {
+    display: D,
+    mapper: M,
+    dirty: bool,
+}
+
+impl<D, M> FlushAdapter<D, M>
+where
+    D: DrawTarget<Color = TriColor> + DisplayBuffer,
+    M: ColorMapper,
+{
+    pub fn new(display: D, mapper: M) -> Self {
+        Self {
+            display,
+            mapper,
+            dirty: false,
+        }
+    }
+
+    /// Draw flushed pixels into the display buffer
+    pub fn flush_pixels<C, I>(&mut self, pixels: I)
+    where
+        C: PixelColor + Into<Rgb888>,
+        I: IntoIterator<Item = Pixel<C>>,
+    {
+        let mapper = &self.mapper;
+        let pixels = pixels
+            .into_iter()
+            .map(|Pixel(point, color)| Pixel(point, mapper.map(color.into(), point)));
+        // drawing into a display buffer cannot fail
+        let _ = self.display.draw_iter(pixels);
+        self.dirty = true;
+    }
+
+    /// Draw the colors of a flushed area (row by row) into the display buffer
+    pub fn flush_area<C, I>(&mut self, area: Rectangle, colors: I)
+    where
+        C: PixelColor + Into<Rgb888>,
+        I: IntoIterator<Item = C>,
+    {
+        let pixels = area
+            .points()
+            .zip(colors)
+            .map(|(point, color): (Point, C)| Pixel(point, color));
+        self.flush_pixels(pixels);
+    }
+
+    /// Returns `true` if something was flushed since the last update
+    #[must_use]
+    pub fn is_dirty(&self) -> bool {
+        self.dirty
+    }
+
+    #[must_use]
+    pub fn display(&self) -> &D {
+        &self.display
+    }
+
+    pub fn display_mut(&mut self) -> &mut D {
+        &mut self.display
+    }
+
+    /// Show the display buffer on the e-paper if something was flushed since
+    /// the last update. Returns `true` if the e-paper was updated.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if there is an error
+    /// with the GPIOs or the SPI device.
+    pub fn update<SPI, BUSY, DC, RST, DELAY>(
+        &mut self,
+        epd: &mut Epd<Active, BUSY, DC, RST>,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+    ) -> Result<bool, EpdError<SPI, DC, RST>>
+    where
+        SPI: SpiDevice,
+        BUSY: InputPin,
+        DC: OutputPin,
+        RST: OutputPin,
+        DELAY: DelayNs,
+    {
+        if !self.dirty {
+            return Ok(false);
+        }
+        epd.update(&self.display, spi, delay)?;
+        self.dirty = false;
+        Ok(true)
+    }
+}
@@ -0,0 +1,65 @@
+//! Bold monospace fonts for e-paper. The stock fonts of
+//! `embedded_graphics` are too thin to be legible at a distance, these
+//! fonts are rendered from `DejaVu Sans Mono Bold` with thickened strokes
+//! (see `fonts/LICENSE.txt`). All fonts contain the ASCII characters.
+
+use embedded_graphics::{
+    geometry::Size,
+    image::ImageRaw,
+    mono_font::{mapping::ASCII, DecorationDimensions, MonoFont},
+};
+
+/// 8x16 pixel bold monospace font
+pub const EPD_FONT_8X16: MonoFont = MonoFont {
+    image: ImageRaw::new(include_bytes!("../fonts/epd_8x16.raw"), 128),
+    glyph_mapping: &ASCII,
+    character_size: Size::new(8, 16),
+    character_spacing: 0,
+    baseline: 13,
+    underline: DecorationDimensions::new(13 + 2, 1),
+    strikethrough: DecorationDimensions::new(16 / 2, 1),
+};
+
+/// 12x24 pixel bold monospace font
+pub const EPD_FONT_12X24: MonoFont = MonoFont {
+    image: ImageRaw::new(include_bytes!("../fonts/epd_12x24.raw"), 192),
+    glyph_mapping: &ASCII,
+    character_size: Size::new(12, 24),
+    character_spacing: 0,
+    baseline: 19,
+    underline: DecorationDimensions::new(19 + 2, 1),
+    strikethrough: DecorationDimensions::new(24 / 2, 1),
+};
+
+/// 17x32 pixel bold monospace font
+pub const EPD_FONT_17X32: MonoFont = MonoFont {
+    image: ImageRaw::new(include_bytes!("../fonts/epd_17x32.raw"), 272),
+    glyph_mapping: &ASCII,
+    character_size: Size::new(17, 32),
+    character_spacing: 0,
+    baseline: 26,
+    underline: DecorationDimensions::new(26 + 3, 2),
+    strikethrough: DecorationDimensions::new(32 / 2, 2),
+};
+
+/// 25x48 pixel bold monospace font
+pub const EPD_FONT_25X48: MonoFont = MonoFont {
+    image: ImageRaw::new(include_bytes!("../fonts/epd_25x48.raw"), 400),
+    glyph_mapping: &ASCII,
+    character_size: Size::new(25, 48),
+    character_spacing: 0,
+    baseline: 38,
+    underline: DecorationDimensions::new(38 + 4, 3),
+    strikethrough: DecorationDimensions::new(48 / 2, 3),
+};
+
+/// 33x64 pixel bold monospace font
+pub const EPD_FONT_33X64: MonoFont = MonoFont {
+    image: ImageRaw::new(include_bytes!("../fonts/epd_33x64.raw"), 528),
+    glyph_mapping: &ASCII,
+    character_size: Size::new(33, 64),
+    character_spacing: 0,
+    baseline: 51,
+    underline: DecorationDimensions::new(51 + 5, 4),
+    strikethrough: DecorationDimensions::new(64 / 2, 4),
+};
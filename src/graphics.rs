@@ -1,13 +1,20 @@
 //! Specific display buffers for each EPDs and `embedded_graphics` related implementations
 
-use core::cmp::{max, min};
+use crate::{transport::Crc, BufferStorage, DisplayBuffer, Plane};
+use core::{
+    cmp::{max, min},
+    hash::{Hash, Hasher},
+    mem::MaybeUninit,
+    ptr,
+};
 use embedded_graphics::{
     draw_target::DrawTarget,
-    geometry::{OriginDimensions, Size},
+    geometry::{Dimensions, OriginDimensions, Point, Size},
     pixelcolor::{
         raw::{RawData, RawU2},
         BinaryColor, PixelColor, Rgb888, RgbColor,
     },
+    primitives::{PointsIter, Rectangle},
     Pixel,
 };
 
@@ -46,6 +53,27 @@ impl From<BinaryColor> for TriColor {
     }
 }
 
+impl From<TriColor> for BinaryColor {
+    /// Lossy conversion, red is mapped to `On` like black
+    /// (see `TriColor::to_binary` for other mappings)
+    fn from(c: TriColor) -> Self {
+        c.to_binary(BinaryColor::On)
+    }
+}
+
+impl TriColor {
+    /// Convert to `BinaryColor` with black as `On`, white as `Off`
+    /// and red as `red`
+    #[must_use]
+    pub fn to_binary(self, red: BinaryColor) -> BinaryColor {
+        match self {
+            TriColor::White => BinaryColor::Off,
+            TriColor::Black => BinaryColor::On,
+            TriColor::Red => red,
+        }
+    }
+}
+
 impl From<TriColor> for Rgb888 {
     fn from(b: TriColor) -> Self {
         match b {
@@ -72,8 +100,30 @@ impl From<Rgb888> for TriColor {
     }
 }
 
+#[cfg(feature = "epd-waveshare")]
+impl From<TriColor> for epd_waveshare::color::TriColor {
+    fn from(color: TriColor) -> Self {
+        match color {
+            TriColor::White => Self::White,
+            TriColor::Black => Self::Black,
+            TriColor::Red => Self::Chromatic,
+        }
+    }
+}
+
+#[cfg(feature = "epd-waveshare")]
+impl From<epd_waveshare::color::TriColor> for TriColor {
+    fn from(color: epd_waveshare::color::TriColor) -> Self {
+        match color {
+            epd_waveshare::color::TriColor::White => Self::White,
+            epd_waveshare::color::TriColor::Black => Self::Black,
+            epd_waveshare::color::TriColor::Chromatic => Self::Red,
+        }
+    }
+}
+
 /// Display rotation, only 90° increments supported
-#[derive(Clone, Copy, Default)]
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Default)]
 pub enum DisplayRotation {
     /// No rotation
     #[default]
@@ -86,23 +136,226 @@ pub enum DisplayRotation {
     Rotate270,
 }
 
-pub trait DisplayBuffer {
-    fn get_buffer_black(&self) -> &[u8];
-    fn get_buffer_red(&self) -> &[u8];
+impl DisplayRotation {
+    /// Rotation in which the panel with `size_v` rows of `size_h` pixels
+    /// is normally mounted, as given by the `landscape` preset of
+    /// [`PANEL_MODELS`](crate::PANEL_MODELS). Panels without a preset,
+    /// e.g. custom sizes of `display_type!`, are used as they are.
+    #[must_use]
+    pub const fn natural(size_v: u32, size_h: u32) -> DisplayRotation {
+        if crate::PanelModel::is_landscape(size_h, size_v) {
+            DisplayRotation::Rotate90
+        } else {
+            DisplayRotation::Rotate0
+        }
+    }
+
+    /// Rotation resulting from applying `self` followed by `other`
+    #[must_use]
+    pub fn then(self, other: DisplayRotation) -> DisplayRotation {
+        match (self as u8 + other as u8) % 4 {
+            0 => DisplayRotation::Rotate0,
+            1 => DisplayRotation::Rotate90,
+            2 => DisplayRotation::Rotate180,
+            _ => DisplayRotation::Rotate270,
+        }
+    }
+}
+
+/// How pixels drawn with `embedded_graphics` are combined with the
+/// buffer, see `Display::set_draw_mode`
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum DrawMode {
+    /// Pixels are replaced by the drawn color
+    #[default]
+    Normal,
+    /// Black and red pixels toggle the pixel in the black plane, white
+    /// pixels and the red plane are left untouched. Red pixels of the
+    /// buffer are not toggled, so the planes stay mutually exclusive.
+    /// Drawing the same shape again restores the content, e.g. for cursors
+    /// and selections.
+    Xor,
+}
+
+/// Combination of two displays, see `Display::overlay`
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum OverlayMode {
+    /// Non-white pixels of the other display are drawn on top
+    Or,
+    /// Only pixels with the same color in both displays are kept,
+    /// all others become white
+    And,
+    /// The content is replaced by the other display
+    Replace,
+}
+
+/// Error of `Display::rotate_buffer`
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum RotateError {
+    /// the scratch buffer of a 90° or 270° rotation of a non-square
+    /// display is shorter than the planes
+    ScratchTooSmall { expected: usize, actual: usize },
+}
+
+/// Patterns for hardware bring-up and production tests, see
+/// `Display::test_pattern`. All patterns cycle through white, black and
+/// red, so both planes are exercised and neighbouring cells always differ.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Pattern {
+    /// Square cells
+    Checkerboard,
+    /// Vertical stripes
+    VStripes,
+    /// Horizontal stripes
+    HStripes,
+    /// Three vertical bars of a third of the width each, the cell size
+    /// is not used
+    ColorBars,
+}
+
+/// Number of pixels of each color, see `Display::stats`
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub struct FrameStats {
+    pub white: u32,
+    pub black: u32,
+    pub red: u32,
+}
+
+impl FrameStats {
+    /// Number of pixels of `color`
+    #[must_use]
+    pub fn count(&self, color: TriColor) -> u32 {
+        match color {
+            TriColor::White => self.white,
+            TriColor::Black => self.black,
+            TriColor::Red => self.red,
+        }
+    }
+
+    /// Share of the pixels of `color` in percent (0.0 to 100.0)
+    #[must_use]
+    #[allow(clippy::cast_precision_loss)]
+    pub fn coverage(&self, color: TriColor) -> f32 {
+        let total = self.white + self.black + self.red;
+        if total == 0 {
+            return 0.0;
+        }
+        self.count(color) as f32 * 100.0 / total as f32
+    }
 }
 
 /// Display buffer used for drawing with `embedded_graphics`.
 /// The concrete types are dependent on the size.
 /// Examples: `Display1in54`, `Display2in13`, ...
-pub struct Display<const SIZE_V: u32, const SIZE_H: u32, const IMAGE_SIZE: usize> {
-    buffer_black: [u8; IMAGE_SIZE],
-    buffer_red: [u8; IMAGE_SIZE],
+/// By default the planes are stored in arrays, see [`BufferStorage`]
+/// for other kinds of memory.
+#[derive(Clone)]
+pub struct Display<
+    const SIZE_V: u32,
+    const SIZE_H: u32,
+    const IMAGE_SIZE: usize,
+    B = [u8; IMAGE_SIZE],
+> {
+    buffer_black: B,
+    buffer_red: B,
     rotation: DisplayRotation,
+    transparent: Option<TriColor>,
+    mode: DrawMode,
+}
+
+// Display buffers can be drawn on one thread and sent to another one,
+// checked at compile time
+#[allow(dead_code)]
+fn assert_send_sync<const SIZE_V: u32, const SIZE_H: u32, const IMAGE_SIZE: usize>() {
+    fn check<T: Send + Sync>() {}
+    check::<Display<SIZE_V, SIZE_H, IMAGE_SIZE>>();
+    check::<crate::DoubleDisplay<SIZE_V, SIZE_H, IMAGE_SIZE>>();
+    check::<crate::MonoDisplay<SIZE_V, SIZE_H, IMAGE_SIZE>>();
 }
 
 impl<const SIZE_V: u32, const SIZE_H: u32, const IMAGE_SIZE: usize>
     Display<SIZE_V, SIZE_H, IMAGE_SIZE>
 {
+    /// Empty (white) display in its natural orientation (see
+    /// [`DisplayRotation::natural`]), same as `default()`. As `const fn`
+    /// it can be used to place the buffers in a `static` without
+    /// initializing them on the stack first.
+    #[must_use]
+    pub const fn new() -> Self {
+        let () = Self::GEOMETRY;
+        Self {
+            buffer_black: [0; IMAGE_SIZE],
+            buffer_red: [0; IMAGE_SIZE],
+            rotation: Self::NATURAL_ROTATION,
+            transparent: None,
+            mode: DrawMode::Normal,
+        }
+    }
+
+    /// Initialize a display in uninitialized memory, e.g. a
+    /// `static mut MaybeUninit<Display2in66>` or a buffer in a custom link
+    /// section. The buffers are zeroed in place, so the display is never
+    /// created on the stack (and `.bss` space can be avoided for sections
+    /// which are not zeroed at startup).
+    pub fn init_in_place(slot: &mut MaybeUninit<Self>) -> &mut Self {
+        let () = Self::GEOMETRY;
+        let display = slot.as_mut_ptr();
+        // SAFETY: all fields are written through raw pointers into `slot`
+        // without creating references to uninitialized memory. Zero bytes
+        // are valid values for the `u8` buffers.
+        unsafe {
+            ptr::addr_of_mut!((*display).buffer_black).write_bytes(0, 1);
+            ptr::addr_of_mut!((*display).buffer_red).write_bytes(0, 1);
+            ptr::addr_of_mut!((*display).rotation).write(Self::NATURAL_ROTATION);
+            ptr::addr_of_mut!((*display).transparent).write(None);
+            ptr::addr_of_mut!((*display).mode).write(DrawMode::Normal);
+            slot.assume_init_mut()
+        }
+    }
+}
+
+impl<const SIZE_V: u32, const SIZE_H: u32, const IMAGE_SIZE: usize, B: BufferStorage>
+    Display<SIZE_V, SIZE_H, IMAGE_SIZE, B>
+{
+    /// Number of bytes per row (in panel coordinates) of a buffer plane
+    pub const BYTES_PER_ROW: usize = SIZE_H as usize / 8;
+
+    /// Rotation of a new display, the orientation in which the panel is
+    /// normally mounted (see `DisplaySpec::NATURAL_ROTATION`). Change it
+    /// with `set_rotation`.
+    pub const NATURAL_ROTATION: DisplayRotation = DisplayRotation::natural(SIZE_V, SIZE_H);
+
+    /// Evaluated when a display is created, so a custom size with an
+    /// `IMAGE_SIZE` not matching rows × bytes per row (or a width which is
+    /// not a multiple of 8) is a compile error instead of an index panic
+    pub(crate) const GEOMETRY: () = assert!(
+        IMAGE_SIZE == image_size(SIZE_V, SIZE_H),
+        "IMAGE_SIZE does not match the display size, define the type with `display_type!`"
+    );
+
+    /// Display using the given memory for the planes. The content of the
+    /// memory is kept.
+    ///
+    /// # Panics
+    ///
+    /// Panics if one of the storages is smaller than `IMAGE_SIZE`.
+    pub fn from_storage(black: B, red: B) -> Self {
+        let () = Self::GEOMETRY;
+        assert!(black.as_slice().len() >= IMAGE_SIZE && red.as_slice().len() >= IMAGE_SIZE);
+        Self {
+            buffer_black: black,
+            buffer_red: red,
+            rotation: Self::NATURAL_ROTATION,
+            transparent: None,
+            mode: DrawMode::Normal,
+        }
+    }
+
+    /// Release the memory of the planes (black, red)
+    pub fn into_storage(self) -> (B, B) {
+        (self.buffer_black, self.buffer_red)
+    }
+
     pub fn set_rotation(&mut self, rotation: DisplayRotation) {
         self.rotation = rotation;
     }
@@ -110,16 +363,604 @@ impl<const SIZE_V: u32, const SIZE_H: u32, const IMAGE_SIZE: usize>
     pub fn rotation(&self) -> DisplayRotation {
         self.rotation
     }
+
+    /// Treat `color` as transparent: pixels of this color drawn with
+    /// `embedded_graphics` leave the buffer untouched, e.g. to stamp icons
+    /// or sprites with a white background onto existing content. This
+    /// also applies to `clear`. `None` (the default) draws all colors.
+    pub fn set_transparent_color(&mut self, color: Option<TriColor>) {
+        self.transparent = color;
+    }
+    #[must_use]
+    pub fn transparent_color(&self) -> Option<TriColor> {
+        self.transparent
+    }
+
+    /// Set how subsequently drawn pixels are combined with the buffer,
+    /// see [`DrawMode`]. Switch back to `DrawMode::Normal` after the draw
+    /// calls which should toggle pixels.
+    pub fn set_draw_mode(&mut self, mode: DrawMode) {
+        self.mode = mode;
+    }
+    #[must_use]
+    pub fn draw_mode(&self) -> DrawMode {
+        self.mode
+    }
+
+    /// Rotate the already rendered content of both planes clockwise by `rotation`.
+    /// The rotation used for subsequent drawing is adjusted accordingly.
+    /// For non-square displays, 90° and 270° rotations clip all pixels
+    /// falling outside of the panel.
+    ///
+    /// 90° and 270° rotations of non-square displays copy each plane into
+    /// `scratch`, which has to hold at least `IMAGE_SIZE` bytes, e.g. a
+    /// static buffer instead of several KB on the stack. Square displays
+    /// and 0° and 180° rotations are rotated in place, so an empty slice is
+    /// enough for them.
+    ///
+    /// # Errors
+    ///
+    /// Returns `RotateError::ScratchTooSmall` and leaves the display
+    /// unchanged if `scratch` is needed and shorter than `IMAGE_SIZE`.
+    #[allow(clippy::cast_possible_wrap)]
+    pub fn rotate_buffer(
+        &mut self,
+        rotation: DisplayRotation,
+        scratch: &mut [u8],
+    ) -> Result<(), RotateError> {
+        match rotation {
+            DisplayRotation::Rotate0 => {}
+            DisplayRotation::Rotate180 => {
+                // rows are byte aligned, so reversing the bytes and their bits is enough
+                for buffer in [&mut self.buffer_black, &mut self.buffer_red] {
+                    let buffer = &mut buffer.as_mut_slice()[..IMAGE_SIZE];
+                    buffer.reverse();
+                    for byte in buffer.iter_mut() {
+                        *byte = byte.reverse_bits();
+                    }
+                }
+            }
+            DisplayRotation::Rotate90 | DisplayRotation::Rotate270 if SIZE_V == SIZE_H => {
+                for buffer in [&mut self.buffer_black, &mut self.buffer_red] {
+                    Self::rotate_square(&mut buffer.as_mut_slice()[..IMAGE_SIZE], rotation);
+                }
+            }
+            DisplayRotation::Rotate90 | DisplayRotation::Rotate270 => {
+                let actual = scratch.len();
+                let source = scratch
+                    .get_mut(..IMAGE_SIZE)
+                    .ok_or(RotateError::ScratchTooSmall {
+                        expected: IMAGE_SIZE,
+                        actual,
+                    })?;
+                for buffer in [&mut self.buffer_black, &mut self.buffer_red] {
+                    let buffer = &mut buffer.as_mut_slice()[..IMAGE_SIZE];
+                    source.copy_from_slice(buffer);
+                    buffer.fill(0);
+                    for y in 0..SIZE_V as i32 {
+                        for x in 0..SIZE_H as i32 {
+                            if let Some((index, mask)) = Self::pixel_position(x, y) {
+                                if source[index] & mask == 0 {
+                                    continue;
+                                }
+                            }
+                            let (x, y) = Self::rotate_point(rotation, x, y);
+                            if let Some((index, mask)) = Self::pixel_position(x, y) {
+                                buffer[index] |= mask;
+                            }
+                        }
+                    }
+                }
+            }
+        }
+        self.rotation = self.rotation.then(rotation);
+        Ok(())
+    }
+
+    /// Rotate a plane of a square display by 90° or 270° in place, moving
+    /// the pixels along the cycles of four positions which the rotation
+    /// maps onto each other
+    #[allow(clippy::cast_possible_wrap)]
+    fn rotate_square(buffer: &mut [u8], rotation: DisplayRotation) {
+        let size = SIZE_H as i32;
+        for y in 0..size / 2 {
+            for x in 0..(size + 1) / 2 {
+                let mut point = (x, y);
+                let mut carried = None;
+                // the fifth step writes the last pixel back to (x, y)
+                for _ in 0..5 {
+                    if let Some((index, mask)) = Self::pixel_position(point.0, point.1) {
+                        let set = buffer[index] & mask != 0;
+                        match carried {
+                            Some(true) => buffer[index] |= mask,
+                            Some(false) => buffer[index] &= !mask,
+                            None => {}
+                        }
+                        carried = Some(set);
+                    }
+                    point = Self::rotate_point(rotation, point.0, point.1);
+                }
+            }
+        }
+    }
+
+    /// Rows of the black plane in panel coordinates, top row first
+    pub fn black_rows(&self) -> core::slice::ChunksExact<'_, u8> {
+        self.buffer_black.as_slice()[..IMAGE_SIZE].chunks_exact(Self::BYTES_PER_ROW)
+    }
+
+    /// Rows of the red plane in panel coordinates, top row first
+    pub fn red_rows(&self) -> core::slice::ChunksExact<'_, u8> {
+        self.buffer_red.as_slice()[..IMAGE_SIZE].chunks_exact(Self::BYTES_PER_ROW)
+    }
+
+    /// Rows of `plane` in the order the controller expects them, each row
+    /// as packed bytes (`SIZE_H / 8`). Sending all rows one after the other
+    /// is the same as sending the whole plane, e.g. for custom drivers or
+    /// transports which upload the frame incrementally.
+    pub fn iter_plane_rows(&self, plane: Plane) -> core::slice::ChunksExact<'_, u8> {
+        match plane {
+            Plane::Black => self.black_rows(),
+            Plane::Red => self.red_rows(),
+        }
+    }
+
+    /// Shift the content by `dx` and `dy` pixels (in rotated coordinates),
+    /// e.g. for ticker or panning effects. Content shifted out of the
+    /// display is lost, the freed area is filled with `fill`. Horizontal
+    /// shifts (in panel coordinates) by multiples of 8 pixels are moving
+    /// whole bytes and are faster.
+    pub fn shift(&mut self, dx: i32, dy: i32, fill: TriColor) {
+        let (dx, dy) = match self.rotation {
+            DisplayRotation::Rotate0 => (dx, dy),
+            DisplayRotation::Rotate90 => (-dy, dx),
+            DisplayRotation::Rotate180 => (-dx, -dy),
+            DisplayRotation::Rotate270 => (dy, -dx),
+        };
+        let fill_black = if fill == TriColor::Black { 0xff } else { 0 };
+        let fill_red = if fill == TriColor::Red { 0xff } else { 0 };
+        Self::shift_plane(
+            &mut self.buffer_black.as_mut_slice()[..IMAGE_SIZE],
+            dx,
+            dy,
+            fill_black,
+        );
+        Self::shift_plane(
+            &mut self.buffer_red.as_mut_slice()[..IMAGE_SIZE],
+            dx,
+            dy,
+            fill_red,
+        );
+    }
+
+    /// Shift a plane by `dx` and `dy` pixels in panel coordinates
+    fn shift_plane(buffer: &mut [u8], dx: i32, dy: i32, fill: u8) {
+        let row_len = Self::BYTES_PER_ROW;
+        let rows = (dy.unsigned_abs() as usize).min(SIZE_V as usize);
+        let len = buffer.len();
+        if dy > 0 {
+            buffer.copy_within(..len - rows * row_len, rows * row_len);
+            buffer[..rows * row_len].fill(fill);
+        } else if dy < 0 {
+            buffer.copy_within(rows * row_len.., 0);
+            buffer[len - rows * row_len..].fill(fill);
+        }
+
+        let columns = (dx.unsigned_abs() as usize).min(SIZE_H as usize);
+        let (bytes, bits) = (columns / 8, columns % 8);
+        for row in buffer.chunks_exact_mut(row_len) {
+            // the leftmost pixel of a byte is its most significant bit
+            if dx > 0 {
+                row.copy_within(..row_len - bytes, bytes);
+                row[..bytes].fill(fill);
+                if bits > 0 {
+                    for i in (0..row_len).rev() {
+                        let previous = if i > 0 { row[i - 1] } else { fill };
+                        row[i] = (row[i] >> bits) | (previous << (8 - bits));
+                    }
+                }
+            } else if dx < 0 {
+                row.copy_within(bytes.., 0);
+                row[row_len - bytes..].fill(fill);
+                if bits > 0 {
+                    for i in 0..row_len {
+                        let next = if i + 1 < row_len { row[i + 1] } else { fill };
+                        row[i] = (row[i] << bits) | (next >> (8 - bits));
+                    }
+                }
+            }
+        }
+    }
+
+    /// Render the content as text, one line per row (in rotated
+    /// coordinates) with ' ' for white, '#' for black and 'R' for red
+    /// pixels, e.g. to check frames in headless tests.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if writing to `out` fails.
+    #[allow(clippy::cast_possible_wrap)]
+    pub fn write_ascii(&self, out: &mut impl core::fmt::Write) -> core::fmt::Result {
+        let size = self.size();
+        for y in 0..size.height as i32 {
+            for x in 0..size.width as i32 {
+                let c = match self.get_pixel(Point::new(x, y)) {
+                    Some(TriColor::Black) => '#',
+                    Some(TriColor::Red) => 'R',
+                    Some(TriColor::White) | None => ' ',
+                };
+                out.write_char(c)?;
+            }
+            out.write_char('\n')?;
+        }
+        Ok(())
+    }
+
+    /// Print the content to stdout, see `write_ascii`
+    #[cfg(feature = "std")]
+    pub fn print_ascii(&self) {
+        let mut text = std::string::String::new();
+        // writing to a string cannot fail
+        let _ = self.write_ascii(&mut text);
+        std::print!("{text}");
+    }
+
+    /// Combine the planes of `other` with the planes of this display,
+    /// e.g. a dynamic foreground with a static background, see [`OverlayMode`]
+    pub fn overlay<B2: BufferStorage>(
+        &mut self,
+        other: &Display<SIZE_V, SIZE_H, IMAGE_SIZE, B2>,
+        mode: OverlayMode,
+    ) {
+        let black = &mut self.buffer_black.as_mut_slice()[..IMAGE_SIZE];
+        let red = &mut self.buffer_red.as_mut_slice()[..IMAGE_SIZE];
+        let other_black = other.get_buffer_black();
+        let other_red = other.get_buffer_red();
+        for (((black, red), &other_black), &other_red) in black
+            .iter_mut()
+            .zip(red.iter_mut())
+            .zip(other_black)
+            .zip(other_red)
+        {
+            match mode {
+                OverlayMode::Or => {
+                    let foreground = other_black | other_red;
+                    *black = (*black & !foreground) | other_black;
+                    *red = (*red & !foreground) | other_red;
+                }
+                OverlayMode::And => {
+                    *black &= other_black;
+                    *red &= other_red;
+                }
+                OverlayMode::Replace => {
+                    *black = other_black;
+                    *red = other_red;
+                }
+            }
+        }
+    }
+
+    /// Copy raw plane data (e.g. a pre-rendered frame) into the buffers.
+    /// Data exceeding the buffer size is ignored.
+    pub fn copy_from_buffers(&mut self, black: &[u8], red: &[u8]) {
+        let len = black.len().min(IMAGE_SIZE);
+        self.buffer_black.as_mut_slice()[..len].copy_from_slice(&black[..len]);
+        let len = red.len().min(IMAGE_SIZE);
+        self.buffer_red.as_mut_slice()[..len].copy_from_slice(&red[..len]);
+    }
+
+    /// Exchange the black and the red plane, e.g. to toggle the emphasis
+    /// of a pre-rendered frame between normal and alert state without
+    /// drawing it again
+    pub fn swap_accent(&mut self) {
+        core::mem::swap(&mut self.buffer_black, &mut self.buffer_red);
+    }
+
+    /// Fill the display with `pattern` (in rotated coordinates), with
+    /// cells or stripes of `cell_size` pixels. A cell size of 0 is
+    /// treated as 1.
+    #[allow(clippy::cast_possible_wrap)]
+    pub fn test_pattern(&mut self, pattern: Pattern, cell_size: u32) {
+        const COLORS: [TriColor; 3] = [TriColor::White, TriColor::Black, TriColor::Red];
+        let cell_size = cell_size.max(1);
+        let size = self.size();
+        for y in 0..size.height {
+            for x in 0..size.width {
+                let index = match pattern {
+                    Pattern::Checkerboard => x / cell_size + y / cell_size,
+                    Pattern::VStripes => x / cell_size,
+                    Pattern::HStripes => y / cell_size,
+                    Pattern::ColorBars => x * 3 / size.width,
+                };
+                let color = COLORS[(index % 3) as usize];
+                self.set_pixel(Point::new(x as i32, y as i32), color);
+            }
+        }
+    }
+
+    /// Count the pixels of each color, e.g. to estimate the energy of a
+    /// refresh or to warn about frames which are mostly red
+    #[must_use]
+    pub fn stats(&self) -> FrameStats {
+        let black = &self.buffer_black.as_slice()[..IMAGE_SIZE];
+        let red = &self.buffer_red.as_slice()[..IMAGE_SIZE];
+        let mut stats = FrameStats::default();
+        for (&black, &red) in black.iter().zip(red) {
+            // red has priority over black, see `get_pixel`
+            stats.red += red.count_ones();
+            stats.black += (black & !red).count_ones();
+        }
+        stats.white = SIZE_V * SIZE_H - stats.red - stats.black;
+        stats
+    }
+
+    /// Memory of `plane`
+    pub(crate) fn storage(&self, plane: Plane) -> &B {
+        match plane {
+            Plane::Black => &self.buffer_black,
+            Plane::Red => &self.buffer_red,
+        }
+    }
+
+    /// Mutable memory of `plane`
+    pub(crate) fn storage_mut(&mut self, plane: Plane) -> &mut B {
+        match plane {
+            Plane::Black => &mut self.buffer_black,
+            Plane::Red => &mut self.buffer_red,
+        }
+    }
+
+    /// Mutable access to the black and the red plane
+    #[cfg(feature = "testing")]
+    pub(crate) fn buffers_mut(&mut self) -> (&mut [u8], &mut [u8]) {
+        (
+            &mut self.buffer_black.as_mut_slice()[..IMAGE_SIZE],
+            &mut self.buffer_red.as_mut_slice()[..IMAGE_SIZE],
+        )
+    }
+
+    /// Color of a pixel given in rotated coordinates,
+    /// `None` if the pixel is outside of the display
+    #[must_use]
+    pub fn get_pixel(&self, point: Point) -> Option<TriColor> {
+        let (x, y) = Self::rotate_point(self.rotation, point.x, point.y);
+        let (index, mask) = Self::pixel_position(x, y)?;
+        if self.buffer_red.as_slice()[index] & mask != 0 {
+            Some(TriColor::Red)
+        } else if self.buffer_black.as_slice()[index] & mask != 0 {
+            Some(TriColor::Black)
+        } else {
+            Some(TriColor::White)
+        }
+    }
+
+    /// Set a pixel given in rotated coordinates, pixels outside of the display are ignored
+    pub(crate) fn set_pixel(&mut self, point: Point, color: TriColor) {
+        let (x, y) = Self::rotate_point(self.rotation, point.x, point.y);
+        self.set_panel_pixel(x, y, color);
+    }
+
+    /// Set a pixel given in panel coordinates
+    fn set_panel_pixel(&mut self, x: i32, y: i32, color: TriColor) {
+        let Some((index, mask)) = Self::pixel_position(x, y) else {
+            return;
+        };
+        match color {
+            TriColor::White => {
+                self.buffer_black.as_mut_slice()[index] &= !mask;
+                self.buffer_red.as_mut_slice()[index] &= !mask;
+            }
+            TriColor::Black => {
+                self.buffer_black.as_mut_slice()[index] |= mask;
+                self.buffer_red.as_mut_slice()[index] &= !mask;
+            }
+            TriColor::Red => {
+                self.buffer_black.as_mut_slice()[index] &= !mask;
+                self.buffer_red.as_mut_slice()[index] |= mask;
+            }
+        }
+    }
+
+    /// Toggle a pixel given in panel coordinates in the black plane
+    /// unless `color` is white or the pixel is red, see `DrawMode::Xor`
+    fn toggle_panel_pixel(&mut self, x: i32, y: i32, color: TriColor) {
+        let Some((index, mask)) = Self::pixel_position(x, y) else {
+            return;
+        };
+        if color != TriColor::White {
+            let red = self.buffer_red.as_slice()[index];
+            self.buffer_black.as_mut_slice()[index] ^= mask & !red;
+        }
+    }
+
+    /// Draw `pixels` given in coordinates rotated by `rotation`, with the
+    /// transparent color and the draw mode. For a constant rotation (see
+    /// [`FixedRotation`](crate::FixedRotation)) the rotation is resolved
+    /// at compile time.
+    #[inline]
+    pub(crate) fn draw_rotated(
+        &mut self,
+        rotation: DisplayRotation,
+        pixels: impl IntoIterator<Item = Pixel<TriColor>>,
+    ) {
+        for Pixel(point, color) in pixels {
+            if Some(color) == self.transparent {
+                continue;
+            }
+            let (x, y) = Self::rotate_point(rotation, point.x, point.y);
+            match self.mode {
+                DrawMode::Normal => self.set_panel_pixel(x, y, color),
+                DrawMode::Xor => self.toggle_panel_pixel(x, y, color),
+            }
+        }
+    }
+
+    /// Fill `area` given in coordinates rotated by `rotation`, see `draw_rotated`
+    #[inline]
+    pub(crate) fn fill_rotated(
+        &mut self,
+        rotation: DisplayRotation,
+        area: &Rectangle,
+        color: TriColor,
+    ) {
+        if Some(color) == self.transparent {
+            return;
+        }
+        let area = area.intersection(&Rectangle::new(Point::zero(), Self::rotated_size(rotation)));
+        let Some(bottom_right) = area.bottom_right() else {
+            return;
+        };
+        // a rectangle stays a rectangle in panel coordinates for all rotations
+        let (x0, y0) = Self::rotate_point(rotation, area.top_left.x, area.top_left.y);
+        let (x1, y1) = Self::rotate_point(rotation, bottom_right.x, bottom_right.y);
+        self.fill_rect(min(x0, x1), max(x0, x1), min(y0, y1), max(y0, y1), color);
+    }
+
+    /// Size of the display in coordinates rotated by `rotation`
+    pub(crate) const fn rotated_size(rotation: DisplayRotation) -> Size {
+        match rotation {
+            DisplayRotation::Rotate0 | DisplayRotation::Rotate180 => Size::new(SIZE_H, SIZE_V),
+            DisplayRotation::Rotate90 | DisplayRotation::Rotate270 => Size::new(SIZE_V, SIZE_H),
+        }
+    }
+
+    /// Set or clear a pixel given in rotated coordinates in a single plane.
+    /// Setting a pixel clears it in the other plane, so the planes stay
+    /// mutually exclusive.
+    pub(crate) fn set_plane_pixel(&mut self, plane: Plane, point: Point, on: bool) {
+        let (x, y) = Self::rotate_point(self.rotation, point.x, point.y);
+        let Some((index, mask)) = Self::pixel_position(x, y) else {
+            return;
+        };
+        let (buffer, other) = match plane {
+            Plane::Black => (&mut self.buffer_black, &mut self.buffer_red),
+            Plane::Red => (&mut self.buffer_red, &mut self.buffer_black),
+        };
+        if on {
+            buffer.as_mut_slice()[index] |= mask;
+            other.as_mut_slice()[index] &= !mask;
+        } else {
+            buffer.as_mut_slice()[index] &= !mask;
+        }
+    }
+
+    /// Fill the area between the given panel coordinates (inclusive, inside of
+    /// the panel) row by row, writing whole bytes instead of single pixels.
+    /// Toggles the black plane outside of red pixels instead in
+    /// `DrawMode::Xor`.
+    #[allow(clippy::cast_sign_loss)]
+    fn fill_rect(&mut self, x_start: i32, x_end: i32, y_start: i32, y_end: i32, color: TriColor) {
+        let (black, red) = match color {
+            TriColor::White => (false, false),
+            TriColor::Black => (true, false),
+            TriColor::Red => (false, true),
+        };
+        let (x_start, x_end) = (x_start as usize, x_end as usize);
+        let (first, last) = (x_start / 8, x_end / 8);
+        let first_mask = 0xff_u8 >> (x_start % 8);
+        let last_mask = 0xff_u8 << (7 - x_end % 8);
+        for y in y_start as usize..=y_end as usize {
+            let row = y * Self::BYTES_PER_ROW;
+            for column in first..=last {
+                let mut mask = 0xff;
+                if column == first {
+                    mask &= first_mask;
+                }
+                if column == last {
+                    mask &= last_mask;
+                }
+                let index = row + column;
+                if self.mode == DrawMode::Xor {
+                    if color != TriColor::White {
+                        let red = self.buffer_red.as_slice()[index];
+                        self.buffer_black.as_mut_slice()[index] ^= mask & !red;
+                    }
+                    continue;
+                }
+                set_bits(&mut self.buffer_black.as_mut_slice()[index], mask, black);
+                set_bits(&mut self.buffer_red.as_mut_slice()[index], mask, red);
+            }
+        }
+    }
+
+    /// Map a point given in rotated coordinates to panel coordinates
+    #[inline]
+    #[allow(clippy::cast_possible_wrap)]
+    pub(crate) fn rotate_point(rotation: DisplayRotation, x: i32, y: i32) -> (i32, i32) {
+        match rotation {
+            DisplayRotation::Rotate0 => (x, y),
+            DisplayRotation::Rotate90 => (SIZE_H as i32 - 1 - y, x),
+            DisplayRotation::Rotate180 => (SIZE_H as i32 - 1 - x, SIZE_V as i32 - 1 - y),
+            DisplayRotation::Rotate270 => (y, SIZE_V as i32 - 1 - x),
+        }
+    }
+
+    /// Buffer index and bit mask of a pixel given in panel coordinates,
+    /// `None` if the pixel is outside of the panel
+    #[allow(clippy::cast_sign_loss, clippy::cast_possible_wrap)]
+    pub(crate) fn pixel_position(x: i32, y: i32) -> Option<(usize, u8)> {
+        if (x < 0) || (x >= SIZE_H as i32) || (y < 0) || y >= SIZE_V as i32 {
+            return None;
+        }
+        let mask: u8 = 1 << (7 - (x % 8));
+        let index = y as usize * Self::BYTES_PER_ROW + x as usize / 8;
+        assert!(index < IMAGE_SIZE);
+        Some((index, mask))
+    }
 }
 
-impl<const SIZE_V: u32, const SIZE_H: u32, const IMAGE_SIZE: usize> DisplayBuffer
-    for Display<SIZE_V, SIZE_H, IMAGE_SIZE>
+impl<const SIZE_V: u32, const SIZE_H: u32, const IMAGE_SIZE: usize, B: BufferStorage> DisplayBuffer
+    for Display<SIZE_V, SIZE_H, IMAGE_SIZE, B>
 {
     fn get_buffer_black(&self) -> &[u8] {
-        &self.buffer_black
+        &self.buffer_black.as_slice()[..IMAGE_SIZE]
     }
     fn get_buffer_red(&self) -> &[u8] {
-        &self.buffer_red
+        &self.buffer_red.as_slice()[..IMAGE_SIZE]
+    }
+}
+
+impl<const SIZE_V: u32, const SIZE_H: u32, const IMAGE_SIZE: usize, B: BufferStorage>
+    Display<SIZE_V, SIZE_H, IMAGE_SIZE, B>
+{
+    /// CRC-32 of the planes and the rotation, which is the same on every
+    /// platform and for every kind of storage, e.g. to skip refreshes of
+    /// frames already shown or as key of a cache of rendered frames
+    #[must_use]
+    pub fn content_hash(&self) -> u32 {
+        let mut crc = Crc::new();
+        crc.update(self.rotation as u8);
+        for &byte in self.get_buffer_black().iter().chain(self.get_buffer_red()) {
+            crc.update(byte);
+        }
+        crc.finish()
+    }
+}
+
+/// Displays are equal if their planes and rotations are equal, transparent
+/// color and draw mode are not compared
+impl<const SIZE_V: u32, const SIZE_H: u32, const IMAGE_SIZE: usize, B: BufferStorage> PartialEq
+    for Display<SIZE_V, SIZE_H, IMAGE_SIZE, B>
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.rotation == other.rotation
+            && self.get_buffer_black() == other.get_buffer_black()
+            && self.get_buffer_red() == other.get_buffer_red()
+    }
+}
+
+impl<const SIZE_V: u32, const SIZE_H: u32, const IMAGE_SIZE: usize, B: BufferStorage> Eq
+    for Display<SIZE_V, SIZE_H, IMAGE_SIZE, B>
+{
+}
+
+impl<const SIZE_V: u32, const SIZE_H: u32, const IMAGE_SIZE: usize, B: BufferStorage> Hash
+    for Display<SIZE_V, SIZE_H, IMAGE_SIZE, B>
+{
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.rotation.hash(state);
+        self.get_buffer_black().hash(state);
+        self.get_buffer_red().hash(state);
     }
 }
 
@@ -127,76 +968,94 @@ impl<const SIZE_V: u32, const SIZE_H: u32, const IMAGE_SIZE: usize> Default
     for Display<SIZE_V, SIZE_H, IMAGE_SIZE>
 {
     fn default() -> Self {
-        Self {
-            buffer_black: [0; IMAGE_SIZE],
-            buffer_red: [0; IMAGE_SIZE],
-            rotation: DisplayRotation::default(),
-        }
+        Self::new()
     }
 }
 
-impl<const SIZE_V: u32, const SIZE_H: u32, const IMAGE_SIZE: usize> OriginDimensions
-    for Display<SIZE_V, SIZE_H, IMAGE_SIZE>
+impl<const SIZE_V: u32, const SIZE_H: u32, const IMAGE_SIZE: usize, B: BufferStorage>
+    OriginDimensions for Display<SIZE_V, SIZE_H, IMAGE_SIZE, B>
 {
     fn size(&self) -> Size {
-        match self.rotation {
-            DisplayRotation::Rotate0 | DisplayRotation::Rotate180 => Size::new(SIZE_H, SIZE_V),
-            DisplayRotation::Rotate90 | DisplayRotation::Rotate270 => Size::new(SIZE_V, SIZE_H),
-        }
+        Self::rotated_size(self.rotation)
     }
 }
 
-impl<const SIZE_V: u32, const SIZE_H: u32, const IMAGE_SIZE: usize> DrawTarget
-    for Display<SIZE_V, SIZE_H, IMAGE_SIZE>
+impl<const SIZE_V: u32, const SIZE_H: u32, const IMAGE_SIZE: usize, B: BufferStorage> DrawTarget
+    for Display<SIZE_V, SIZE_H, IMAGE_SIZE, B>
 {
     type Color = TriColor;
     type Error = core::convert::Infallible;
 
-    #[allow(clippy::cast_sign_loss, clippy::cast_possible_wrap)]
     fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Self::Error>
     where
         I: IntoIterator<Item = Pixel<Self::Color>>,
     {
-        for pixel in pixels {
-            let Pixel(p, color) = pixel;
-
-            let (x, y) = match self.rotation {
-                DisplayRotation::Rotate0 => (p.x, p.y),
-                DisplayRotation::Rotate90 => (SIZE_H as i32 - 1 - p.y, p.x),
-                DisplayRotation::Rotate180 => (SIZE_H as i32 - 1 - p.x, SIZE_V as i32 - 1 - p.y),
-                DisplayRotation::Rotate270 => (p.y, SIZE_V as i32 - 1 - p.x),
-            };
-
-            if (x < 0) || (x >= SIZE_H as i32) || (y < 0) || y >= SIZE_V as i32 {
-                continue;
-            }
+        self.draw_rotated(self.rotation, pixels);
+        Ok(())
+    }
 
-            let mask: u8 = 1 << (7 - (x % 8));
-            let index = y as usize * SIZE_H as usize / 8 + x as usize / 8;
-            assert!(index < IMAGE_SIZE);
+    fn fill_solid(&mut self, area: &Rectangle, color: Self::Color) -> Result<(), Self::Error> {
+        self.fill_rotated(self.rotation, area, color);
+        Ok(())
+    }
 
-            match color {
-                TriColor::White => {
-                    self.buffer_black[index] &= !mask;
-                    self.buffer_red[index] &= !mask;
-                }
-                TriColor::Black => {
-                    self.buffer_black[index] |= mask;
-                    self.buffer_red[index] &= !mask;
-                }
-                TriColor::Red => {
-                    self.buffer_black[index] &= !mask;
-                    self.buffer_red[index] |= mask;
-                }
-            }
+    fn fill_contiguous<I>(&mut self, area: &Rectangle, colors: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Self::Color>,
+    {
+        // skip images which are completely off-screen without iterating them
+        if area.intersection(&self.bounding_box()).is_zero_sized() {
+            return Ok(());
         }
-        Ok(())
+        self.draw_iter(
+            area.points()
+                .zip(colors)
+                .map(|(point, color)| Pixel(point, color)),
+        )
     }
 }
 
+/// Set or clear the bits of `mask` in `byte`
+fn set_bits(byte: &mut u8, mask: u8, on: bool) {
+    if on {
+        *byte |= mask;
+    } else {
+        *byte &= !mask;
+    }
+}
+
+/// Size of a buffer plane in bytes for a panel with `size_v` rows of
+/// `size_h` pixels. Used by [`display_type!`] to reject invalid sizes
+/// at compile time.
+///
+/// # Panics
+///
+/// Panics if a size is 0, `size_h` is not a multiple of 8 or the plane
+/// does not fit into `usize`, e.g. for large panels on 16-bit MCUs.
+#[must_use]
+#[allow(clippy::cast_possible_truncation)]
+pub const fn image_size(size_v: u32, size_h: u32) -> usize {
+    assert!(size_v > 0 && size_h > 0, "display size must not be 0");
+    assert!(
+        size_h % 8 == 0,
+        "horizontal display size must be a multiple of 8"
+    );
+    let len = size_v as u64 * (size_h as u64 / 8);
+    assert!(
+        len <= usize::MAX as u64,
+        "display buffer does not fit into the address space"
+    );
+    len as usize
+}
+
+/// Display type for a panel with `$a` rows of `$b` pixels, e.g. for
+/// panels without a predefined alias:
+/// `type Display5in81 = epd_spectra::display_type!(720, 256);`.
+/// Invalid sizes are rejected at compile time.
+#[macro_export]
 macro_rules! display_type {
     ($a:expr, $b:expr) => {
-        Display<$a, $b, {$a * ($b / 8)}>
+        $crate::Display<$a, $b, { $crate::image_size($a, $b) }>
     };
 }
 pub type Display1in54 = display_type!(152, 152);
@@ -207,3 +1066,215 @@ pub type Display2in87 = display_type!(296, 128);
 pub type Display3in70 = display_type!(416, 240);
 pub type Display4in17 = display_type!(300, 400);
 pub type Display4in37 = display_type!(480, 176);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Pixels at asymmetric positions, so every rotation changes the content
+    fn pattern<const SIZE_V: u32, const SIZE_H: u32, const IMAGE_SIZE: usize>(
+    ) -> Display<SIZE_V, SIZE_H, IMAGE_SIZE> {
+        let mut display = Display::new();
+        display.set_rotation(DisplayRotation::Rotate0);
+        display.set_pixel(Point::new(3, 1), TriColor::Black);
+        display.set_pixel(Point::new(17, 40), TriColor::Red);
+        display.set_pixel(Point::new(100, 9), TriColor::Black);
+        display
+    }
+
+    /// Whether a pixel given in panel coordinates is set in the black plane
+    fn black_at<const SIZE_V: u32, const SIZE_H: u32, const IMAGE_SIZE: usize>(
+        display: &Display<SIZE_V, SIZE_H, IMAGE_SIZE>,
+        x: i32,
+        y: i32,
+    ) -> bool {
+        let (index, mask) = Display::<SIZE_V, SIZE_H, IMAGE_SIZE>::pixel_position(x, y).unwrap();
+        display.get_buffer_black()[index] & mask != 0
+    }
+
+    #[test]
+    fn rotate_buffer_90_four_times_is_identity() {
+        let original: Display1in54 = pattern();
+        let mut display = original.clone();
+        // square displays are rotated in place
+        display
+            .rotate_buffer(DisplayRotation::Rotate90, &mut [])
+            .unwrap();
+        assert!(display != original);
+        for _ in 0..3 {
+            display
+                .rotate_buffer(DisplayRotation::Rotate90, &mut [])
+                .unwrap();
+        }
+        assert!(display == original);
+    }
+
+    #[test]
+    fn rotate_buffer_square_in_place() {
+        let mut display = Display1in54::new();
+        display.set_rotation(DisplayRotation::Rotate0);
+        display.set_pixel(Point::new(10, 5), TriColor::Black);
+        display.set_pixel(Point::new(0, 151), TriColor::Red);
+        display
+            .rotate_buffer(DisplayRotation::Rotate270, &mut [])
+            .unwrap();
+        assert!(black_at(&display, 5, 141));
+        assert_eq!(display.stats().black, 1);
+        assert_eq!(display.stats().red, 1);
+        assert_eq!(display.rotation(), DisplayRotation::Rotate270);
+    }
+
+    #[test]
+    fn rotate_buffer_reports_short_scratch() {
+        let original: Display2in66 = pattern();
+        let mut display = original.clone();
+        assert_eq!(
+            display.rotate_buffer(DisplayRotation::Rotate90, &mut [0; 16]),
+            Err(RotateError::ScratchTooSmall {
+                expected: image_size(296, 152),
+                actual: 16,
+            })
+        );
+        assert!(display == original);
+    }
+
+    #[test]
+    fn rotate_buffer_180_twice_is_identity() {
+        let original: Display2in66 = pattern();
+        let mut display = original.clone();
+        display
+            .rotate_buffer(DisplayRotation::Rotate180, &mut [])
+            .unwrap();
+        assert!(display != original);
+        display
+            .rotate_buffer(DisplayRotation::Rotate180, &mut [])
+            .unwrap();
+        assert!(display == original);
+    }
+
+    #[test]
+    fn rotate_buffer_clips_non_square() {
+        // 152 pixels wide and 296 rows high
+        let mut display = Display2in66::new();
+        display.set_rotation(DisplayRotation::Rotate0);
+        display.set_pixel(Point::new(10, 5), TriColor::Black);
+        display.set_pixel(Point::new(0, 200), TriColor::Red);
+        let mut scratch = [0; image_size(296, 152)];
+        display
+            .rotate_buffer(DisplayRotation::Rotate90, &mut scratch)
+            .unwrap();
+        assert!(black_at(&display, 146, 10));
+        assert_eq!(display.stats().black, 1);
+        // row 200 is mapped to column -49
+        assert_eq!(display.stats().red, 0);
+        assert_eq!(display.rotation(), DisplayRotation::Rotate90);
+    }
+
+    /// 2.66" display (152 pixels wide, 296 rows) with `rotation` and `pixels`
+    fn with_pixels(rotation: DisplayRotation, pixels: &[(i32, i32, TriColor)]) -> Display2in66 {
+        let mut display = Display2in66::new();
+        display.set_rotation(rotation);
+        for &(x, y, color) in pixels {
+            display.set_pixel(Point::new(x, y), color);
+        }
+        display
+    }
+
+    #[test]
+    fn shift_by_bits() {
+        let rotation = DisplayRotation::Rotate0;
+        let mut display = with_pixels(rotation, &[(3, 1, TriColor::Black), (20, 5, TriColor::Red)]);
+        display.shift(5, 2, TriColor::White);
+        let expected = with_pixels(rotation, &[(8, 3, TriColor::Black), (25, 7, TriColor::Red)]);
+        assert!(display == expected);
+    }
+
+    #[test]
+    fn shift_negative() {
+        let rotation = DisplayRotation::Rotate0;
+        let mut display = with_pixels(
+            rotation,
+            &[
+                (1, 0, TriColor::Black),
+                (10, 4, TriColor::Black),
+                (150, 295, TriColor::Red),
+            ],
+        );
+        display.shift(-3, -2, TriColor::White);
+        // the pixel at (1, 0) is shifted out
+        let expected = with_pixels(
+            rotation,
+            &[(7, 2, TriColor::Black), (147, 293, TriColor::Red)],
+        );
+        assert!(display == expected);
+    }
+
+    #[test]
+    fn shift_fills_freed_area() {
+        let mut display = with_pixels(DisplayRotation::Rotate0, &[]);
+        display.shift(5, 0, TriColor::Black);
+        assert_eq!(display.stats().black, 5 * 296);
+        assert_eq!(display.get_pixel(Point::new(4, 100)), Some(TriColor::Black));
+        assert_eq!(display.get_pixel(Point::new(5, 100)), Some(TriColor::White));
+
+        let mut display = with_pixels(DisplayRotation::Rotate0, &[]);
+        display.shift(-5, 0, TriColor::Red);
+        assert_eq!(display.stats().red, 5 * 296);
+        assert_eq!(display.get_pixel(Point::new(147, 0)), Some(TriColor::Red));
+        assert_eq!(display.get_pixel(Point::new(146, 0)), Some(TriColor::White));
+    }
+
+    #[test]
+    fn shift_beyond_the_display_clears_it() {
+        let mut display: Display2in66 = pattern();
+        display.shift(200, 0, TriColor::White);
+        assert_eq!(display.stats().black + display.stats().red, 0);
+
+        let mut display: Display2in66 = pattern();
+        display.shift(0, -400, TriColor::Red);
+        assert_eq!(display.stats().red, 152 * 296);
+    }
+
+    #[test]
+    fn shift_in_rotated_coordinates() {
+        for rotation in [
+            DisplayRotation::Rotate0,
+            DisplayRotation::Rotate90,
+            DisplayRotation::Rotate180,
+            DisplayRotation::Rotate270,
+        ] {
+            let mut display = with_pixels(rotation, &[(3, 1, TriColor::Black)]);
+            display.shift(5, 2, TriColor::White);
+            assert!(display == with_pixels(rotation, &[(8, 3, TriColor::Black)]));
+        }
+    }
+
+    #[test]
+    fn xor_keeps_red_pixels() {
+        let mut display = with_pixels(DisplayRotation::Rotate0, &[]);
+        let red = Rectangle::new(Point::new(4, 4), Size::new(10, 10));
+        display.fill_solid(&red, TriColor::Red).unwrap();
+        display.set_draw_mode(DrawMode::Xor);
+        let cursor = Rectangle::new(Point::new(0, 0), Size::new(8, 8));
+        display.fill_solid(&cursor, TriColor::Black).unwrap();
+        display
+            .draw_iter([
+                Pixel(Point::new(5, 20), TriColor::Black),
+                Pixel(Point::new(6, 6), TriColor::Black),
+            ])
+            .unwrap();
+
+        let black = display.get_buffer_black();
+        let red = display.get_buffer_red();
+        assert!(black.iter().zip(red).all(|(black, red)| black & red == 0));
+        assert_eq!(display.get_pixel(Point::new(2, 2)), Some(TriColor::Black));
+        assert_eq!(display.get_pixel(Point::new(5, 5)), Some(TriColor::Red));
+        assert_eq!(display.get_pixel(Point::new(6, 6)), Some(TriColor::Red));
+        assert_eq!(display.get_pixel(Point::new(5, 20)), Some(TriColor::Black));
+
+        // drawing again restores the content
+        display.fill_solid(&cursor, TriColor::Black).unwrap();
+        assert_eq!(display.get_pixel(Point::new(2, 2)), Some(TriColor::White));
+        assert_eq!(display.stats().red, 100);
+    }
+}
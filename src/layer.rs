@@ -0,0 +1,70 @@
+//! Single planes of a display as monochrome draw targets
+
+use embedded_graphics::{
+    draw_target::DrawTarget,
+    geometry::{OriginDimensions, Size},
+    pixelcolor::BinaryColor,
+    Pixel,
+};
+
+use crate::{BufferStorage, Display, Plane};
+
+/// One plane of a [`Display`] as `BinaryColor` draw target, so monochrome
+/// drawing code can render directly into it. `On` sets the pixel (and
+/// clears it in the other plane), `Off` clears it.
+pub struct Layer<
+    'a,
+    const SIZE_V: u32,
+    const SIZE_H: u32,
+    const IMAGE_SIZE: usize,
+    B = [u8; IMAGE_SIZE],
+> {
+    display: &'a mut Display<SIZE_V, SIZE_H, IMAGE_SIZE, B>,
+    plane: Plane,
+}
+
+impl<const SIZE_V: u32, const SIZE_H: u32, const IMAGE_SIZE: usize, B: BufferStorage>
+    Display<SIZE_V, SIZE_H, IMAGE_SIZE, B>
+{
+    /// Black plane as monochrome draw target, see [`Layer`]
+    pub fn black_layer(&mut self) -> Layer<'_, SIZE_V, SIZE_H, IMAGE_SIZE, B> {
+        Layer {
+            display: self,
+            plane: Plane::Black,
+        }
+    }
+
+    /// Red plane as monochrome draw target, see [`Layer`]
+    pub fn red_layer(&mut self) -> Layer<'_, SIZE_V, SIZE_H, IMAGE_SIZE, B> {
+        Layer {
+            display: self,
+            plane: Plane::Red,
+        }
+    }
+}
+
+impl<const SIZE_V: u32, const SIZE_H: u32, const IMAGE_SIZE: usize, B: BufferStorage>
+    OriginDimensions for Layer<'_, SIZE_V, SIZE_H, IMAGE_SIZE, B>
+{
+    fn size(&self) -> Size {
+        self.display.size()
+    }
+}
+
+impl<const SIZE_V: u32, const SIZE_H: u32, const IMAGE_SIZE: usize, B: BufferStorage> DrawTarget
+    for Layer<'_, SIZE_V, SIZE_H, IMAGE_SIZE, B>
+{
+    type Color = BinaryColor;
+    type Error = core::convert::Infallible;
+
+    fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Pixel<Self::Color>>,
+    {
+        for Pixel(point, color) in pixels {
+            self.display
+                .set_plane_pixel(self.plane, point, color.is_on());
+        }
+        Ok(())
+    }
+}
@@ -5,11 +5,143 @@
 //! See the examples folder to get started.
 #![no_std]
 
+#[cfg(feature = "slint")]
+extern crate alloc;
 #[cfg(feature = "std")]
 extern crate std;
 
+pub mod animator;
+pub mod auto;
+#[cfg(feature = "graphics")]
+pub mod band;
+#[cfg(feature = "graphics")]
+pub mod bitmap;
+#[cfg(feature = "graphics")]
+pub mod calendar;
+#[cfg(feature = "wasm")]
+pub mod canvas;
+pub mod commands;
+#[cfg(feature = "graphics")]
+pub mod cull;
+#[cfg(feature = "graphics")]
+pub mod double;
 pub mod driver;
+#[cfg(feature = "esp-hal")]
+pub mod esp;
+pub mod events;
+#[cfg(feature = "graphics")]
+pub mod fixed;
+#[cfg(feature = "graphics")]
+pub mod flush;
+#[cfg(feature = "fonts")]
+pub mod fonts;
+#[cfg(feature = "graphics")]
 pub mod graphics;
+#[cfg(feature = "graphics")]
+pub mod layer;
+#[cfg(feature = "graphics")]
+pub mod mapping;
+#[cfg(feature = "graphics")]
+pub mod mask;
+#[cfg(feature = "graphics")]
+pub mod mono;
+#[cfg(feature = "graphics")]
+pub mod plane;
+#[cfg(feature = "queue")]
+pub mod queue;
+pub mod rate_limit;
+#[cfg(feature = "testing")]
+pub mod replay;
+pub mod schedule;
+#[cfg(feature = "graphics")]
+pub mod scroll;
+#[cfg(feature = "sdmmc")]
+pub mod sdcard;
+#[cfg(feature = "std")]
+pub mod shared;
+#[cfg(feature = "gif")]
+pub mod slideshow;
+#[cfg(feature = "slint")]
+pub mod slint_platform;
+pub mod source;
+pub mod spec;
+#[cfg(feature = "splash")]
+pub mod splash;
+pub mod storage;
+#[cfg(all(feature = "std", feature = "graphics"))]
+pub mod supersample;
+#[cfg(feature = "graphics")]
+pub mod table;
+#[cfg(feature = "testing")]
+pub mod testing;
+#[cfg(feature = "graphics")]
+pub mod text;
+pub mod transport;
+#[cfg(feature = "wear")]
+pub mod wear;
+#[cfg(feature = "weather-icons")]
+pub mod weather;
 
+pub use animator::*;
+pub use auto::*;
+#[cfg(feature = "graphics")]
+pub use band::*;
+#[cfg(feature = "graphics")]
+pub use bitmap::*;
+#[cfg(feature = "graphics")]
+pub use calendar::*;
+#[cfg(feature = "wasm")]
+pub use canvas::*;
+pub use commands::*;
+#[cfg(feature = "graphics")]
+pub use cull::*;
+#[cfg(feature = "graphics")]
+pub use double::*;
 pub use driver::*;
+#[cfg(feature = "esp-hal")]
+pub use esp::*;
+pub use events::*;
+#[cfg(feature = "graphics")]
+pub use fixed::*;
+#[cfg(feature = "graphics")]
+pub use flush::*;
+#[cfg(feature = "graphics")]
 pub use graphics::*;
+#[cfg(feature = "graphics")]
+pub use layer::*;
+#[cfg(feature = "graphics")]
+pub use mapping::*;
+#[cfg(feature = "graphics")]
+pub use mask::*;
+#[cfg(feature = "graphics")]
+pub use mono::*;
+#[cfg(feature = "graphics")]
+pub use plane::*;
+#[cfg(feature = "queue")]
+pub use queue::*;
+pub use rate_limit::*;
+pub use schedule::*;
+#[cfg(feature = "graphics")]
+pub use scroll::*;
+#[cfg(feature = "sdmmc")]
+pub use sdcard::*;
+#[cfg(feature = "std")]
+pub use shared::*;
+#[cfg(feature = "gif")]
+pub use slideshow::*;
+pub use source::*;
+pub use spec::*;
+#[cfg(feature = "splash")]
+pub use splash::*;
+pub use storage::*;
+#[cfg(all(feature = "std", feature = "graphics"))]
+pub use supersample::*;
+#[cfg(feature = "graphics")]
+pub use table::*;
+#[cfg(feature = "graphics")]
+pub use text::*;
+pub use transport::*;
+#[cfg(feature = "wear")]
+pub use wear::*;
+#[cfg(feature = "weather-icons")]
+pub use weather::*;
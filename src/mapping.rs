@@ -0,0 +1,306 @@
+//! Conversion of RGB content to `TriColor`, e.g. with dithering
+
+use embedded_graphics::{
+    draw_target::DrawTarget,
+    geometry::{Dimensions, Point},
+    pixelcolor::{Rgb888, RgbColor},
+    primitives::Rectangle,
+    Pixel,
+};
+
+use crate::TriColor;
+
+/// 4x4 Bayer matrix for ordered dithering
+const BAYER: [[u8; 4]; 4] = [[0, 8, 2, 10], [12, 4, 14, 6], [3, 11, 1, 9], [15, 7, 13, 5]];
+
+/// sRGB to linear light conversion table
+const SRGB_TO_LINEAR: [u8; 256] = [
+    0, 0, 0, 0, 0, 0, 0, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 2, 2, 2, 2, 2, 2, 2, 2, 3, 3, 3, 3, 3, 3,
+    4, 4, 4, 4, 4, 5, 5, 5, 5, 6, 6, 6, 6, 7, 7, 7, 8, 8, 8, 8, 9, 9, 9, 10, 10, 10, 11, 11, 12,
+    12, 12, 13, 13, 13, 14, 14, 15, 15, 16, 16, 17, 17, 17, 18, 18, 19, 19, 20, 20, 21, 22, 22, 23,
+    23, 24, 24, 25, 25, 26, 27, 27, 28, 29, 29, 30, 30, 31, 32, 32, 33, 34, 35, 35, 36, 37, 37, 38,
+    39, 40, 41, 41, 42, 43, 44, 45, 45, 46, 47, 48, 49, 50, 51, 51, 52, 53, 54, 55, 56, 57, 58, 59,
+    60, 61, 62, 63, 64, 65, 66, 67, 68, 69, 70, 71, 72, 73, 74, 76, 77, 78, 79, 80, 81, 82, 84, 85,
+    86, 87, 88, 90, 91, 92, 93, 95, 96, 97, 99, 100, 101, 103, 104, 105, 107, 108, 109, 111, 112,
+    114, 115, 116, 118, 119, 121, 122, 124, 125, 127, 128, 130, 131, 133, 134, 136, 138, 139, 141,
+    142, 144, 146, 147, 149, 151, 152, 154, 156, 157, 159, 161, 163, 164, 166, 168, 170, 171, 173,
+    175, 177, 179, 181, 183, 184, 186, 188, 190, 192, 194, 196, 198, 200, 202, 204, 206, 208, 210,
+    212, 214, 216, 218, 220, 222, 224, 226, 229, 231, 233, 235, 237, 239, 242, 244, 246, 248, 250,
+    253, 255,
+];
+
+/// Luminance of pure red
+const RED_LUMA: u8 = 76;
+
+/// Maps RGB colors to `TriColor`. The position of the pixel is
+/// passed as well, which allows spatial dithering.
+pub trait ColorMapper {
+    fn map(&self, color: Rgb888, point: Point) -> TriColor;
+}
+
+impl<M: ColorMapper + ?Sized> ColorMapper for &M {
+    fn map(&self, color: Rgb888, point: Point) -> TriColor {
+        (**self).map(color, point)
+    }
+}
+
+/// Simple thresholding, same as `TriColor::from(Rgb888)`
+#[derive(Clone, Copy, Default)]
+pub struct Threshold;
+
+impl ColorMapper for Threshold {
+    fn map(&self, color: Rgb888, _point: Point) -> TriColor {
+        TriColor::from(color)
+    }
+}
+
+/// Approximates gray levels with ordered dithering of black and white
+/// pixels. With `red_mixing`, red is used as additional intermediate
+/// level between black and white.
+#[derive(Clone, Copy, Default)]
+pub struct GrayDither {
+    pub red_mixing: bool,
+}
+
+impl ColorMapper for GrayDither {
+    fn map(&self, color: Rgb888, point: Point) -> TriColor {
+        let level = luma(color);
+        if !self.red_mixing {
+            return dither(level, point, TriColor::Black, TriColor::White);
+        }
+        if level < RED_LUMA {
+            dither(
+                scale(level, 0, RED_LUMA),
+                point,
+                TriColor::Black,
+                TriColor::Red,
+            )
+        } else {
+            dither(
+                scale(level, RED_LUMA, u8::MAX),
+                point,
+                TriColor::Red,
+                TriColor::White,
+            )
+        }
+    }
+}
+
+/// Renders warm tones (orange, pink, brown, ...) as dither patterns of red
+/// mixed with white and black, instead of collapsing them to flat red or
+/// black. Colors without a red tint are dithered in black and white.
+#[derive(Clone, Copy, Default)]
+pub struct WarmDither;
+
+impl ColorMapper for WarmDither {
+    fn map(&self, color: Rgb888, point: Point) -> TriColor {
+        // average of green and blue rounded down, without overflow
+        let (g, b) = (color.g(), color.b());
+        let gray = (g >> 1) + (b >> 1) + (g & b & 1);
+        if color.r() <= gray {
+            return dither(luma(color), point, TriColor::Black, TriColor::White);
+        }
+        // decompose into red, white and black portions which add up to 255
+        let red = color.r() - gray;
+        let white = gray;
+        let threshold = bayer_threshold(point);
+        if threshold < red {
+            TriColor::Red
+        } else if u16::from(threshold) < u16::from(red) + u16::from(white) {
+            TriColor::White
+        } else {
+            TriColor::Black
+        }
+    }
+}
+
+/// Picks the nearest of white, black and red in the CIELAB color space
+/// (ΔE 1976), which usually gives better results for photos than the
+/// simple heuristic of [`Threshold`].
+#[derive(Clone, Copy, Default)]
+pub struct Cielab;
+
+impl Cielab {
+    /// CIELAB values of the palette (D65 white point)
+    const PALETTE: [(TriColor, [f32; 3]); 3] = [
+        (TriColor::White, [100.0, 0.0, 0.0]),
+        (TriColor::Black, [0.0, 0.0, 0.0]),
+        (TriColor::Red, [53.24, 80.09, 67.20]),
+    ];
+}
+
+impl ColorMapper for Cielab {
+    fn map(&self, color: Rgb888, _point: Point) -> TriColor {
+        let [l, a, b] = to_lab(color);
+        let mut nearest = TriColor::White;
+        let mut min_distance = f32::MAX;
+        for (palette_color, [pl, pa, pb]) in Self::PALETTE {
+            let distance = (l - pl) * (l - pl) + (a - pa) * (a - pa) + (b - pb) * (b - pb);
+            if distance < min_distance {
+                min_distance = distance;
+                nearest = palette_color;
+            }
+        }
+        nearest
+    }
+}
+
+/// Linearizes sRGB colors (gamma correction) before passing them to the
+/// inner mapper. With dithering mappers the share of white pixels then
+/// matches the light intensity of the original color, so midtones are
+/// rendered darker (e.g. sRGB 128 becomes 55, about a fifth white).
+#[derive(Clone, Copy, Default)]
+pub struct GammaCorrected<M>(pub M);
+
+impl<M: ColorMapper> ColorMapper for GammaCorrected<M> {
+    fn map(&self, color: Rgb888, point: Point) -> TriColor {
+        self.0.map(linearize(color), point)
+    }
+}
+
+/// Draw target accepting `Rgb888` which converts all pixels with a
+/// [`ColorMapper`] before drawing them into the underlying `TriColor`
+/// target (e.g. a [`Display`](crate::Display)).
+pub struct MappedTarget<'a, D, M> {
+    target: &'a mut D,
+    mapper: M,
+}
+
+impl<'a, D, M> MappedTarget<'a, D, M>
+where
+    D: DrawTarget<Color = TriColor>,
+    M: ColorMapper,
+{
+    pub fn new(target: &'a mut D, mapper: M) -> Self {
+        Self { target, mapper }
+    }
+}
+
+impl<D, M> Dimensions for MappedTarget<'_, D, M>
+where
+    D: DrawTarget<Color = TriColor>,
+{
+    fn bounding_box(&self) -> Rectangle {
+        self.target.bounding_box()
+    }
+}
+
+impl<D, M> DrawTarget for MappedTarget<'_, D, M>
+where
+    D: DrawTarget<Color = TriColor>,
+    M: ColorMapper,
+{
+    type Color = Rgb888;
+    type Error = D::Error;
+
+    fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Pixel<Self::Color>>,
+    {
+        let mapper = &self.mapper;
+        self.target.draw_iter(
+            pixels
+                .into_iter()
+                .map(|Pixel(point, color)| Pixel(point, mapper.map(color, point))),
+        )
+    }
+}
+
+/// Convert sRGB to linear light
+pub(crate) fn linearize(color: Rgb888) -> Rgb888 {
+    Rgb888::new(
+        SRGB_TO_LINEAR[usize::from(color.r())],
+        SRGB_TO_LINEAR[usize::from(color.g())],
+        SRGB_TO_LINEAR[usize::from(color.b())],
+    )
+}
+
+/// Convert sRGB to CIELAB (D65 white point)
+#[allow(clippy::many_single_char_names)]
+fn to_lab(color: Rgb888) -> [f32; 3] {
+    let linear = linearize(color);
+    let r = f32::from(linear.r()) / 255.0;
+    let g = f32::from(linear.g()) / 255.0;
+    let b = f32::from(linear.b()) / 255.0;
+    let x = (0.412_456_4 * r + 0.357_576_1 * g + 0.180_437_5 * b) / 0.950_47;
+    let y = 0.212_672_9 * r + 0.715_152_2 * g + 0.072_175 * b;
+    let z = (0.019_333_9 * r + 0.119_192 * g + 0.950_304_1 * b) / 1.088_83;
+    let (fx, fy, fz) = (lab_f(x), lab_f(y), lab_f(z));
+    [116.0 * fy - 16.0, 500.0 * (fx - fy), 200.0 * (fy - fz)]
+}
+
+fn lab_f(t: f32) -> f32 {
+    const DELTA: f32 = 6.0 / 29.0;
+    if t > DELTA * DELTA * DELTA {
+        cbrt(t)
+    } else {
+        t / (3.0 * DELTA * DELTA) + 4.0 / 29.0
+    }
+}
+
+/// Cube root for positive values, as `f32::cbrt` is not available without std
+fn cbrt(x: f32) -> f32 {
+    // initial guess by dividing the exponent, refined with Newton's method
+    let mut y = f32::from_bits(x.to_bits() / 3 + 709_921_077);
+    for _ in 0..3 {
+        y = (2.0 * y + x / (y * y)) / 3.0;
+    }
+    y
+}
+
+/// Luminance of a color (ITU-R BT.601)
+pub(crate) fn luma(color: Rgb888) -> u8 {
+    let luma = 77 * u32::from(color.r()) + 150 * u32::from(color.g()) + 29 * u32::from(color.b());
+    u8::try_from(luma >> 8).unwrap_or(u8::MAX)
+}
+
+/// Map `level` from the range `low..=high` to `0..=255`
+pub(crate) fn scale(level: u8, low: u8, high: u8) -> u8 {
+    let range = u32::from(high - low).max(1);
+    let level = u32::from(level.clamp(low, high) - low) * 255 / range;
+    u8::try_from(level).unwrap_or(u8::MAX)
+}
+
+/// Dither threshold of a pixel in the range 8..=248
+#[allow(clippy::cast_sign_loss)]
+pub(crate) fn bayer_threshold(point: Point) -> u8 {
+    BAYER[(point.y & 3) as usize][(point.x & 3) as usize] * 16 + 8
+}
+
+/// Ordered dithering between `dark` (level 0) and `light` (level 255)
+pub(crate) fn dither(level: u8, point: Point, dark: TriColor, light: TriColor) -> TriColor {
+    if level >= bayer_threshold(point) {
+        light
+    } else {
+        dark
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Number of white pixels of a 4x4 dither cell filled with `color`
+    fn white_pixels(mapper: &impl ColorMapper, color: Rgb888) -> usize {
+        (0..4)
+            .flat_map(|y| (0..4).map(move |x| Point::new(x, y)))
+            .filter(|&point| mapper.map(color, point) == TriColor::White)
+            .count()
+    }
+
+    #[test]
+    fn srgb_to_linear_table() {
+        assert_eq!(SRGB_TO_LINEAR[0], 0);
+        assert_eq!(SRGB_TO_LINEAR[128], 55);
+        assert_eq!(SRGB_TO_LINEAR[255], 255);
+    }
+
+    #[test]
+    fn gamma_corrected_midtones_are_darker() {
+        let gray = Rgb888::new(128, 128, 128);
+        let dither = GrayDither { red_mixing: false };
+        assert_eq!(white_pixels(&dither, gray), 8);
+        // linear 55 is above the thresholds 8, 24 and 40 only
+        assert_eq!(white_pixels(&GammaCorrected(dither), gray), 3);
+    }
+}
@@ -0,0 +1,99 @@
+//! Drawing limited by 1-bpp masks, e.g. for round widgets or to protect a
+//! logo from being overdrawn
+
+use embedded_graphics::{
+    draw_target::DrawTarget,
+    geometry::{Dimensions, Point, Size},
+    primitives::Rectangle,
+    Pixel,
+};
+
+/// Mask of one bit per pixel placed at an area of the draw target. The
+/// rows are packed most significant bit first and padded to whole bytes,
+/// as the planes of a display or a raw PBM (P4) image. Only pixels set in
+/// the mask are drawn, or only pixels not set for an inverted mask.
+/// Pixels outside of the area count as not set.
+#[derive(Clone, Copy)]
+pub struct Mask<'a> {
+    data: &'a [u8],
+    area: Rectangle,
+    inverted: bool,
+}
+
+impl<'a> Mask<'a> {
+    /// Mask of `size` pixels with the top left corner at `top_left` (in
+    /// the coordinates of the draw target)
+    ///
+    /// # Panics
+    ///
+    /// Panics if `data` is shorter than the rows of `size`.
+    #[must_use]
+    pub fn new(data: &'a [u8], top_left: Point, size: Size) -> Self {
+        assert!(
+            data.len() >= size.width.div_ceil(8) as usize * size.height as usize,
+            "mask data is shorter than its size"
+        );
+        Self {
+            data,
+            area: Rectangle::new(top_left, size),
+            inverted: false,
+        }
+    }
+
+    /// Draw only the pixels which are not set in the mask, e.g. to protect
+    /// a logo from dynamic content drawn around it
+    #[must_use]
+    pub fn inverted(mut self) -> Self {
+        self.inverted = !self.inverted;
+        self
+    }
+
+    /// Whether a pixel at `point` is drawn through the mask
+    #[must_use]
+    #[allow(clippy::cast_sign_loss)]
+    pub fn allows(&self, point: Point) -> bool {
+        let set = self.area.contains(point) && {
+            let Point { x, y } = point - self.area.top_left;
+            let (x, y) = (x as usize, y as usize);
+            let bytes_per_row = self.area.size.width.div_ceil(8) as usize;
+            self.data[y * bytes_per_row + x / 8] & (0x80 >> (x % 8)) != 0
+        };
+        set != self.inverted
+    }
+}
+
+/// Draw target drawing only the pixels allowed by a [`Mask`] into the
+/// underlying target (e.g. a [`Display`](crate::Display))
+pub struct MaskedTarget<'a, 'm, D> {
+    target: &'a mut D,
+    mask: Mask<'m>,
+}
+
+impl<'a, 'm, D: DrawTarget> MaskedTarget<'a, 'm, D> {
+    pub fn new(target: &'a mut D, mask: Mask<'m>) -> Self {
+        Self { target, mask }
+    }
+}
+
+impl<D: DrawTarget> Dimensions for MaskedTarget<'_, '_, D> {
+    fn bounding_box(&self) -> Rectangle {
+        self.target.bounding_box()
+    }
+}
+
+impl<D: DrawTarget> DrawTarget for MaskedTarget<'_, '_, D> {
+    type Color = D::Color;
+    type Error = D::Error;
+
+    fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Pixel<Self::Color>>,
+    {
+        let mask = &self.mask;
+        self.target.draw_iter(
+            pixels
+                .into_iter()
+                .filter(|Pixel(point, _)| mask.allows(*point)),
+        )
+    }
+}
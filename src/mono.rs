@@ -0,0 +1,126 @@
+//! Black and white display buffers without a red plane
+
+use embedded_graphics::{
+    draw_target::DrawTarget,
+    geometry::{OriginDimensions, Point, Size},
+    Pixel,
+};
+
+use crate::{Display, DisplayBuffer, DisplayRotation, TriColor};
+
+/// Display buffer with only the black plane, which needs half the RAM of
+/// a [`Display`]. Red pixels are drawn as black and the red plane is sent
+/// as all zeros. The concrete types are dependent on the size.
+/// Examples: `MonoDisplay1in54`, `MonoDisplay2in13`, ...
+#[derive(Clone)]
+pub struct MonoDisplay<const SIZE_V: u32, const SIZE_H: u32, const IMAGE_SIZE: usize> {
+    buffer_black: [u8; IMAGE_SIZE],
+    rotation: DisplayRotation,
+}
+
+impl<const SIZE_V: u32, const SIZE_H: u32, const IMAGE_SIZE: usize>
+    MonoDisplay<SIZE_V, SIZE_H, IMAGE_SIZE>
+{
+    /// Empty (white) display in its natural orientation, same as `default()`
+    #[must_use]
+    pub const fn new() -> Self {
+        let () = Display::<SIZE_V, SIZE_H, IMAGE_SIZE>::GEOMETRY;
+        Self {
+            buffer_black: [0; IMAGE_SIZE],
+            rotation: Display::<SIZE_V, SIZE_H, IMAGE_SIZE>::NATURAL_ROTATION,
+        }
+    }
+
+    pub fn set_rotation(&mut self, rotation: DisplayRotation) {
+        self.rotation = rotation;
+    }
+    #[must_use]
+    pub fn rotation(&self) -> DisplayRotation {
+        self.rotation
+    }
+
+    /// Color of a pixel given in rotated coordinates,
+    /// `None` if the pixel is outside of the display
+    #[must_use]
+    pub fn get_pixel(&self, point: Point) -> Option<TriColor> {
+        let (x, y) =
+            Display::<SIZE_V, SIZE_H, IMAGE_SIZE>::rotate_point(self.rotation, point.x, point.y);
+        let (index, mask) = Display::<SIZE_V, SIZE_H, IMAGE_SIZE>::pixel_position(x, y)?;
+        if self.buffer_black[index] & mask != 0 {
+            Some(TriColor::Black)
+        } else {
+            Some(TriColor::White)
+        }
+    }
+
+    fn set_pixel(&mut self, point: Point, color: TriColor) {
+        let (x, y) =
+            Display::<SIZE_V, SIZE_H, IMAGE_SIZE>::rotate_point(self.rotation, point.x, point.y);
+        let Some((index, mask)) = Display::<SIZE_V, SIZE_H, IMAGE_SIZE>::pixel_position(x, y)
+        else {
+            return;
+        };
+        match color {
+            TriColor::White => self.buffer_black[index] &= !mask,
+            TriColor::Black | TriColor::Red => self.buffer_black[index] |= mask,
+        }
+    }
+}
+
+impl<const SIZE_V: u32, const SIZE_H: u32, const IMAGE_SIZE: usize> DisplayBuffer
+    for MonoDisplay<SIZE_V, SIZE_H, IMAGE_SIZE>
+{
+    fn get_buffer_black(&self) -> &[u8] {
+        &self.buffer_black
+    }
+    fn get_buffer_red(&self) -> &[u8] {
+        &[]
+    }
+}
+
+impl<const SIZE_V: u32, const SIZE_H: u32, const IMAGE_SIZE: usize> Default
+    for MonoDisplay<SIZE_V, SIZE_H, IMAGE_SIZE>
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<const SIZE_V: u32, const SIZE_H: u32, const IMAGE_SIZE: usize> OriginDimensions
+    for MonoDisplay<SIZE_V, SIZE_H, IMAGE_SIZE>
+{
+    fn size(&self) -> Size {
+        Display::<SIZE_V, SIZE_H, IMAGE_SIZE>::rotated_size(self.rotation)
+    }
+}
+
+impl<const SIZE_V: u32, const SIZE_H: u32, const IMAGE_SIZE: usize> DrawTarget
+    for MonoDisplay<SIZE_V, SIZE_H, IMAGE_SIZE>
+{
+    type Color = TriColor;
+    type Error = core::convert::Infallible;
+
+    fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Pixel<Self::Color>>,
+    {
+        for Pixel(point, color) in pixels {
+            self.set_pixel(point, color);
+        }
+        Ok(())
+    }
+}
+
+macro_rules! mono_display_type {
+    ($a:expr, $b:expr) => {
+        MonoDisplay<$a, $b, { $crate::image_size($a, $b) }>
+    };
+}
+pub type MonoDisplay1in54 = mono_display_type!(152, 152);
+pub type MonoDisplay2in13 = mono_display_type!(212, 104);
+pub type MonoDisplay2in66 = mono_display_type!(296, 152);
+pub type MonoDisplay2in71 = mono_display_type!(264, 176);
+pub type MonoDisplay2in87 = mono_display_type!(296, 128);
+pub type MonoDisplay3in70 = mono_display_type!(416, 240);
+pub type MonoDisplay4in17 = mono_display_type!(300, 400);
+pub type MonoDisplay4in37 = mono_display_type!(480, 176);
@@ -0,0 +1,192 @@
+//! Planes of a display as standalone buffers, which can be detached from a
+//! display, stored (e.g. as cache of a static background) and attached again
+
+use embedded_graphics::{
+    draw_target::DrawTarget,
+    geometry::{OriginDimensions, Point, Size},
+    pixelcolor::BinaryColor,
+    Pixel,
+};
+
+use crate::{BufferStorage, Display, DisplayRotation, Plane};
+
+/// One plane of a display with its own memory, as `BinaryColor` draw
+/// target. `On` sets a pixel (black or red, depending on where the plane is
+/// attached), `Off` clears it. Unlike a [`Layer`](crate::Layer) it does not
+/// borrow a display, see `Display::into_planes` and `Display::replace_plane`.
+#[derive(Clone)]
+pub struct PlaneBuffer<
+    const SIZE_V: u32,
+    const SIZE_H: u32,
+    const IMAGE_SIZE: usize,
+    B = [u8; IMAGE_SIZE],
+> {
+    buffer: B,
+    rotation: DisplayRotation,
+}
+
+impl<const SIZE_V: u32, const SIZE_H: u32, const IMAGE_SIZE: usize>
+    PlaneBuffer<SIZE_V, SIZE_H, IMAGE_SIZE>
+{
+    /// Empty plane in the natural orientation of the panel, same as `default()`
+    #[must_use]
+    pub const fn new() -> Self {
+        let () = Display::<SIZE_V, SIZE_H, IMAGE_SIZE>::GEOMETRY;
+        Self {
+            buffer: [0; IMAGE_SIZE],
+            rotation: Display::<SIZE_V, SIZE_H, IMAGE_SIZE>::NATURAL_ROTATION,
+        }
+    }
+}
+
+impl<const SIZE_V: u32, const SIZE_H: u32, const IMAGE_SIZE: usize, B: BufferStorage>
+    PlaneBuffer<SIZE_V, SIZE_H, IMAGE_SIZE, B>
+{
+    /// Plane using the given memory, the content of the memory is kept
+    ///
+    /// # Panics
+    ///
+    /// Panics if the storage is smaller than `IMAGE_SIZE`.
+    pub fn from_storage(buffer: B, rotation: DisplayRotation) -> Self {
+        let () = Display::<SIZE_V, SIZE_H, IMAGE_SIZE, B>::GEOMETRY;
+        assert!(buffer.as_slice().len() >= IMAGE_SIZE);
+        Self { buffer, rotation }
+    }
+
+    /// Release the memory of the plane
+    pub fn into_storage(self) -> B {
+        self.buffer
+    }
+
+    pub fn set_rotation(&mut self, rotation: DisplayRotation) {
+        self.rotation = rotation;
+    }
+    #[must_use]
+    pub fn rotation(&self) -> DisplayRotation {
+        self.rotation
+    }
+
+    /// Raw plane data in panel coordinates, as sent to the e-paper
+    #[must_use]
+    pub fn data(&self) -> &[u8] {
+        &self.buffer.as_slice()[..IMAGE_SIZE]
+    }
+
+    /// Mutable raw plane data in panel coordinates
+    pub fn data_mut(&mut self) -> &mut [u8] {
+        &mut self.buffer.as_mut_slice()[..IMAGE_SIZE]
+    }
+
+    /// Whether a pixel given in rotated coordinates is set,
+    /// `None` if the pixel is outside of the plane
+    #[must_use]
+    pub fn get_pixel(&self, point: Point) -> Option<BinaryColor> {
+        let (x, y) =
+            Display::<SIZE_V, SIZE_H, IMAGE_SIZE, B>::rotate_point(self.rotation, point.x, point.y);
+        let (index, mask) = Display::<SIZE_V, SIZE_H, IMAGE_SIZE, B>::pixel_position(x, y)?;
+        Some(BinaryColor::from(self.buffer.as_slice()[index] & mask != 0))
+    }
+
+    fn set_pixel(&mut self, point: Point, on: bool) {
+        let (x, y) =
+            Display::<SIZE_V, SIZE_H, IMAGE_SIZE, B>::rotate_point(self.rotation, point.x, point.y);
+        let Some((index, mask)) = Display::<SIZE_V, SIZE_H, IMAGE_SIZE, B>::pixel_position(x, y)
+        else {
+            return;
+        };
+        let byte = &mut self.buffer.as_mut_slice()[index];
+        if on {
+            *byte |= mask;
+        } else {
+            *byte &= !mask;
+        }
+    }
+}
+
+impl<const SIZE_V: u32, const SIZE_H: u32, const IMAGE_SIZE: usize, B: BufferStorage>
+    Display<SIZE_V, SIZE_H, IMAGE_SIZE, B>
+{
+    /// Split the display into its planes (black, red), both with the
+    /// rotation of the display
+    pub fn into_planes(
+        self,
+    ) -> (
+        PlaneBuffer<SIZE_V, SIZE_H, IMAGE_SIZE, B>,
+        PlaneBuffer<SIZE_V, SIZE_H, IMAGE_SIZE, B>,
+    ) {
+        let rotation = self.rotation();
+        let (black, red) = self.into_storage();
+        (
+            PlaneBuffer::from_storage(black, rotation),
+            PlaneBuffer::from_storage(red, rotation),
+        )
+    }
+
+    /// Display made of two planes, with the rotation of the black plane.
+    /// Pixels set in both planes are shown red.
+    pub fn from_planes(
+        black: PlaneBuffer<SIZE_V, SIZE_H, IMAGE_SIZE, B>,
+        red: PlaneBuffer<SIZE_V, SIZE_H, IMAGE_SIZE, B>,
+    ) -> Self {
+        let rotation = black.rotation;
+        let mut display = Self::from_storage(black.buffer, red.buffer);
+        display.set_rotation(rotation);
+        display
+    }
+
+    /// Attach `buffer` as `plane` and return the detached plane (with the
+    /// rotation of the display), e.g. to swap in a cached background
+    /// without copying it. Pixels set in both planes are shown red.
+    pub fn replace_plane(
+        &mut self,
+        plane: Plane,
+        buffer: PlaneBuffer<SIZE_V, SIZE_H, IMAGE_SIZE, B>,
+    ) -> PlaneBuffer<SIZE_V, SIZE_H, IMAGE_SIZE, B> {
+        let storage = core::mem::replace(self.storage_mut(plane), buffer.buffer);
+        PlaneBuffer::from_storage(storage, self.rotation())
+    }
+}
+
+impl<const SIZE_V: u32, const SIZE_H: u32, const IMAGE_SIZE: usize, B: BufferStorage + Clone>
+    Display<SIZE_V, SIZE_H, IMAGE_SIZE, B>
+{
+    /// Copy of `plane` with the rotation of the display, e.g. to cache a
+    /// rendered plane
+    #[must_use]
+    pub fn plane(&self, plane: Plane) -> PlaneBuffer<SIZE_V, SIZE_H, IMAGE_SIZE, B> {
+        PlaneBuffer::from_storage(self.storage(plane).clone(), self.rotation())
+    }
+}
+
+impl<const SIZE_V: u32, const SIZE_H: u32, const IMAGE_SIZE: usize> Default
+    for PlaneBuffer<SIZE_V, SIZE_H, IMAGE_SIZE>
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<const SIZE_V: u32, const SIZE_H: u32, const IMAGE_SIZE: usize, B: BufferStorage>
+    OriginDimensions for PlaneBuffer<SIZE_V, SIZE_H, IMAGE_SIZE, B>
+{
+    fn size(&self) -> Size {
+        Display::<SIZE_V, SIZE_H, IMAGE_SIZE, B>::rotated_size(self.rotation)
+    }
+}
+
+impl<const SIZE_V: u32, const SIZE_H: u32, const IMAGE_SIZE: usize, B: BufferStorage> DrawTarget
+    for PlaneBuffer<SIZE_V, SIZE_H, IMAGE_SIZE, B>
+{
+    type Color = BinaryColor;
+    type Error = core::convert::Infallible;
+
+    fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Pixel<Self::Color>>,
+    {
+        for Pixel(point, color) in pixels {
+            self.set_pixel(point, color.is_on());
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,228 @@
+//! Queue of deferred updates, handing off work from interrupts to the main loop
+
+use core::cmp::Ordering;
+
+use embedded_hal::{delay::DelayNs, digital::InputPin, digital::OutputPin, spi::SpiDevice};
+use heapless::binary_heap::{BinaryHeap, Max};
+
+use crate::{driver::EpdError, Active, DoubleDisplay, Epd};
+
+/// Refresh done for a queued update
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum UpdateKind {
+    /// whole frame with a full refresh
+    Frame,
+    /// only the region changed since the last update, see `Epd::update_changed`
+    Region,
+}
+
+/// Queued update, ordered by priority and then by the order of `push`
+struct Entry<T> {
+    priority: u8,
+    sequence: u32,
+    kind: UpdateKind,
+    item: T,
+}
+
+impl<T> PartialEq for Entry<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl<T> Eq for Entry<T> {}
+
+impl<T> PartialOrd for Entry<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<T> Ord for Entry<T> {
+    #[allow(clippy::cast_possible_wrap)]
+    fn cmp(&self, other: &Self) -> Ordering {
+        // earlier entries are greater, the sequence number may wrap around
+        let age = other.sequence.wrapping_sub(self.sequence) as i32;
+        self.priority.cmp(&other.priority).then(age.cmp(&0))
+    }
+}
+
+/// Up to `N` pending updates with priorities. Producers like interrupt
+/// handlers only `push` an application defined item describing the update
+/// (e.g. the id of a page or a new sensor value), the main loop draws and
+/// shows it with `service`. Items with a higher priority are served first,
+/// items with the same priority in the order they were pushed. To share
+/// the queue with an interrupt, put it into a mutex, e.g. of the
+/// `critical-section` crate.
+pub struct UpdateQueue<T, const N: usize> {
+    heap: BinaryHeap<Entry<T>, Max, N>,
+    sequence: u32,
+}
+
+impl<T, const N: usize> Default for UpdateQueue<T, N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T, const N: usize> UpdateQueue<T, N> {
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            heap: BinaryHeap::new(),
+            sequence: 0,
+        }
+    }
+
+    /// Queue `item` with `priority` (higher is served first). If the queue
+    /// is full, the item is given back.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error with `item` if the queue is full.
+    pub fn push(&mut self, priority: u8, kind: UpdateKind, item: T) -> Result<(), T> {
+        let entry = Entry {
+            priority,
+            sequence: self.sequence,
+            kind,
+            item,
+        };
+        self.heap.push(entry).map_err(|entry| entry.item)?;
+        self.sequence = self.sequence.wrapping_add(1);
+        Ok(())
+    }
+
+    /// Number of pending updates
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.heap.len()
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.heap.is_empty()
+    }
+
+    /// Drop all pending updates
+    pub fn clear(&mut self) {
+        self.heap.clear();
+    }
+
+    /// Perform at most one pending update: the item with the highest
+    /// priority is drawn into `display` with `render` and shown, as a
+    /// frame or only the changed region depending on its kind. Nothing is
+    /// done while the rate limit of `epd` (see `Epd::set_rate_limit`)
+    /// blocks the next refresh, so the function never waits for it.
+    /// Returns `true` if a refresh was done. This function is blocking
+    /// until the update process is complete.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if there is an error
+    /// with the GPIOs or the SPI device or if the refresh was vetoed by the
+    /// refresh guard. The item stays in the queue in this case.
+    pub fn service<
+        SPI,
+        BUSY,
+        DC,
+        RST,
+        const SIZE_V: u32,
+        const SIZE_H: u32,
+        const IMAGE_SIZE: usize,
+    >(
+        &mut self,
+        display: &mut DoubleDisplay<SIZE_V, SIZE_H, IMAGE_SIZE>,
+        render: impl FnOnce(&T, &mut DoubleDisplay<SIZE_V, SIZE_H, IMAGE_SIZE>),
+        epd: &mut Epd<Active, BUSY, DC, RST>,
+        spi: &mut SPI,
+        delay: &mut impl DelayNs,
+    ) -> Result<bool, EpdError<SPI, DC, RST>>
+    where
+        SPI: SpiDevice,
+        BUSY: InputPin,
+        DC: OutputPin,
+        RST: OutputPin,
+    {
+        if self.heap.is_empty() || epd.refresh_blocked_ms() > 0 {
+            return Ok(false);
+        }
+        let Some(entry) = self.heap.pop() else {
+            return Ok(false);
+        };
+        render(&entry.item, display);
+        let result = match entry.kind {
+            UpdateKind::Frame => epd.update(display.current(), spi, delay).map(|()| {
+                display.mark_shown();
+                true
+            }),
+            UpdateKind::Region => epd.update_changed(display, spi, delay),
+        };
+        if result.is_err() {
+            // there is space for the entry again since it was just popped
+            let _ = self.heap.push(entry);
+        }
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Items in the order they are served
+    fn drain<const N: usize>(queue: &mut UpdateQueue<char, N>) -> impl Iterator<Item = char> + '_ {
+        core::iter::from_fn(|| queue.heap.pop().map(|entry| entry.item))
+    }
+
+    #[test]
+    fn priority_then_fifo() {
+        let mut queue = UpdateQueue::<char, 4>::new();
+        for (priority, item) in [(1, 'a'), (2, 'b'), (1, 'c'), (2, 'd')] {
+            queue.push(priority, UpdateKind::Frame, item).unwrap();
+        }
+        assert_eq!(queue.push(3, UpdateKind::Frame, 'e'), Err('e'));
+        assert!(drain(&mut queue).eq(['b', 'd', 'a', 'c']));
+    }
+
+    #[test]
+    fn fifo_across_sequence_wrap_around() {
+        let mut queue = UpdateQueue::<char, 4>::new();
+        queue.sequence = u32::MAX - 1;
+        for item in ['a', 'b', 'c', 'd'] {
+            queue.push(0, UpdateKind::Region, item).unwrap();
+        }
+        assert_eq!(queue.sequence, 2);
+        assert!(drain(&mut queue).eq(['a', 'b', 'c', 'd']));
+    }
+
+    #[cfg(feature = "testing")]
+    #[test]
+    fn entry_is_kept_when_service_fails() {
+        use crate::{driver::Error, testing::Recorder};
+
+        let recorder = Recorder::new();
+        let mut spi = recorder.spi();
+        let mut delay = recorder.delay();
+        let mut epd = Epd::new(recorder.busy(), recorder.dc(), recorder.rst(), 0)
+            .init(&mut spi, &mut delay)
+            .unwrap();
+        epd.set_refresh_guard(Some(|| false));
+        let mut display = DoubleDisplay::<296, 152, { 296 * 19 }>::default();
+        let mut queue = UpdateQueue::<char, 2>::new();
+        queue.push(0, UpdateKind::Frame, 'a').unwrap();
+        queue.push(1, UpdateKind::Frame, 'b').unwrap();
+
+        let mut rendered = None;
+        let result = queue.service(
+            &mut display,
+            |&item, _| rendered = Some(item),
+            &mut epd,
+            &mut spi,
+            &mut delay,
+        );
+        assert!(matches!(result, Err(Error::RefreshVetoed)));
+        assert_eq!(rendered, Some('b'));
+        assert_eq!(queue.len(), 2);
+        assert!(drain(&mut queue).eq(['b', 'a']));
+    }
+}
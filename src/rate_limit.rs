@@ -0,0 +1,29 @@
+//! Minimum interval between refreshes, protecting the panel from
+//! application loops refreshing too often
+
+/// Monotonic time source, e.g. a timer of the MCU or `std::time::Instant`.
+/// The clock is shared by reference, so it has to be `Sync` (as every
+/// `static` is) to keep the driver `Send`.
+pub trait Clock: Sync {
+    /// Milliseconds since an arbitrary start, wrapping around at `u32::MAX`
+    fn now_ms(&self) -> u32;
+}
+
+/// Behaviour of the driver if a refresh is requested too soon
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum RateLimitPolicy {
+    /// Block with the delay until the interval has elapsed
+    Wait,
+    /// Fail with `Error::TooSoon`, the uploaded buffers are kept
+    Reject,
+}
+
+/// Minimum interval between the start of two refreshes, see
+/// `Epd::set_rate_limit`. Check the datasheet of your panel for the
+/// minimum interval.
+#[derive(Clone, Copy)]
+pub struct RateLimit {
+    pub clock: &'static dyn Clock,
+    pub min_interval_ms: u32,
+    pub policy: RateLimitPolicy,
+}
@@ -0,0 +1,288 @@
+//! Reconstruction of the displayed frame from a recorded command stream,
+//! e.g. for debugging garbled output, and previews with simulated e-paper
+//! artifacts for evaluating refresh strategies.
+
+use std::{
+    io::{self, BufRead, Write},
+    vec::Vec,
+};
+
+use embedded_graphics::{
+    geometry::{OriginDimensions, Point},
+    pixelcolor::{Rgb888, RgbColor},
+};
+
+use crate::{testing::Record, Command, Display, DisplayRotation, TriColor};
+
+/// Parse a command stream exported from a logic analyzer. Each line
+/// contains one byte, the last two comma separated columns are the level
+/// of the DC pin (`0` or `1`) and the byte on MOSI (decimal or hex with
+/// `0x` prefix). Further leading columns (e.g. a timestamp) and lines
+/// which cannot be parsed (e.g. a header) are ignored.
+///
+/// # Errors
+///
+/// This function will return an error if reading fails.
+pub fn parse_csv(reader: impl BufRead) -> io::Result<Vec<Record>> {
+    let mut records = Vec::new();
+    for line in reader.lines() {
+        let line = line?;
+        let mut columns = line.rsplit(',').map(str::trim);
+        let (Some(value), Some(dc)) = (columns.next(), columns.next()) else {
+            continue;
+        };
+        let value = match value
+            .strip_prefix("0x")
+            .or_else(|| value.strip_prefix("0X"))
+        {
+            Some(hex) => u8::from_str_radix(hex, 16),
+            None => value.parse(),
+        };
+        let Ok(value) = value else {
+            continue;
+        };
+        match dc {
+            "0" => records.push(Record::Command {
+                command: value,
+                data: Vec::new(),
+            }),
+            "1" => {
+                if let Some(Record::Command { data, .. }) = records.last_mut() {
+                    data.push(value);
+                }
+            }
+            _ => {}
+        }
+    }
+    Ok(records)
+}
+
+/// Buffer RAM of the COG as written by the recorded commands
+struct Cog<const SIZE_V: u32, const SIZE_H: u32, const IMAGE_SIZE: usize> {
+    ram: Display<SIZE_V, SIZE_H, IMAGE_SIZE>,
+    partial: bool,
+    /// byte columns and rows of the partial window (inclusive)
+    window: (usize, usize, usize, usize),
+}
+
+impl<const SIZE_V: u32, const SIZE_H: u32, const IMAGE_SIZE: usize>
+    Cog<SIZE_V, SIZE_H, IMAGE_SIZE>
+{
+    const BYTES_PER_ROW: usize = Display::<SIZE_V, SIZE_H, IMAGE_SIZE>::BYTES_PER_ROW;
+
+    fn new() -> Self {
+        let mut ram = Display::default();
+        ram.set_rotation(DisplayRotation::Rotate0);
+        Self {
+            ram,
+            partial: false,
+            window: Self::full_window(),
+        }
+    }
+
+    fn full_window() -> (usize, usize, usize, usize) {
+        (
+            0,
+            Self::BYTES_PER_ROW.saturating_sub(1),
+            0,
+            SIZE_V as usize - 1,
+        )
+    }
+
+    /// Area updated by a refresh, in pixels (inclusive)
+    fn refreshed_window(&self) -> (usize, usize, usize, usize) {
+        let (x_start, x_end, y_start, y_end) = if self.partial {
+            self.window
+        } else {
+            Self::full_window()
+        };
+        (x_start * 8, x_end * 8 + 7, y_start, y_end)
+    }
+
+    /// Apply a command to the RAM, returns true for a refresh
+    fn apply(&mut self, command: u8, data: &[u8]) -> bool {
+        let bytes_per_row = Self::BYTES_PER_ROW;
+        let (black, red) = self.ram.buffers_mut();
+        match command {
+            c if c == Command::PartialIn as u8 => self.partial = true,
+            c if c == Command::PartialOut as u8 => self.partial = false,
+            c if c == Command::PartialWindow as u8 && data.len() >= 8 => {
+                let value = |i: usize| usize::from(u16::from_be_bytes([data[i], data[i + 1]]));
+                self.window = (value(0) / 8, value(2) / 8, value(4), value(6));
+            }
+            c if c == Command::BufferBlack as u8 || c == Command::BufferRed as u8 => {
+                let buffer = if c == Command::BufferBlack as u8 {
+                    black
+                } else {
+                    red
+                };
+                if self.partial {
+                    let (x_start, x_end, y_start, y_end) = self.window;
+                    let width = x_end.saturating_sub(x_start) + 1;
+                    let rows = (y_start..=y_end).zip(data.chunks(width));
+                    for (y, row) in rows {
+                        let start = y * bytes_per_row + x_start;
+                        let len = row.len().min(bytes_per_row.saturating_sub(x_start));
+                        if let Some(target) = buffer.get_mut(start..start + len) {
+                            target.copy_from_slice(&row[..len]);
+                        }
+                    }
+                } else {
+                    let len = data.len().min(IMAGE_SIZE);
+                    buffer[..len].copy_from_slice(&data[..len]);
+                }
+            }
+            c if c == Command::Refresh as u8 => return true,
+            _ => {}
+        }
+        false
+    }
+}
+
+/// Reconstruct the frame shown after the last refresh in `records` by
+/// simulating the buffer RAM of the COG, including partial windows.
+/// If there is no refresh, the current content of the RAM is returned.
+#[must_use]
+pub fn reconstruct<const SIZE_V: u32, const SIZE_H: u32, const IMAGE_SIZE: usize>(
+    records: &[Record],
+) -> Display<SIZE_V, SIZE_H, IMAGE_SIZE> {
+    let mut cog = Cog::<SIZE_V, SIZE_H, IMAGE_SIZE>::new();
+    let mut shown = None;
+    for record in records {
+        let Record::Command { command, data } = record else {
+            continue;
+        };
+        if cog.apply(*command, data) {
+            shown = Some(cog.ram.clone());
+        }
+    }
+    shown.unwrap_or(cog.ram)
+}
+
+/// E-paper artifacts simulated by [`simulate`]. The default simulates an
+/// ideal panel.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub struct Artifacts {
+    /// share of the previous content in percent which stays visible in
+    /// refreshed pixels (ghosting)
+    pub ghosting: u8,
+    /// number of intermediate frames of each refresh, showing the slow
+    /// transition from the previous to the new content
+    pub transition_steps: u8,
+}
+
+/// Image of the panel as seen by the user, see [`simulate`]
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Preview {
+    pub width: u32,
+    pub height: u32,
+    /// pixels row by row
+    pub pixels: Vec<Rgb888>,
+}
+
+impl Preview {
+    fn new(width: u32, height: u32) -> Self {
+        Self {
+            width,
+            height,
+            pixels: std::vec![Rgb888::WHITE; width as usize * height as usize],
+        }
+    }
+
+    /// Write the preview as binary PPM image
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if writing fails.
+    pub fn write_ppm(&self, mut writer: impl Write) -> io::Result<()> {
+        write!(writer, "P6\n{} {}\n255\n", self.width, self.height)?;
+        for color in &self.pixels {
+            writer.write_all(&[color.r(), color.g(), color.b()])?;
+        }
+        Ok(())
+    }
+}
+
+/// Simulate what the panel shows over the course of `records`, with the
+/// given `artifacts`. Returns the intermediate frames and the final frame
+/// of each refresh. Only the pixels of the partial window are refreshed,
+/// so ghosting accumulates outside of it like on a real panel. The
+/// preview is in panel coordinates (without rotation).
+#[must_use]
+#[allow(clippy::cast_possible_truncation, clippy::cast_possible_wrap)]
+pub fn simulate<const SIZE_V: u32, const SIZE_H: u32, const IMAGE_SIZE: usize>(
+    records: &[Record],
+    artifacts: Artifacts,
+) -> Vec<Preview> {
+    let mut cog = Cog::<SIZE_V, SIZE_H, IMAGE_SIZE>::new();
+    let mut shown = Preview::new(SIZE_H, SIZE_V);
+    let mut frames = Vec::new();
+    let ghosting = artifacts.ghosting.min(100);
+    for record in records {
+        let Record::Command { command, data } = record else {
+            continue;
+        };
+        if !cog.apply(*command, data) {
+            continue;
+        }
+        let (x_start, x_end, y_start, y_end) = cog.refreshed_window();
+        let mut target = shown.clone();
+        for y in y_start..=y_end.min(SIZE_V as usize - 1) {
+            for x in x_start..=x_end.min(SIZE_H as usize - 1) {
+                let point = Point::new(x as i32, y as i32);
+                let color = cog.ram.get_pixel(point).unwrap_or(TriColor::White);
+                let pixel = &mut target.pixels[y * SIZE_H as usize + x];
+                *pixel = mix(Rgb888::from(color), *pixel, ghosting);
+            }
+        }
+        let steps = u32::from(artifacts.transition_steps);
+        for step in 1..=steps {
+            let progress = u8::try_from(step * 100 / (steps + 1)).unwrap_or(100);
+            let mut frame = shown.clone();
+            for (pixel, new) in frame.pixels.iter_mut().zip(&target.pixels) {
+                *pixel = mix(*new, *pixel, 100 - progress);
+            }
+            frames.push(frame);
+        }
+        frames.push(target.clone());
+        shown = target;
+    }
+    frames
+}
+
+/// Mix `share` percent of `old` into `new`
+#[allow(clippy::cast_possible_truncation)]
+fn mix(new: Rgb888, old: Rgb888, share: u8) -> Rgb888 {
+    let share = u16::from(share);
+    let channel =
+        |new: u8, old: u8| ((u16::from(new) * (100 - share) + u16::from(old) * share) / 100) as u8;
+    Rgb888::new(
+        channel(new.r(), old.r()),
+        channel(new.g(), old.g()),
+        channel(new.b(), old.b()),
+    )
+}
+
+/// Write the display content as binary PPM image
+///
+/// # Errors
+///
+/// This function will return an error if writing fails.
+#[allow(clippy::cast_possible_wrap)]
+pub fn write_ppm<const SIZE_V: u32, const SIZE_H: u32, const IMAGE_SIZE: usize>(
+    display: &Display<SIZE_V, SIZE_H, IMAGE_SIZE>,
+    mut writer: impl Write,
+) -> io::Result<()> {
+    let size = display.size();
+    write!(writer, "P6\n{} {}\n255\n", size.width, size.height)?;
+    for y in 0..size.height as i32 {
+        for x in 0..size.width as i32 {
+            let color = display
+                .get_pixel(Point::new(x, y))
+                .unwrap_or(TriColor::White);
+            let color = Rgb888::from(color);
+            writer.write_all(&[color.r(), color.g(), color.b()])?;
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,289 @@
+//! Planning of refreshes, coalescing update requests of the application
+//! under limits of the panel and the installation
+
+const HOUR: u64 = 60 * 60;
+const DAY: u64 = 24 * HOUR;
+
+/// Time of day without refreshes, e.g. at night, in seconds since
+/// midnight. The end is excluded, an end before the start wraps around
+/// midnight (e.g. 22:00 to 6:00).
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct QuietHours {
+    /// first second of the quiet hours
+    pub start: u32,
+    /// first second after the quiet hours, before `start` if they span
+    /// midnight
+    pub end: u32,
+}
+
+impl QuietHours {
+    /// Whether `time` (seconds of local time) is within the quiet hours
+    #[must_use]
+    pub fn contains(&self, time: u64) -> bool {
+        let time_of_day = time % DAY;
+        let (start, end) = (u64::from(self.start), u64::from(self.end));
+        if start <= end {
+            (start..end).contains(&time_of_day)
+        } else {
+            time_of_day >= start || time_of_day < end
+        }
+    }
+
+    /// End of the quiet hours containing `time`
+    fn end_after(&self, time: u64) -> u64 {
+        let midnight = time - time % DAY;
+        let end = midnight + u64::from(self.end);
+        if end > time {
+            end
+        } else {
+            end + DAY
+        }
+    }
+}
+
+/// Limits of a [`RefreshScheduler`]
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub struct ScheduleConfig {
+    /// no refreshes in these hours, requests are served at their end
+    pub quiet_hours: Option<QuietHours>,
+    /// seconds between mandatory full refreshes clearing the ghosting of
+    /// partial updates, `None` for no periodic full refreshes
+    pub full_refresh_interval: Option<u64>,
+}
+
+/// Next step of the application loop, see [`RefreshScheduler::next_action`]
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ScheduledAction {
+    /// nothing to do before `until`, or before the next request if `None`
+    Sleep { until: Option<u64> },
+    /// update the panel now, with a full refresh if `full`, and report it
+    /// with `RefreshScheduler::record_update`
+    Update { full: bool },
+}
+
+/// Planner for the refreshes of a panel, allowing at most `MAX_PER_HOUR`
+/// refreshes within any hour. The application requests updates with a
+/// deadline, all requests until the deadline of the earliest one are
+/// served with a single refresh. Times are seconds of local time (e.g. of
+/// a RTC), so the quiet hours match the time of day. The scheduler only
+/// plans, it is polled with `next_action` by the application loop, which
+/// sleeps or updates the panel.
+pub struct RefreshScheduler<const MAX_PER_HOUR: usize> {
+    config: ScheduleConfig,
+    /// times of the last refreshes, `next` is the oldest once all are used
+    history: [Option<u64>; MAX_PER_HOUR],
+    next: usize,
+    last_full_refresh: Option<u64>,
+    pending: Option<u64>,
+}
+
+impl<const MAX_PER_HOUR: usize> RefreshScheduler<MAX_PER_HOUR> {
+    /// Evaluated when a scheduler is created, so a limit of 0 is a
+    /// compile error
+    const LIMIT: () = assert!(MAX_PER_HOUR > 0, "at least one refresh per hour");
+
+    /// Scheduler without refreshes so far, so a full refresh is due
+    /// immediately with a `full_refresh_interval`
+    #[must_use]
+    pub const fn new(config: ScheduleConfig) -> Self {
+        let () = Self::LIMIT;
+        Self {
+            config,
+            history: [None; MAX_PER_HOUR],
+            next: 0,
+            last_full_refresh: None,
+            pending: None,
+        }
+    }
+
+    #[must_use]
+    pub fn config(&self) -> ScheduleConfig {
+        self.config
+    }
+
+    /// Time of the last full refresh, e.g. restored from a `UsageRecord`
+    /// after a reset
+    pub fn set_last_full_refresh(&mut self, time: Option<u64>) {
+        self.last_full_refresh = time;
+    }
+    #[must_use]
+    pub fn last_full_refresh(&self) -> Option<u64> {
+        self.last_full_refresh
+    }
+
+    /// Request an update until `deadline`, a deadline in the past is
+    /// served as soon as the limits allow it
+    pub fn request(&mut self, deadline: u64) {
+        self.pending = Some(
+            self.pending
+                .map_or(deadline, |pending| pending.min(deadline)),
+        );
+    }
+
+    /// Deadline of the pending requests, `None` if there are none
+    #[must_use]
+    pub fn pending(&self) -> Option<u64> {
+        self.pending
+    }
+
+    /// Next step at `now`: an update if a request or a mandatory full
+    /// refresh is due and neither the limit per hour nor the quiet hours
+    /// prevent it, otherwise the time to sleep until
+    #[must_use]
+    pub fn next_action(&self, now: u64) -> ScheduledAction {
+        let full_due = self.full_refresh_due();
+        let Some(wanted) = self.pending.into_iter().chain(full_due).min() else {
+            return ScheduledAction::Sleep { until: None };
+        };
+        let mut at = wanted.max(self.rate_allowed());
+        if let Some(quiet) = self.config.quiet_hours {
+            if quiet.contains(at) {
+                at = quiet.end_after(at);
+            }
+        }
+        if at > now {
+            return ScheduledAction::Sleep { until: Some(at) };
+        }
+        ScheduledAction::Update {
+            full: full_due.is_some_and(|due| due <= now),
+        }
+    }
+
+    /// Report an update of the panel at `now`, which serves all pending
+    /// requests
+    pub fn record_update(&mut self, now: u64, full: bool) {
+        self.history[self.next] = Some(now);
+        self.next = (self.next + 1) % MAX_PER_HOUR;
+        if full {
+            self.last_full_refresh = Some(now);
+        }
+        self.pending = None;
+    }
+
+    /// Time of the next mandatory full refresh
+    fn full_refresh_due(&self) -> Option<u64> {
+        let interval = self.config.full_refresh_interval?;
+        Some(
+            self.last_full_refresh
+                .map_or(0, |last| last.saturating_add(interval)),
+        )
+    }
+
+    /// Earliest time of the next refresh allowed by the limit per hour
+    fn rate_allowed(&self) -> u64 {
+        self.history[self.next].map_or(0, |oldest| oldest.saturating_add(HOUR))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NIGHT: QuietHours = QuietHours {
+        start: 22 * 3600,
+        end: 6 * 3600,
+    };
+
+    #[test]
+    fn limit_per_hour() {
+        let mut scheduler = RefreshScheduler::<2>::new(ScheduleConfig::default());
+        scheduler.record_update(0, false);
+        scheduler.record_update(100, false);
+        scheduler.request(200);
+        // the refresh at 0 is an hour old at 3600
+        assert_eq!(
+            scheduler.next_action(200),
+            ScheduledAction::Sleep { until: Some(HOUR) }
+        );
+        assert_eq!(
+            scheduler.next_action(HOUR),
+            ScheduledAction::Update { full: false }
+        );
+        scheduler.record_update(HOUR, false);
+        scheduler.request(HOUR);
+        assert_eq!(
+            scheduler.next_action(HOUR),
+            ScheduledAction::Sleep {
+                until: Some(100 + HOUR)
+            }
+        );
+    }
+
+    #[test]
+    fn quiet_hours_around_midnight() {
+        assert!(NIGHT.contains(22 * HOUR));
+        assert!(NIGHT.contains(DAY + 23 * HOUR + 59 * 60));
+        assert!(NIGHT.contains(5 * HOUR + 59 * 60));
+        assert!(!NIGHT.contains(6 * HOUR));
+        assert!(!NIGHT.contains(22 * HOUR - 1));
+
+        let mut scheduler = RefreshScheduler::<4>::new(ScheduleConfig {
+            quiet_hours: Some(NIGHT),
+            ..ScheduleConfig::default()
+        });
+        // a request at 23:59 is served at 6:00 of the next day
+        let now = DAY + 23 * HOUR + 59 * 60;
+        scheduler.request(now);
+        let morning = 2 * DAY + 6 * HOUR;
+        assert_eq!(
+            scheduler.next_action(now),
+            ScheduledAction::Sleep {
+                until: Some(morning)
+            }
+        );
+        assert_eq!(
+            scheduler.next_action(morning),
+            ScheduledAction::Update { full: false }
+        );
+    }
+
+    #[test]
+    fn requests_are_coalesced() {
+        let mut scheduler = RefreshScheduler::<4>::new(ScheduleConfig::default());
+        scheduler.request(1000);
+        scheduler.request(500);
+        assert_eq!(scheduler.pending(), Some(500));
+        assert_eq!(
+            scheduler.next_action(400),
+            ScheduledAction::Sleep { until: Some(500) }
+        );
+        assert_eq!(
+            scheduler.next_action(500),
+            ScheduledAction::Update { full: false }
+        );
+        // the single update serves both requests
+        scheduler.record_update(500, false);
+        assert_eq!(scheduler.pending(), None);
+        assert_eq!(
+            scheduler.next_action(1000),
+            ScheduledAction::Sleep { until: None }
+        );
+    }
+
+    #[test]
+    fn periodic_full_refresh() {
+        let mut scheduler = RefreshScheduler::<4>::new(ScheduleConfig {
+            full_refresh_interval: Some(DAY),
+            ..ScheduleConfig::default()
+        });
+        assert_eq!(
+            scheduler.next_action(0),
+            ScheduledAction::Update { full: true }
+        );
+        scheduler.record_update(0, true);
+        scheduler.request(HOUR);
+        assert_eq!(
+            scheduler.next_action(HOUR),
+            ScheduledAction::Update { full: false }
+        );
+        scheduler.record_update(HOUR, false);
+        assert_eq!(
+            scheduler.next_action(2 * HOUR),
+            ScheduledAction::Sleep { until: Some(DAY) }
+        );
+        assert_eq!(
+            scheduler.next_action(DAY),
+            ScheduledAction::Update { full: true }
+        );
+    }
+}
@@ -0,0 +1,80 @@
+//! Scrollable regions of a display buffer, e.g. for log-style displays
+
+use embedded_graphics::{
+    geometry::{Point, Size},
+    primitives::Rectangle,
+};
+
+use crate::{BufferStorage, Display, TriColor};
+
+/// Rectangular region of a [`Display`] whose content can be scrolled.
+/// The region is given in rotated coordinates and clipped to the display.
+pub struct ScrollRegion<
+    'a,
+    const SIZE_V: u32,
+    const SIZE_H: u32,
+    const IMAGE_SIZE: usize,
+    B = [u8; IMAGE_SIZE],
+> {
+    display: &'a mut Display<SIZE_V, SIZE_H, IMAGE_SIZE, B>,
+    area: Rectangle,
+    fill: TriColor,
+}
+
+impl<'a, const SIZE_V: u32, const SIZE_H: u32, const IMAGE_SIZE: usize, B: BufferStorage>
+    ScrollRegion<'a, SIZE_V, SIZE_H, IMAGE_SIZE, B>
+{
+    /// Create a scroll region covering `area` of `display`
+    pub fn new(display: &'a mut Display<SIZE_V, SIZE_H, IMAGE_SIZE, B>, area: Rectangle) -> Self {
+        Self {
+            display,
+            area,
+            fill: TriColor::White,
+        }
+    }
+
+    /// Set the color used for the rows freed by scrolling (default: white)
+    #[must_use]
+    pub fn with_fill(mut self, fill: TriColor) -> Self {
+        self.fill = fill;
+        self
+    }
+
+    #[must_use]
+    pub fn area(&self) -> Rectangle {
+        self.area
+    }
+
+    /// Shift the content of the region up by `rows` pixels and
+    /// clear the freed rows at the bottom.
+    #[allow(clippy::cast_possible_wrap)]
+    pub fn scroll_up(&mut self, rows: u32) {
+        let Size { width, height } = self.area.size;
+        let rows = rows.min(height);
+        let top_left = self.area.top_left;
+        for y in 0..height as i32 {
+            for x in 0..width as i32 {
+                let target = top_left + Point::new(x, y);
+                let color = if y + (rows as i32) < height as i32 {
+                    let source = target + Point::new(0, rows as i32);
+                    self.display.get_pixel(source).unwrap_or(self.fill)
+                } else {
+                    self.fill
+                };
+                self.display.set_pixel(target, color);
+            }
+        }
+    }
+}
+
+impl<const SIZE_V: u32, const SIZE_H: u32, const IMAGE_SIZE: usize, B: BufferStorage>
+    Display<SIZE_V, SIZE_H, IMAGE_SIZE, B>
+{
+    /// Define a scrollable region of this display, see [`ScrollRegion`]
+    pub fn scroll_region(
+        &mut self,
+        area: Rectangle,
+    ) -> ScrollRegion<'_, SIZE_V, SIZE_H, IMAGE_SIZE, B> {
+        ScrollRegion::new(self, area)
+    }
+}
@@ -0,0 +1,486 @@
+//! Frames streamed from files on an SD card, e.g. for photo frames which
+//! cannot hold a display buffer in RAM
+
+use embedded_sdmmc::{BlockDevice, TimeSource};
+
+use crate::{FrameSource, Plane};
+
+/// Size of the band of the file read at once, one block of the SD card
+const BAND_LEN: usize = 512;
+
+/// File which can be read from an arbitrary position. Implemented for the
+/// files of `embedded-sdmmc`, other file systems can implement it as well.
+pub trait ImageFile {
+    type Error;
+
+    /// Read the next bytes into `buf`, returns the number of bytes read
+    /// (0 at the end of the file)
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the file cannot be read.
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize, Self::Error>;
+
+    /// Continue reading at `offset` from the start of the file
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the position is invalid.
+    fn seek(&mut self, offset: u32) -> Result<(), Self::Error>;
+}
+
+impl<D, T, const MAX_DIRS: usize, const MAX_FILES: usize, const MAX_VOLUMES: usize> ImageFile
+    for embedded_sdmmc::File<'_, D, T, MAX_DIRS, MAX_FILES, MAX_VOLUMES>
+where
+    D: BlockDevice,
+    T: TimeSource,
+{
+    type Error = embedded_sdmmc::Error<D::Error>;
+
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize, Self::Error> {
+        embedded_sdmmc::File::read(self, buf)
+    }
+
+    fn seek(&mut self, offset: u32) -> Result<(), Self::Error> {
+        self.seek_from_start(offset)
+    }
+}
+
+/// Error while opening an image with [`SdFrame`]
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ImageError<E> {
+    /// the file cannot be read
+    File(E),
+    /// the file is no BMP image
+    UnknownFormat,
+    /// the BMP image is compressed or has not 1, 8 or 24 bits per pixel
+    UnsupportedFormat,
+    /// the image has another size than the panel
+    WrongSize { width: u32, height: u32 },
+}
+
+impl<E> From<E> for ImageError<E> {
+    fn from(error: E) -> Self {
+        ImageError::File(error)
+    }
+}
+
+/// Layout of the pixel data in the file
+#[derive(Clone, Copy)]
+enum Format {
+    /// black plane followed by the red plane, as in `DisplayBuffer`
+    Raw,
+    Bmp {
+        data_offset: u32,
+        /// bytes per row including padding
+        stride: u32,
+        bottom_up: bool,
+        bits_per_pixel: u16,
+        /// palette entries drawn black and red, one bit per entry
+        black: [u8; 32],
+        red: [u8; 32],
+    },
+}
+
+/// Frame streamed from a file, see `Epd::update_from_source`. The file is
+/// read in bands of 512 bytes while the planes are sent, so only a few
+/// hundred bytes of RAM are needed regardless of the size of the panel.
+/// The image has to be in panel coordinates (without rotation) with the
+/// size of the panel. Errors while reading the frame end it with white
+/// pixels and are kept for [`SdFrame::take_error`].
+pub struct SdFrame<F: ImageFile> {
+    file: F,
+    format: Format,
+    width: u32,
+    height: u32,
+    /// plane and position of the next byte sent
+    plane: Plane,
+    position: usize,
+    band: [u8; BAND_LEN],
+    band_start: u32,
+    band_len: usize,
+    error: Option<F::Error>,
+}
+
+impl<F: ImageFile> SdFrame<F> {
+    fn new(file: F, format: Format, width: u32, height: u32) -> Self {
+        Self {
+            file,
+            format,
+            width,
+            height,
+            plane: Plane::Black,
+            position: 0,
+            band: [0; BAND_LEN],
+            band_start: 0,
+            band_len: 0,
+            error: None,
+        }
+    }
+
+    /// Frame stored as the black plane followed by the red plane (as
+    /// returned by `DisplayBuffer`) for a panel of `width` × `height`
+    /// pixels. A missing red plane is sent as all zeros.
+    pub fn raw(file: F, width: u32, height: u32) -> Self {
+        Self::new(file, Format::Raw, width, height)
+    }
+
+    /// Frame stored as uncompressed BMP image with 1, 8 or 24 bits per
+    /// pixel. The colors are converted with the same thresholds as
+    /// `TriColor::from(Rgb888)`.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the file cannot be read, is
+    /// no supported BMP image or does not have the size of the panel.
+    pub fn bmp(file: F, width: u32, height: u32) -> Result<Self, ImageError<F::Error>> {
+        let mut frame = Self::new(file, Format::Raw, width, height);
+        let mut header = [0; 54];
+        frame.read_bytes(0, &mut header)?;
+        if header[..2] != *b"BM" {
+            return Err(ImageError::UnknownFormat);
+        }
+        let u16_at = |pos: usize| u16::from_le_bytes([header[pos], header[pos + 1]]);
+        let u32_at = |pos: usize| {
+            u32::from_le_bytes([
+                header[pos],
+                header[pos + 1],
+                header[pos + 2],
+                header[pos + 3],
+            ])
+        };
+        let (image_width, image_height) = (u32_at(18), u32_at(22));
+        // a negative height marks an image stored top-down
+        let bottom_up = i32::from_le_bytes(image_height.to_le_bytes()) > 0;
+        let image_height = i32::from_le_bytes(image_height.to_le_bytes()).unsigned_abs();
+        if image_width != width || image_height != height {
+            return Err(ImageError::WrongSize {
+                width: image_width,
+                height: image_height,
+            });
+        }
+        let bits_per_pixel = u16_at(28);
+        if u32_at(30) != 0 || !matches!(bits_per_pixel, 1 | 8 | 24) {
+            return Err(ImageError::UnsupportedFormat);
+        }
+
+        let (mut black, mut red) = ([0; 32], [0; 32]);
+        if bits_per_pixel != 24 {
+            let colors = match u32_at(46) {
+                0 => 1 << bits_per_pixel,
+                colors => colors.min(1 << bits_per_pixel),
+            };
+            let palette = 14 + u32_at(14);
+            for index in 0..colors {
+                let mut entry = [0; 4];
+                frame.read_bytes(palette + index * 4, &mut entry)?;
+                let (is_black, is_red) = classify(entry[2], entry[1], entry[0]);
+                let (byte, bit) = (index as usize / 8, 1 << (index % 8));
+                if is_black {
+                    black[byte] |= bit;
+                }
+                if is_red {
+                    red[byte] |= bit;
+                }
+            }
+        }
+        frame.format = Format::Bmp {
+            data_offset: u32_at(10),
+            stride: (u32::from(bits_per_pixel) * width).div_ceil(32) * 4,
+            bottom_up,
+            bits_per_pixel,
+            black,
+            red,
+        };
+        Ok(frame)
+    }
+
+    /// Error that occurred while the frame was sent, if any
+    pub fn take_error(&mut self) -> Option<F::Error> {
+        self.error.take()
+    }
+
+    fn bytes_per_row(&self) -> usize {
+        self.width as usize / 8
+    }
+
+    fn plane_len(&self) -> usize {
+        self.bytes_per_row() * self.height as usize
+    }
+
+    /// Copy the bytes at `offset` of the file into `out`, reading a new
+    /// band if they are not in the current one. Bytes beyond the end of
+    /// the file are zeros.
+    #[allow(clippy::cast_possible_truncation)]
+    fn read_bytes(&mut self, offset: u32, out: &mut [u8]) -> Result<(), F::Error> {
+        // compared in u32, a usize may only have 16 bits
+        let start = offset.wrapping_sub(self.band_start);
+        if offset < self.band_start || start.saturating_add(out.len() as u32) > self.band_len as u32
+        {
+            self.file.seek(offset)?;
+            self.band_start = offset;
+            self.band_len = 0;
+            while self.band_len < BAND_LEN {
+                let len = self.file.read(&mut self.band[self.band_len..])?;
+                if len == 0 {
+                    break;
+                }
+                self.band_len += len;
+            }
+            if self.band_len < out.len() {
+                self.band[self.band_len..out.len()].fill(0);
+                self.band_len = out.len();
+            }
+            return self.read_bytes(offset, out);
+        }
+        let start = start as usize;
+        out.copy_from_slice(&self.band[start..start + out.len()]);
+        Ok(())
+    }
+
+    /// Byte at `position` of `plane`
+    #[allow(clippy::cast_possible_truncation)]
+    fn plane_byte(&mut self, plane: Plane, position: usize) -> Result<u8, F::Error> {
+        let Format::Bmp {
+            data_offset,
+            stride,
+            bottom_up,
+            bits_per_pixel,
+            black,
+            red,
+        } = self.format
+        else {
+            let offset = match plane {
+                Plane::Black => position,
+                Plane::Red => self.plane_len() + position,
+            };
+            let mut byte = [0];
+            self.read_bytes(offset as u32, &mut byte)?;
+            return Ok(byte[0]);
+        };
+        let (row, column) = (
+            position / self.bytes_per_row(),
+            position % self.bytes_per_row(),
+        );
+        let row = if bottom_up {
+            self.height as usize - 1 - row
+        } else {
+            row
+        };
+        let row_offset = data_offset + row as u32 * stride;
+        let mut pixels = [0; 24];
+        let mut byte = 0;
+        match bits_per_pixel {
+            24 => {
+                self.read_bytes(row_offset + column as u32 * 24, &mut pixels)?;
+                for (bit, bgr) in pixels.chunks_exact(3).enumerate() {
+                    let (is_black, is_red) = classify(bgr[2], bgr[1], bgr[0]);
+                    if select(plane, is_black, is_red) {
+                        byte |= 0x80 >> bit;
+                    }
+                }
+            }
+            bits => {
+                let indices = &mut pixels[..usize::from(bits)];
+                self.read_bytes(row_offset + column as u32 * u32::from(bits), indices)?;
+                for bit in 0..8 {
+                    let index = if bits == 1 {
+                        usize::from(indices[0] >> (7 - bit) & 1)
+                    } else {
+                        usize::from(indices[bit])
+                    };
+                    let in_set = |set: &[u8; 32]| set[index / 8] & (1 << (index % 8)) != 0;
+                    if select(plane, in_set(&black), in_set(&red)) {
+                        byte |= 0x80 >> bit;
+                    }
+                }
+            }
+        }
+        Ok(byte)
+    }
+}
+
+impl<F: ImageFile> FrameSource for SdFrame<F> {
+    fn next_chunk(&mut self, plane: Plane, buf: &mut [u8]) -> usize {
+        if plane != self.plane {
+            self.plane = plane;
+            self.position = 0;
+        }
+        let len = buf.len().min(self.plane_len() - self.position);
+        for byte in &mut buf[..len] {
+            *byte = if self.error.is_some() {
+                0
+            } else {
+                self.plane_byte(plane, self.position)
+                    .unwrap_or_else(|error| {
+                        self.error = Some(error);
+                        0
+                    })
+            };
+            self.position += 1;
+        }
+        len
+    }
+}
+
+/// Whether a pixel with the given colors is set in `plane`
+fn select(plane: Plane, is_black: bool, is_red: bool) -> bool {
+    match plane {
+        Plane::Black => is_black,
+        Plane::Red => is_red,
+    }
+}
+
+/// Black and red pixels with the thresholds of `TriColor::from(Rgb888)`
+fn classify(r: u8, g: u8, b: u8) -> (bool, bool) {
+    let min = r.min(g).min(b);
+    let max = r.max(g).max(b);
+    let is_red = max - min > u8::MAX / 3 && r > g && r > b;
+    let is_black = !is_red && max <= u8::MAX / 2;
+    (is_black, is_red)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BLACK: [u8; 3] = [0x00, 0x00, 0x00];
+    const RED: [u8; 3] = [0x00, 0x00, 0xff];
+
+    /// File in memory
+    struct MemFile<'a> {
+        data: &'a [u8],
+        position: usize,
+    }
+
+    impl ImageFile for MemFile<'_> {
+        type Error = core::convert::Infallible;
+
+        fn read(&mut self, buf: &mut [u8]) -> Result<usize, Self::Error> {
+            let rest = self.data.get(self.position..).unwrap_or(&[]);
+            let len = buf.len().min(rest.len());
+            buf[..len].copy_from_slice(&rest[..len]);
+            self.position += len;
+            Ok(len)
+        }
+
+        fn seek(&mut self, offset: u32) -> Result<(), Self::Error> {
+            self.position = offset as usize;
+            Ok(())
+        }
+    }
+
+    /// BMP image with `palette` (BGRA) and `pixels` (rows in file order,
+    /// with padding), returns the buffer and the length of the file
+    #[allow(clippy::cast_possible_truncation)]
+    fn bmp_file(
+        width: u32,
+        height: i32,
+        bits_per_pixel: u16,
+        palette: &[[u8; 4]],
+        pixels: &[u8],
+    ) -> ([u8; 128], usize) {
+        let mut file = [0; 128];
+        let data_offset = 54 + 4 * palette.len();
+        let len = data_offset + pixels.len();
+        file[..2].copy_from_slice(b"BM");
+        file[2..6].copy_from_slice(&(len as u32).to_le_bytes());
+        file[10..14].copy_from_slice(&(data_offset as u32).to_le_bytes());
+        file[14..18].copy_from_slice(&40u32.to_le_bytes());
+        file[18..22].copy_from_slice(&width.to_le_bytes());
+        file[22..26].copy_from_slice(&height.to_le_bytes());
+        file[26..28].copy_from_slice(&1u16.to_le_bytes());
+        file[28..30].copy_from_slice(&bits_per_pixel.to_le_bytes());
+        file[46..50].copy_from_slice(&(palette.len() as u32).to_le_bytes());
+        for (i, entry) in palette.iter().enumerate() {
+            file[54 + 4 * i..58 + 4 * i].copy_from_slice(entry);
+        }
+        file[data_offset..len].copy_from_slice(pixels);
+        (file, len)
+    }
+
+    /// Both planes of `frame`, checking that no error occurred
+    fn planes<const N: usize>(frame: &mut SdFrame<MemFile>) -> ([u8; N], [u8; N]) {
+        let (mut black, mut red) = ([0; N], [0; N]);
+        assert_eq!(frame.next_chunk(Plane::Black, &mut black), N);
+        assert_eq!(frame.next_chunk(Plane::Red, &mut red), N);
+        assert!(frame.take_error().is_none());
+        (black, red)
+    }
+
+    /// 8×2 pixels with 24 bits per pixel: black and red at the start of
+    /// the top row, black at the end of the bottom row
+    fn rgb_rows() -> ([u8; 24], [u8; 24]) {
+        let (mut top, mut bottom) = ([0xff; 24], [0xff; 24]);
+        top[..3].copy_from_slice(&BLACK);
+        top[3..6].copy_from_slice(&RED);
+        bottom[21..].copy_from_slice(&BLACK);
+        (top, bottom)
+    }
+
+    #[test]
+    fn bmp_24_bits_bottom_up_and_top_down() {
+        let (top, bottom) = rgb_rows();
+        let mut pixels = [0; 48];
+        for bottom_up in [true, false] {
+            let height = if bottom_up {
+                pixels[..24].copy_from_slice(&bottom);
+                pixels[24..].copy_from_slice(&top);
+                2
+            } else {
+                pixels[..24].copy_from_slice(&top);
+                pixels[24..].copy_from_slice(&bottom);
+                -2
+            };
+            let (file, len) = bmp_file(8, height, 24, &[], &pixels);
+            let file = MemFile {
+                data: &file[..len],
+                position: 0,
+            };
+            let mut frame = SdFrame::bmp(file, 8, 2).unwrap();
+            assert_eq!(planes::<2>(&mut frame), ([0x80, 0x01], [0x40, 0x00]));
+        }
+    }
+
+    #[test]
+    fn bmp_8_bits_with_palette() {
+        let palette = [[0xff, 0xff, 0xff, 0], [0, 0, 0, 0], [0, 0, 0xff, 0]];
+        let (file, len) = bmp_file(8, 1, 8, &palette, &[1, 2, 0, 0, 0, 0, 0, 1]);
+        let file = MemFile {
+            data: &file[..len],
+            position: 0,
+        };
+        let mut frame = SdFrame::bmp(file, 8, 1).unwrap();
+        assert_eq!(planes::<1>(&mut frame), ([0x81], [0x40]));
+    }
+
+    #[test]
+    fn bmp_1_bit_rows_are_padded() {
+        // rows of 2 bytes padded to 4 with black pixels, bottom row first
+        let pixels = [0xff, 0xfe, 0, 0, 0x7f, 0xff, 0, 0];
+        let palette = [[0, 0, 0, 0], [0xff, 0xff, 0xff, 0]];
+        let (file, len) = bmp_file(16, 2, 1, &palette, &pixels);
+        let file = MemFile {
+            data: &file[..len],
+            position: 0,
+        };
+        let mut frame = SdFrame::bmp(file, 16, 2).unwrap();
+        assert_eq!(planes::<4>(&mut frame), ([0x80, 0, 0, 0x01], [0; 4]));
+    }
+
+    #[test]
+    fn bmp_of_another_size_is_rejected() {
+        let (file, len) = bmp_file(8, -2, 24, &[], &[0xff; 48]);
+        let file = MemFile {
+            data: &file[..len],
+            position: 0,
+        };
+        assert!(matches!(
+            SdFrame::bmp(file, 16, 2),
+            Err(ImageError::WrongSize {
+                width: 8,
+                height: 2
+            })
+        ));
+    }
+}
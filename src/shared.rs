@@ -0,0 +1,78 @@
+//! Driver shared between threads
+
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+
+use embedded_hal::{delay::DelayNs, digital::InputPin, digital::OutputPin, spi::SpiDevice};
+
+use crate::{driver::EpdError, Active, DisplayBuffer, Epd};
+
+/// Driver and SPI device guarded by the mutex
+type Guarded<SPI, BUSY, DC, RST> = (Epd<Active, BUSY, DC, RST>, SPI);
+
+/// Active driver together with its SPI device behind an `Arc<Mutex<..>>`,
+/// e.g. to update the panel from a worker of an async runtime while the
+/// frames are drawn on another thread. Clones share the same driver.
+/// It is `Send` and `Sync` if the SPI device and the pins are `Send`.
+pub struct SharedEpd<SPI, BUSY, DC, RST> {
+    inner: Arc<Mutex<Guarded<SPI, BUSY, DC, RST>>>,
+}
+
+impl<SPI, BUSY, DC, RST> Clone for SharedEpd<SPI, BUSY, DC, RST> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: Arc::clone(&self.inner),
+        }
+    }
+}
+
+impl<SPI, BUSY, DC, RST> SharedEpd<SPI, BUSY, DC, RST>
+where
+    SPI: SpiDevice,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+{
+    /// Share an initialized driver and its SPI device
+    pub fn new(epd: Epd<Active, BUSY, DC, RST>, spi: SPI) -> Self {
+        Self {
+            inner: Arc::new(Mutex::new((epd, spi))),
+        }
+    }
+
+    /// Show `display` on the e-paper, blocking other users of the driver
+    /// until the update is complete
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if there is an error
+    /// with the GPIOs or the SPI device.
+    pub fn update(
+        &self,
+        display: &impl DisplayBuffer,
+        delay: &mut impl DelayNs,
+    ) -> Result<(), EpdError<SPI, DC, RST>> {
+        self.with(|epd, spi| epd.update(display, spi, delay))
+    }
+
+    /// Run `f` with exclusive access to the driver and the SPI device,
+    /// e.g. for `update_changed` or `power_off` of the borrowed driver.
+    /// A panic of a previous user does not poison the driver, the COG
+    /// may be in an unexpected state then (consider `reset`).
+    pub fn with<R>(&self, f: impl FnOnce(&mut Epd<Active, BUSY, DC, RST>, &mut SPI) -> R) -> R {
+        let mut guard = self.lock();
+        let (epd, spi) = &mut *guard;
+        f(epd, spi)
+    }
+
+    /// Take back the driver and the SPI device, returns `None` while
+    /// other clones exist
+    #[must_use]
+    pub fn into_inner(self) -> Option<Guarded<SPI, BUSY, DC, RST>> {
+        let mutex = Arc::try_unwrap(self.inner).ok()?;
+        Some(mutex.into_inner().unwrap_or_else(PoisonError::into_inner))
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Guarded<SPI, BUSY, DC, RST>> {
+        self.inner.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
@@ -0,0 +1,152 @@
+//! Animated GIFs shown as a slideshow, one full refresh per frame
+
+use std::{
+    io::Read,
+    thread,
+    time::{Duration, Instant},
+    vec,
+    vec::Vec,
+};
+
+use embedded_graphics::{draw_target::DrawTarget, geometry::Point, pixelcolor::Rgb888, Pixel};
+use embedded_hal::{delay::DelayNs, digital::InputPin, digital::OutputPin, spi::SpiDevice};
+
+use crate::{
+    driver::EpdError, Active, ColorMapper, DisplayBuffer, Epd, Error, MappedTarget, TriColor,
+};
+
+/// Decodes an animated GIF frame by frame. The frames are composed on a
+/// canvas of the size of the GIF, transparent areas are drawn white.
+/// Frames which restore the previous frame on disposal are kept instead.
+pub struct GifSlideshow<R: Read> {
+    decoder: gif::Decoder<R>,
+    /// composed frame as RGBA values
+    canvas: Vec<u8>,
+    /// area of the previous frame to clear before the next one (left, top, width, height)
+    clear: Option<(usize, usize, usize, usize)>,
+    /// minimum time between the start of two refreshes
+    min_interval: Duration,
+}
+
+impl<R: Read> GifSlideshow<R> {
+    /// Create a slideshow of the GIF read from `reader`. Frames are shown
+    /// for their delay, but at least for `min_interval` (e.g. the minimum
+    /// refresh interval recommended for the panel).
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the GIF header cannot be decoded.
+    pub fn new(reader: R, min_interval: Duration) -> Result<Self, gif::DecodingError> {
+        let mut options = gif::DecodeOptions::new();
+        options.set_color_output(gif::ColorOutput::RGBA);
+        let decoder = options.read_info(reader)?;
+        let len = usize::from(decoder.width()) * usize::from(decoder.height()) * 4;
+        Ok(Self {
+            decoder,
+            canvas: vec![0; len],
+            clear: None,
+            min_interval,
+        })
+    }
+
+    /// Decode the next frame and draw the composed canvas into `target`.
+    /// Returns the delay of the frame, `None` after the last frame.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the frame cannot be decoded.
+    #[allow(clippy::cast_possible_truncation, clippy::cast_possible_wrap)]
+    pub fn next_frame<D>(&mut self, target: &mut D) -> Result<Option<Duration>, gif::DecodingError>
+    where
+        D: DrawTarget<Color = Rgb888>,
+    {
+        let width = usize::from(self.decoder.width());
+        let height = usize::from(self.decoder.height());
+        if let Some((left, top, w, h)) = self.clear.take() {
+            let (x_end, y_end) = ((left + w).min(width), (top + h).min(height));
+            for y in top..y_end {
+                self.canvas[(y * width + left) * 4..(y * width + x_end) * 4].fill(0);
+            }
+        }
+        let Some(frame) = self.decoder.read_next_frame()? else {
+            return Ok(None);
+        };
+        let (left, top) = (usize::from(frame.left), usize::from(frame.top));
+        let (w, h) = (usize::from(frame.width), usize::from(frame.height));
+        for (row, line) in frame.buffer.chunks_exact(w * 4).take(h).enumerate() {
+            let y = top + row;
+            if y >= height {
+                break;
+            }
+            for (column, pixel) in line.chunks_exact(4).enumerate() {
+                let x = left + column;
+                if x >= width {
+                    break;
+                }
+                if pixel[3] != 0 {
+                    self.canvas[(y * width + x) * 4..][..4].copy_from_slice(pixel);
+                }
+            }
+        }
+        if frame.dispose == gif::DisposalMethod::Background {
+            self.clear = Some((left, top, w, h));
+        }
+        let frame_delay = Duration::from_millis(u64::from(frame.delay) * 10);
+
+        let pixels = self.canvas.chunks_exact(4).enumerate().map(|(i, pixel)| {
+            let point = Point::new((i % width) as i32, (i / width) as i32);
+            let color = if pixel[3] == 0 {
+                Rgb888::new(u8::MAX, u8::MAX, u8::MAX)
+            } else {
+                Rgb888::new(pixel[0], pixel[1], pixel[2])
+            };
+            Pixel(point, color)
+        });
+        // drawing into a display buffer cannot fail
+        let _ = target.draw_iter(pixels);
+        Ok(Some(frame_delay))
+    }
+
+    /// Show all remaining frames on the e-paper, converting the colors with
+    /// `mapper`. Each frame is drawn into `display` and shown with a full
+    /// refresh. This function is blocking until the last frame is shown.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if a frame cannot be decoded
+    /// (as `Error::Io`) or if there is an error with the GPIOs or the SPI device.
+    pub fn play<D, M, SPI, BUSY, DC, RST>(
+        &mut self,
+        display: &mut D,
+        mapper: &M,
+        epd: &mut Epd<Active, BUSY, DC, RST>,
+        spi: &mut SPI,
+        delay: &mut impl DelayNs,
+    ) -> Result<(), EpdError<SPI, DC, RST>>
+    where
+        D: DrawTarget<Color = TriColor> + DisplayBuffer,
+        M: ColorMapper,
+        SPI: SpiDevice,
+        BUSY: InputPin,
+        DC: OutputPin,
+        RST: OutputPin,
+    {
+        let mut previous: Option<(Instant, Duration)> = None;
+        loop {
+            let frame_delay = self
+                .next_frame(&mut MappedTarget::new(display, mapper))
+                .map_err(|error| Error::Io(std::io::Error::other(error)))?;
+            let Some(frame_delay) = frame_delay else {
+                return Ok(());
+            };
+            if let Some((shown, wait)) = previous {
+                if let Some(remaining) = wait.checked_sub(shown.elapsed()) {
+                    thread::sleep(remaining);
+                }
+            }
+            let shown = Instant::now();
+            epd.update(display, spi, delay)?;
+            previous = Some((shown, frame_delay.max(self.min_interval)));
+        }
+    }
+}
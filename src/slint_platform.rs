@@ -0,0 +1,154 @@
+//! Platform backend for the [Slint](https://slint.dev) software renderer.
+//!
+//! ```ignore
+//! let platform = EpdPlatform::new(display.size(), time_since_start);
+//! let window = platform.window();
+//! slint::platform::set_platform(Box::new(platform)).unwrap();
+//! let ui = MainWindow::new().unwrap();
+//! ui.show().unwrap();
+//! loop {
+//!     slint::platform::update_timers_and_animations();
+//!     update(&window, &mut display, &Threshold, &mut epd, &mut spi, &mut delay)?;
+//! }
+//! ```
+
+use alloc::{rc::Rc, vec::Vec};
+use core::time::Duration;
+
+use embedded_graphics::{
+    draw_target::DrawTarget,
+    geometry::{Point, Size},
+    pixelcolor::Rgb888,
+    Pixel,
+};
+use embedded_hal::{delay::DelayNs, digital::InputPin, digital::OutputPin, spi::SpiDevice};
+use slint::{
+    platform::{
+        software_renderer::{LineBufferProvider, MinimalSoftwareWindow, RepaintBufferType},
+        Platform, WindowAdapter,
+    },
+    PhysicalSize, PlatformError, Rgb8Pixel,
+};
+
+use crate::{driver::EpdError, Active, ColorMapper, DisplayBuffer, Epd, TriColor};
+
+/// Slint platform with a single window rendered by the software renderer
+pub struct EpdPlatform {
+    window: Rc<MinimalSoftwareWindow>,
+    time_since_start: fn() -> Duration,
+}
+
+impl EpdPlatform {
+    /// Create the platform with a window of the given size (typically the
+    /// size of the display). `time_since_start` is a monotonic time source
+    /// used by Slint for timers and animations.
+    #[must_use]
+    pub fn new(size: Size, time_since_start: fn() -> Duration) -> Self {
+        // the display keeps its content, so only changed regions need to be rendered
+        let window = MinimalSoftwareWindow::new(RepaintBufferType::ReusedBuffer);
+        window.set_size(PhysicalSize::new(size.width, size.height));
+        Self {
+            window,
+            time_since_start,
+        }
+    }
+
+    /// Window which needs to be passed to [`render`] or [`update`]
+    #[must_use]
+    pub fn window(&self) -> Rc<MinimalSoftwareWindow> {
+        self.window.clone()
+    }
+}
+
+impl Platform for EpdPlatform {
+    fn create_window_adapter(&self) -> Result<Rc<dyn WindowAdapter>, PlatformError> {
+        Ok(self.window.clone())
+    }
+
+    fn duration_since_start(&self) -> Duration {
+        (self.time_since_start)()
+    }
+}
+
+struct LineRenderer<'a, D, M> {
+    target: &'a mut D,
+    mapper: &'a M,
+    line: Vec<Rgb8Pixel>,
+}
+
+impl<D, M> LineBufferProvider for LineRenderer<'_, D, M>
+where
+    D: DrawTarget<Color = TriColor>,
+    M: ColorMapper,
+{
+    type TargetPixel = Rgb8Pixel;
+
+    #[allow(clippy::cast_possible_truncation, clippy::cast_possible_wrap)]
+    fn process_line(
+        &mut self,
+        line: usize,
+        range: core::ops::Range<usize>,
+        render_fn: impl FnOnce(&mut [Self::TargetPixel]),
+    ) {
+        self.line.clear();
+        self.line
+            .resize(range.len(), Rgb8Pixel::new(u8::MAX, u8::MAX, u8::MAX));
+        render_fn(&mut self.line);
+        let y = line as i32;
+        let mapper = self.mapper;
+        let pixels = self.line.iter().zip(range).map(|(pixel, x)| {
+            let point = Point::new(x as i32, y);
+            let color = Rgb888::new(pixel.r, pixel.g, pixel.b);
+            Pixel(point, mapper.map(color, point))
+        });
+        // drawing into a display buffer cannot fail
+        let _ = self.target.draw_iter(pixels);
+    }
+}
+
+/// Render the window into `target` if Slint requests a redraw, converting the
+/// colors with `mapper`. Returns `true` if something was rendered.
+pub fn render<D, M>(window: &MinimalSoftwareWindow, target: &mut D, mapper: &M) -> bool
+where
+    D: DrawTarget<Color = TriColor>,
+    M: ColorMapper,
+{
+    window.draw_if_needed(|renderer| {
+        renderer.render_by_line(LineRenderer {
+            target,
+            mapper,
+            line: Vec::new(),
+        });
+    })
+}
+
+/// Render the window into `display` and show it on the e-paper if Slint
+/// requests a redraw. Returns `true` if the e-paper was updated.
+///
+/// # Errors
+///
+/// This function will return an error if there is an error
+/// with the GPIOs or the SPI device.
+pub fn update<D, M, SPI, BUSY, DC, RST, DELAY>(
+    window: &MinimalSoftwareWindow,
+    display: &mut D,
+    mapper: &M,
+    epd: &mut Epd<Active, BUSY, DC, RST>,
+    spi: &mut SPI,
+    delay: &mut DELAY,
+) -> Result<bool, EpdError<SPI, DC, RST>>
+where
+    D: DrawTarget<Color = TriColor> + DisplayBuffer,
+    M: ColorMapper,
+    SPI: SpiDevice,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayNs,
+{
+    if !render(window, display, mapper) {
+        return Ok(false);
+    }
+    epd.update(display, spi, delay)?;
+    Ok(true)
+}
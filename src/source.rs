@@ -0,0 +1,47 @@
+//! Streaming frame data from arbitrary sources
+
+use crate::Plane;
+
+/// Source of the plane data of a frame, e.g. external flash, a
+/// decompressor or a network connection, see `Epd::update_from_source`.
+/// The black plane is requested completely before the red plane.
+pub trait FrameSource {
+    /// Fill `buf` with the next bytes of `plane` and return the number
+    /// of bytes written. Returning 0 ends the plane.
+    fn next_chunk(&mut self, plane: Plane, buf: &mut [u8]) -> usize;
+}
+
+/// Frame source yielding the bytes of the planes from two iterators
+pub struct IterSource<B, R> {
+    black: B,
+    red: R,
+}
+
+impl<B, R> IterSource<B, R>
+where
+    B: Iterator<Item = u8>,
+    R: Iterator<Item = u8>,
+{
+    pub fn new(black: B, red: R) -> Self {
+        Self { black, red }
+    }
+}
+
+impl<B, R> FrameSource for IterSource<B, R>
+where
+    B: Iterator<Item = u8>,
+    R: Iterator<Item = u8>,
+{
+    fn next_chunk(&mut self, plane: Plane, buf: &mut [u8]) -> usize {
+        let data: &mut dyn Iterator<Item = u8> = match plane {
+            Plane::Black => &mut self.black,
+            Plane::Red => &mut self.red,
+        };
+        let mut len = 0;
+        for (byte, value) in buf.iter_mut().zip(data) {
+            *byte = value;
+            len += 1;
+        }
+        len
+    }
+}
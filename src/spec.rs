@@ -0,0 +1,552 @@
+//! Metadata of the supported panels
+
+#[cfg(feature = "graphics")]
+use crate::{BufferStorage, Display, DisplayRotation, DoubleDisplay, MonoDisplay};
+
+/// Register settings for a range of ambient temperatures, see
+/// [`DisplaySpec::TEMPERATURE_TABLE`]
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct TemperatureSetting {
+    /// Upper bound of the range in °C (inclusive), the range starts
+    /// above the bound of the previous entry
+    pub max_celsius: i8,
+    /// Input temperature sent for the range, `None` sends the measured
+    /// temperature
+    pub input_temperature: Option<u8>,
+    /// Active temperature sent for the range
+    pub active_temperature: u8,
+}
+
+/// Temperature settings of the Spectra panels. The panels are specified
+/// for 0 °C to 40 °C, temperatures outside of this range are clamped to
+/// it, so the COG uses the waveform of the nearest supported temperature.
+pub const SPECTRA_TEMPERATURE_TABLE: &[TemperatureSetting] = &[
+    TemperatureSetting {
+        max_celsius: -1,
+        input_temperature: Some(0),
+        active_temperature: 0x02,
+    },
+    TemperatureSetting {
+        max_celsius: 40,
+        input_temperature: None,
+        active_temperature: 0x02,
+    },
+    TemperatureSetting {
+        max_celsius: i8::MAX,
+        input_temperature: Some(40),
+        active_temperature: 0x02,
+    },
+];
+
+/// Temperature settings of the wide temperature (FT) variants of the
+/// Spectra panels, specified for -15 °C to 60 °C. Temperatures outside of
+/// this range are clamped to it. Negative input temperatures are sent as
+/// two's complement, see [`PanelVariant::WideTemperature`].
+pub const WIDE_TEMPERATURE_TABLE: &[TemperatureSetting] = &[
+    TemperatureSetting {
+        max_celsius: -16,
+        input_temperature: Some((-15i8).to_be_bytes()[0]),
+        active_temperature: 0x02,
+    },
+    TemperatureSetting {
+        max_celsius: 60,
+        input_temperature: None,
+        active_temperature: 0x02,
+    },
+    TemperatureSetting {
+        max_celsius: i8::MAX,
+        input_temperature: Some(60),
+        active_temperature: 0x02,
+    },
+];
+
+/// Temperature variant of a panel, see `Epd::set_variant`
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum PanelVariant {
+    /// Panel for 0 °C to 40 °C
+    #[default]
+    Standard,
+    /// Wide temperature (FT) panel for -15 °C to 60 °C. Negative
+    /// temperatures are sent to the COG and refreshes below 0 °C may take
+    /// several times longer.
+    WideTemperature,
+}
+
+impl PanelVariant {
+    /// Temperature settings of the variant
+    #[must_use]
+    pub fn temperature_table(self) -> &'static [TemperatureSetting] {
+        match self {
+            PanelVariant::Standard => SPECTRA_TEMPERATURE_TABLE,
+            PanelVariant::WideTemperature => WIDE_TEMPERATURE_TABLE,
+        }
+    }
+}
+
+/// Setting of the range `celsius` falls into, the last entry for
+/// temperatures above all ranges. `None` for an empty table.
+#[must_use]
+pub fn temperature_setting(
+    table: &[TemperatureSetting],
+    celsius: i8,
+) -> Option<&TemperatureSetting> {
+    table
+        .iter()
+        .find(|setting| celsius <= setting.max_celsius)
+        .or(table.last())
+}
+
+/// Kind of refresh, see [`DisplaySpec::estimate_refresh_duration`]
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum RefreshMode {
+    /// Refresh of the whole panel, e.g. with `Epd::update`
+    Full,
+    /// Refresh of a partial window, see `Epd::update_changed`
+    Partial,
+}
+
+/// Typical refresh durations for a range of ambient temperatures, see
+/// [`DisplaySpec::REFRESH_DURATIONS`]
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct RefreshDuration {
+    /// Upper bound of the range in °C (inclusive), the range starts
+    /// above the bound of the previous entry
+    pub max_celsius: i8,
+    /// Typical duration of a full refresh in ms
+    pub full_ms: u32,
+    /// Typical duration of a refresh of a partial window in ms. The
+    /// waveform is the same, only fewer rows are scanned.
+    pub partial_ms: u32,
+}
+
+impl RefreshDuration {
+    /// Typical duration of a refresh in `mode` in ms
+    #[must_use]
+    pub fn ms(&self, mode: RefreshMode) -> u32 {
+        match mode {
+            RefreshMode::Full => self.full_ms,
+            RefreshMode::Partial => self.partial_ms,
+        }
+    }
+}
+
+/// Refresh durations of the Spectra panels up to 2.87"
+pub const SMALL_REFRESH_DURATIONS: &[RefreshDuration] = &[
+    RefreshDuration {
+        max_celsius: 9,
+        full_ms: 30_000,
+        partial_ms: 27_000,
+    },
+    RefreshDuration {
+        max_celsius: 29,
+        full_ms: 19_000,
+        partial_ms: 17_000,
+    },
+    RefreshDuration {
+        max_celsius: i8::MAX,
+        full_ms: 15_000,
+        partial_ms: 13_500,
+    },
+];
+
+/// Refresh durations of the 3.70" Spectra panel
+pub const MEDIUM_REFRESH_DURATIONS: &[RefreshDuration] = &[
+    RefreshDuration {
+        max_celsius: 9,
+        full_ms: 36_000,
+        partial_ms: 32_000,
+    },
+    RefreshDuration {
+        max_celsius: 29,
+        full_ms: 22_000,
+        partial_ms: 20_000,
+    },
+    RefreshDuration {
+        max_celsius: i8::MAX,
+        full_ms: 18_000,
+        partial_ms: 16_000,
+    },
+];
+
+/// Refresh durations of the 4.17" and 4.37" Spectra panels
+pub const LARGE_REFRESH_DURATIONS: &[RefreshDuration] = &[
+    RefreshDuration {
+        max_celsius: 9,
+        full_ms: 40_000,
+        partial_ms: 36_000,
+    },
+    RefreshDuration {
+        max_celsius: 29,
+        full_ms: 25_000,
+        partial_ms: 22_500,
+    },
+    RefreshDuration {
+        max_celsius: i8::MAX,
+        full_ms: 20_000,
+        partial_ms: 18_000,
+    },
+];
+
+/// Typical duration in ms of a refresh in `mode` at `celsius`, from the
+/// range `celsius` falls into or the last entry for temperatures above
+/// all ranges. 0 for an empty table.
+#[must_use]
+pub fn refresh_duration(table: &[RefreshDuration], celsius: i8, mode: RefreshMode) -> u32 {
+    table
+        .iter()
+        .find(|duration| celsius <= duration.max_celsius)
+        .or(table.last())
+        .map_or(0, |duration| duration.ms(mode))
+}
+
+/// Size and identifier of a supported panel, see [`PANEL_MODELS`]
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct PanelModel {
+    /// same as `DisplaySpec::PANEL_ID`
+    pub id: u8,
+    /// same as `DisplaySpec::MODEL`
+    pub model: &'static str,
+    /// width and height in pixels (panel coordinates)
+    pub width: u32,
+    pub height: u32,
+    /// the module is physically landscape while the panel coordinates are
+    /// portrait, so it is normally used rotated by 90°
+    pub landscape: bool,
+}
+
+/// All supported panels, e.g. to look up a panel detected with
+/// `Epd::detect_panel`
+pub const PANEL_MODELS: &[PanelModel] = &[
+    PanelModel {
+        id: 1,
+        model: "1.54\"",
+        width: 152,
+        height: 152,
+        landscape: false,
+    },
+    PanelModel {
+        id: 2,
+        model: "2.13\"",
+        width: 104,
+        height: 212,
+        landscape: true,
+    },
+    PanelModel {
+        id: 3,
+        model: "2.66\"",
+        width: 152,
+        height: 296,
+        landscape: true,
+    },
+    PanelModel {
+        id: 4,
+        model: "2.71\"",
+        width: 176,
+        height: 264,
+        landscape: true,
+    },
+    PanelModel {
+        id: 5,
+        model: "2.87\"",
+        width: 128,
+        height: 296,
+        landscape: true,
+    },
+    PanelModel {
+        id: 6,
+        model: "3.70\"",
+        width: 240,
+        height: 416,
+        landscape: true,
+    },
+    PanelModel {
+        id: 7,
+        model: "4.17\"",
+        width: 400,
+        height: 300,
+        landscape: false,
+    },
+    PanelModel {
+        id: 8,
+        model: "4.37\"",
+        width: 176,
+        height: 480,
+        landscape: true,
+    },
+];
+
+impl PanelModel {
+    /// Supported panel with the given size, `None` for unknown sizes
+    #[must_use]
+    pub fn find(width: u32, height: u32) -> Option<&'static PanelModel> {
+        PANEL_MODELS
+            .iter()
+            .find(|model| model.width == width && model.height == height)
+    }
+
+    /// Whether the supported panel with the given size is normally used
+    /// rotated by 90° (see `landscape`), `false` for unknown sizes. As
+    /// `const fn` it determines the rotation of new displays.
+    #[must_use]
+    pub const fn is_landscape(width: u32, height: u32) -> bool {
+        let mut i = 0;
+        while i < PANEL_MODELS.len() {
+            let model = &PANEL_MODELS[i];
+            if model.width == width && model.height == height {
+                return model.landscape;
+            }
+            i += 1;
+        }
+        false
+    }
+}
+
+/// Metadata of a panel, implemented by all display types. This allows
+/// generic code to work with whatever panel was selected at compile time,
+/// e.g. `fn show<D: DisplaySpec>()` with `D = Display2in66`. The marker
+/// types of the panels (e.g. [`Panel2in66`]) implement it as well and are
+/// available without the `graphics` feature.
+pub trait DisplaySpec {
+    /// Width in pixels (panel coordinates, without rotation)
+    const WIDTH: u32;
+    /// Height in pixels (panel coordinates, without rotation)
+    const HEIGHT: u32;
+    /// Length of one buffer plane in bytes
+    const BUFFER_LEN: usize;
+    /// Name of the panel, e.g. `"2.66\""`
+    const MODEL: &'static str;
+    /// Identifier of the panel in the serial protocol, see [`Receiver`](crate::Receiver)
+    const PANEL_ID: u8;
+    /// Data of the soft reset command (PSR register)
+    const SOFT_RESET: u8;
+    /// Input temperature sent during initialisation
+    const INPUT_TEMPERATURE: u8;
+    /// Active temperature sent during initialisation
+    const ACTIVE_TEMPERATURE: u8;
+    /// Panel settings (PSR register) sent during initialisation
+    const PSR: [u8; 2];
+    /// Settings for the measured temperature, see `Epd::set_temperature_table`
+    const TEMPERATURE_TABLE: &'static [TemperatureSetting];
+    /// Panel settings (PSR register) of the wide temperature (FT) variant,
+    /// see `Epd::set_variant`
+    const WIDE_TEMPERATURE_PSR: [u8; 2];
+    /// Settings for the measured temperature of the wide temperature
+    /// variant
+    const WIDE_TEMPERATURE_TABLE: &'static [TemperatureSetting];
+    /// Gate/source non-overlap period (TCON register) sent during
+    /// initialisation, `None` keeps the power-on value of the COG
+    const TCON: Option<u8>;
+    /// Maximum SPI clock of the COG in Hz. Higher clocks are not detected
+    /// by the COG, but show up as corrupted pixels.
+    const MAX_SPI_HZ: u32;
+    /// Typical refresh durations from the datasheet of the panel, see
+    /// [`Self::estimate_refresh_duration`]
+    const REFRESH_DURATIONS: &'static [RefreshDuration] = SMALL_REFRESH_DURATIONS;
+    /// Rotation in which the panel is normally mounted, the rotation of
+    /// a new display
+    #[cfg(feature = "graphics")]
+    const NATURAL_ROTATION: DisplayRotation = DisplayRotation::Rotate0;
+
+    /// Check a configured SPI clock against [`Self::MAX_SPI_HZ`],
+    /// e.g. with an assert or a log message during startup
+    #[must_use]
+    fn supports_spi_frequency(hz: u32) -> bool {
+        hz <= Self::MAX_SPI_HZ
+    }
+
+    /// Typical duration of a refresh in ms at the ambient temperature
+    /// `celsius`, e.g. to size watchdog windows or as typical duration of
+    /// `Epd::set_progress_hook` before starting the refresh. The actual
+    /// duration varies between panels, so leave a margin. Wide temperature
+    /// panels take several times longer below 0 °C.
+    #[must_use]
+    fn estimate_refresh_duration(celsius: i8, mode: RefreshMode) -> u32 {
+        refresh_duration(Self::REFRESH_DURATIONS, celsius, mode)
+    }
+}
+
+/// Define the marker type `$panel` of a panel with its [`DisplaySpec`] and
+/// implement [`DisplaySpec`] for the display types of the panel by
+/// delegating to the marker. The initialisation data (soft reset, input
+/// temperature, active temperature, panel settings), the temperature table
+/// and the panel settings and temperature table of the wide temperature
+/// variant are given per size as listed in the application notes, as are the
+/// gate/source non-overlap period (`None` where the COG default applies) and
+/// the maximum SPI clock.
+macro_rules! display_spec {
+    (@impl [$($generics:tt)*] $display:ty, $panel:ty) => {
+        impl<$($generics)*> DisplaySpec for $display {
+            const WIDTH: u32 = <$panel as DisplaySpec>::WIDTH;
+            const HEIGHT: u32 = <$panel as DisplaySpec>::HEIGHT;
+            const BUFFER_LEN: usize = <$panel as DisplaySpec>::BUFFER_LEN;
+            const MODEL: &'static str = <$panel as DisplaySpec>::MODEL;
+            const PANEL_ID: u8 = <$panel as DisplaySpec>::PANEL_ID;
+            const SOFT_RESET: u8 = <$panel as DisplaySpec>::SOFT_RESET;
+            const INPUT_TEMPERATURE: u8 = <$panel as DisplaySpec>::INPUT_TEMPERATURE;
+            const ACTIVE_TEMPERATURE: u8 = <$panel as DisplaySpec>::ACTIVE_TEMPERATURE;
+            const PSR: [u8; 2] = <$panel as DisplaySpec>::PSR;
+            const TEMPERATURE_TABLE: &'static [TemperatureSetting] =
+                <$panel as DisplaySpec>::TEMPERATURE_TABLE;
+            const WIDE_TEMPERATURE_PSR: [u8; 2] = <$panel as DisplaySpec>::WIDE_TEMPERATURE_PSR;
+            const WIDE_TEMPERATURE_TABLE: &'static [TemperatureSetting] =
+                <$panel as DisplaySpec>::WIDE_TEMPERATURE_TABLE;
+            const TCON: Option<u8> = <$panel as DisplaySpec>::TCON;
+            const MAX_SPI_HZ: u32 = <$panel as DisplaySpec>::MAX_SPI_HZ;
+            const REFRESH_DURATIONS: &'static [RefreshDuration] =
+                <$panel as DisplaySpec>::REFRESH_DURATIONS;
+            const NATURAL_ROTATION: DisplayRotation = <$panel as DisplaySpec>::NATURAL_ROTATION;
+        }
+    };
+    (
+        panel: $panel:ident,
+        rows: $v:expr,
+        columns: $h:expr,
+        model: $model:literal,
+        id: $id:expr,
+        init: [$soft_reset:expr, $input:expr, $active:expr, $psr:expr],
+        temperature_table: $table:expr,
+        wide_temperature: [$wide_psr:expr, $wide_table:expr],
+        tcon: $tcon:expr,
+        max_spi_hz: $spi:expr,
+        durations: $durations:expr $(,)?
+    ) => {
+        #[doc = concat!("The ", $model, " panel, implements [`DisplaySpec`] without the")]
+        /// `graphics` feature, e.g. for `Epd::set_panel` or
+        /// [`encode_frame`](crate::encode_frame)
+        #[derive(Clone, Copy, PartialEq, Eq, Debug)]
+        pub struct $panel;
+
+        impl DisplaySpec for $panel {
+            const WIDTH: u32 = $h;
+            const HEIGHT: u32 = $v;
+            const BUFFER_LEN: usize = $v * ($h / 8);
+            const MODEL: &'static str = $model;
+            const PANEL_ID: u8 = $id;
+            const SOFT_RESET: u8 = $soft_reset;
+            const INPUT_TEMPERATURE: u8 = $input;
+            const ACTIVE_TEMPERATURE: u8 = $active;
+            const PSR: [u8; 2] = $psr;
+            const TEMPERATURE_TABLE: &'static [TemperatureSetting] = $table;
+            const WIDE_TEMPERATURE_PSR: [u8; 2] = $wide_psr;
+            const WIDE_TEMPERATURE_TABLE: &'static [TemperatureSetting] = $wide_table;
+            const TCON: Option<u8> = $tcon;
+            const MAX_SPI_HZ: u32 = $spi;
+            const REFRESH_DURATIONS: &'static [RefreshDuration] = $durations;
+            #[cfg(feature = "graphics")]
+            const NATURAL_ROTATION: DisplayRotation = DisplayRotation::natural($v, $h);
+        }
+
+        #[cfg(feature = "graphics")]
+        display_spec!(@impl [B: BufferStorage] Display<$v, $h, { $v * ($h / 8) }, B>, $panel);
+        #[cfg(feature = "graphics")]
+        display_spec!(@impl [] MonoDisplay<$v, $h, { $v * ($h / 8) }>, $panel);
+        #[cfg(feature = "graphics")]
+        display_spec!(@impl [] DoubleDisplay<$v, $h, { $v * ($h / 8) }>, $panel);
+    };
+}
+
+// the 4.2" panel with other panel settings is not supported
+display_spec! {
+    panel: Panel1in54,
+    rows: 152,
+    columns: 152,
+    model: "1.54\"",
+    id: 1,
+    init: [0x0e, 0x19, 0x02, [0xcf, 0x8d]],
+    temperature_table: SPECTRA_TEMPERATURE_TABLE,
+    wide_temperature: [[0xcf, 0x8d], WIDE_TEMPERATURE_TABLE],
+    tcon: None,
+    max_spi_hz: 8_000_000,
+    durations: SMALL_REFRESH_DURATIONS,
+}
+display_spec! {
+    panel: Panel2in13,
+    rows: 212,
+    columns: 104,
+    model: "2.13\"",
+    id: 2,
+    init: [0x0e, 0x19, 0x02, [0xcf, 0x8d]],
+    temperature_table: SPECTRA_TEMPERATURE_TABLE,
+    wide_temperature: [[0xcf, 0x8d], WIDE_TEMPERATURE_TABLE],
+    tcon: None,
+    max_spi_hz: 8_000_000,
+    durations: SMALL_REFRESH_DURATIONS,
+}
+display_spec! {
+    panel: Panel2in66,
+    rows: 296,
+    columns: 152,
+    model: "2.66\"",
+    id: 3,
+    init: [0x0e, 0x19, 0x02, [0xcf, 0x8d]],
+    temperature_table: SPECTRA_TEMPERATURE_TABLE,
+    wide_temperature: [[0xcf, 0x8d], WIDE_TEMPERATURE_TABLE],
+    tcon: None,
+    max_spi_hz: 8_000_000,
+    durations: SMALL_REFRESH_DURATIONS,
+}
+display_spec! {
+    panel: Panel2in71,
+    rows: 264,
+    columns: 176,
+    model: "2.71\"",
+    id: 4,
+    init: [0x0e, 0x19, 0x02, [0xcf, 0x8d]],
+    temperature_table: SPECTRA_TEMPERATURE_TABLE,
+    wide_temperature: [[0xcf, 0x8d], WIDE_TEMPERATURE_TABLE],
+    tcon: None,
+    max_spi_hz: 8_000_000,
+    durations: SMALL_REFRESH_DURATIONS,
+}
+display_spec! {
+    panel: Panel2in87,
+    rows: 296,
+    columns: 128,
+    model: "2.87\"",
+    id: 5,
+    init: [0x0e, 0x19, 0x02, [0xcf, 0x8d]],
+    temperature_table: SPECTRA_TEMPERATURE_TABLE,
+    wide_temperature: [[0xcf, 0x8d], WIDE_TEMPERATURE_TABLE],
+    tcon: None,
+    max_spi_hz: 8_000_000,
+    durations: SMALL_REFRESH_DURATIONS,
+}
+display_spec! {
+    panel: Panel3in70,
+    rows: 416,
+    columns: 240,
+    model: "3.70\"",
+    id: 6,
+    init: [0x0e, 0x19, 0x02, [0xcf, 0x8d]],
+    temperature_table: SPECTRA_TEMPERATURE_TABLE,
+    wide_temperature: [[0xcf, 0x8d], WIDE_TEMPERATURE_TABLE],
+    tcon: None,
+    max_spi_hz: 8_000_000,
+    durations: MEDIUM_REFRESH_DURATIONS,
+}
+display_spec! {
+    panel: Panel4in17,
+    rows: 300,
+    columns: 400,
+    model: "4.17\"",
+    id: 7,
+    init: [0x0e, 0x19, 0x02, [0xcf, 0x8d]],
+    temperature_table: SPECTRA_TEMPERATURE_TABLE,
+    wide_temperature: [[0xcf, 0x8d], WIDE_TEMPERATURE_TABLE],
+    tcon: None,
+    max_spi_hz: 8_000_000,
+    durations: LARGE_REFRESH_DURATIONS,
+}
+display_spec! {
+    panel: Panel4in37,
+    rows: 480,
+    columns: 176,
+    model: "4.37\"",
+    id: 8,
+    init: [0x0e, 0x19, 0x02, [0xcf, 0x8d]],
+    temperature_table: SPECTRA_TEMPERATURE_TABLE,
+    wide_temperature: [[0xcf, 0x8d], WIDE_TEMPERATURE_TABLE],
+    tcon: None,
+    max_spi_hz: 8_000_000,
+    durations: LARGE_REFRESH_DURATIONS,
+}
@@ -0,0 +1,130 @@
+//! Built-in splash screen, e.g. for the first boot before any assets are
+//! provisioned
+
+use embedded_hal::{delay::DelayNs, digital::InputPin, digital::OutputPin, spi::SpiDevice};
+
+use crate::{driver::EpdError, Active, Epd, FrameSource, Plane};
+
+/// Logo of 16x16 pixels, a framed "E", most significant bit left
+const LOGO: [u16; 16] = [
+    0x0000, 0x7ffe, 0x4002, 0x4ff2, 0x4ff2, 0x4c02, 0x4c02, 0x4fe2, 0x4fe2, 0x4c02, 0x4c02, 0x4ff2,
+    0x4ff2, 0x4002, 0x7ffe, 0x0000,
+];
+
+/// Splash frame generated on the fly, so no display buffer is needed: a
+/// black border, a red band at the top, the logo in the center and two
+/// placeholder lines below it. The size of the panel is given by `SIZE_V`
+/// and `SIZE_H` as for the display types, e.g. `Splash::<296, 152>` for
+/// 2.66". The layout is in panel coordinates (without rotation).
+pub struct Splash<const SIZE_V: u32, const SIZE_H: u32> {
+    /// plane and byte position of the next chunk
+    plane: Plane,
+    pos: usize,
+}
+
+impl<const SIZE_V: u32, const SIZE_H: u32> Splash<SIZE_V, SIZE_H> {
+    const ROW_LEN: usize = SIZE_H as usize / 8;
+    const LEN: usize = SIZE_V as usize * Self::ROW_LEN;
+    /// size of a logo pixel
+    const SCALE: u32 = {
+        let short = if SIZE_V < SIZE_H { SIZE_V } else { SIZE_H };
+        let scale = short / 3 / 16;
+        if scale == 0 {
+            1
+        } else {
+            scale
+        }
+    };
+    const BAND: u32 = SIZE_V / 6;
+
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            plane: Plane::Black,
+            pos: 0,
+        }
+    }
+
+    /// Color of a pixel as (black, red)
+    fn pixel(x: u32, y: u32) -> (bool, bool) {
+        let border = Self::SCALE.max(2);
+        if x < border || y < border || x >= SIZE_H - border || y >= SIZE_V - border {
+            return (true, false);
+        }
+        if y < Self::BAND {
+            return (false, true);
+        }
+        let logo_size = 16 * Self::SCALE;
+        let left = (SIZE_H - logo_size) / 2;
+        let top = Self::BAND + (SIZE_V - Self::BAND - logo_size) / 2;
+        if (left..left + logo_size).contains(&x) && (top..top + logo_size).contains(&y) {
+            let row = LOGO[((y - top) / Self::SCALE) as usize];
+            let bit = 15 - (x - left) / Self::SCALE;
+            return (row & (1 << bit) != 0, false);
+        }
+        // placeholder lines for text, half the width of the panel
+        let line = top + logo_size + Self::SCALE * 2;
+        let in_line = (line..line + Self::SCALE).contains(&y)
+            || (line + Self::SCALE * 3..line + Self::SCALE * 4).contains(&y);
+        (in_line && (SIZE_H / 4..SIZE_H * 3 / 4).contains(&x), false)
+    }
+
+    /// Byte of `plane` at `pos`
+    #[allow(clippy::cast_possible_truncation)]
+    fn byte(plane: Plane, pos: usize) -> u8 {
+        let y = (pos / Self::ROW_LEN) as u32;
+        let x = (pos % Self::ROW_LEN) as u32 * 8;
+        (0..8).fold(0, |byte, bit| {
+            let (black, red) = Self::pixel(x + bit, y);
+            let on = match plane {
+                Plane::Black => black,
+                Plane::Red => red,
+            };
+            byte | (u8::from(on) << (7 - bit))
+        })
+    }
+}
+
+impl<const SIZE_V: u32, const SIZE_H: u32> Default for Splash<SIZE_V, SIZE_H> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<const SIZE_V: u32, const SIZE_H: u32> FrameSource for Splash<SIZE_V, SIZE_H> {
+    fn next_chunk(&mut self, plane: Plane, buf: &mut [u8]) -> usize {
+        if plane != self.plane {
+            self.plane = plane;
+            self.pos = 0;
+        }
+        let len = buf.len().min(Self::LEN - self.pos);
+        for (i, byte) in buf[..len].iter_mut().enumerate() {
+            *byte = Self::byte(plane, self.pos + i);
+        }
+        self.pos += len;
+        len
+    }
+}
+
+impl<BUSY, DC, RST> Epd<Active, BUSY, DC, RST>
+where
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+{
+    /// Show the built-in splash screen, see [`Splash`]. The size of the
+    /// panel is given as for `self_test`, e.g. `show_splash::<_, 296, 152>`.
+    /// This function is blocking until the update process is complete.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if there is an error
+    /// with the GPIOs or the SPI device.
+    pub fn show_splash<SPI: SpiDevice, const SIZE_V: u32, const SIZE_H: u32>(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut impl DelayNs,
+    ) -> Result<(), EpdError<SPI, DC, RST>> {
+        self.update_from_source(&mut Splash::<SIZE_V, SIZE_H>::new(), spi, delay)
+    }
+}
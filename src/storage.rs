@@ -0,0 +1,86 @@
+//! Memory holding the planes of a display buffer
+
+/// One of the two buffer planes of a display
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Plane {
+    Black,
+    Red,
+}
+
+/// Raw plane data sent to the e-paper. An empty red buffer is sent
+/// as all zeros, i.e. without any red pixels.
+pub trait DisplayBuffer {
+    fn get_buffer_black(&self) -> &[u8];
+    fn get_buffer_red(&self) -> &[u8];
+}
+
+impl DisplayBuffer for (&[u8], &[u8]) {
+    fn get_buffer_black(&self) -> &[u8] {
+        self.0
+    }
+    fn get_buffer_red(&self) -> &[u8] {
+        self.1
+    }
+}
+
+/// Pre-rendered frame, e.g. planes stored in flash with `include_bytes!`,
+/// which can be passed to `Epd::update` without a [`Display`](crate::Display)
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct RawFrame<'a> {
+    pub black: &'a [u8],
+    /// red plane, empty for a frame without red pixels
+    pub red: &'a [u8],
+}
+
+impl<'a> RawFrame<'a> {
+    #[must_use]
+    pub const fn new(black: &'a [u8], red: &'a [u8]) -> Self {
+        Self { black, red }
+    }
+}
+
+impl DisplayBuffer for RawFrame<'_> {
+    fn get_buffer_black(&self) -> &[u8] {
+        self.black
+    }
+    fn get_buffer_red(&self) -> &[u8] {
+        self.red
+    }
+}
+
+/// Memory for one plane of a [`Display`](crate::Display), e.g. an owned
+/// array (the default), a borrowed slice in a static or external RAM or a
+/// `Vec` with std. The storage must hold at least `IMAGE_SIZE` bytes,
+/// additional bytes are not used.
+pub trait BufferStorage {
+    fn as_slice(&self) -> &[u8];
+    fn as_mut_slice(&mut self) -> &mut [u8];
+}
+
+impl<const N: usize> BufferStorage for [u8; N] {
+    fn as_slice(&self) -> &[u8] {
+        self
+    }
+    fn as_mut_slice(&mut self) -> &mut [u8] {
+        self
+    }
+}
+
+impl BufferStorage for &mut [u8] {
+    fn as_slice(&self) -> &[u8] {
+        self
+    }
+    fn as_mut_slice(&mut self) -> &mut [u8] {
+        self
+    }
+}
+
+#[cfg(feature = "std")]
+impl BufferStorage for std::vec::Vec<u8> {
+    fn as_slice(&self) -> &[u8] {
+        self
+    }
+    fn as_mut_slice(&mut self) -> &mut [u8] {
+        self
+    }
+}
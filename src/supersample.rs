@@ -0,0 +1,105 @@
+//! Rendering at a multiple of the panel resolution on the host, for
+//! smoother text and curves in pre-rendered frames
+
+use std::{vec, vec::Vec};
+
+use embedded_graphics::{
+    draw_target::DrawTarget,
+    geometry::{OriginDimensions, Point, Size},
+    pixelcolor::{Rgb888, RgbColor},
+    Pixel,
+};
+
+use crate::{ColorMapper, TriColor};
+
+/// RGB draw target with `factor` × `factor` pixels per pixel of the
+/// target size. Draw the scene scaled up by the factor (e.g. with a font of
+/// three times the size for a factor of 3), then convert it with
+/// [`Supersampled::downsample`]: each block is averaged, so edges become
+/// intermediate colors which a dithering mapper renders as patterns.
+pub struct Supersampled {
+    factor: u32,
+    size: Size,
+    pixels: Vec<Rgb888>,
+}
+
+impl Supersampled {
+    /// White canvas for a target of `size` (e.g. `display.size()`)
+    ///
+    /// # Panics
+    ///
+    /// Panics if `factor` is not 2, 3 or 4.
+    #[must_use]
+    pub fn new(size: Size, factor: u32) -> Self {
+        assert!((2..=4).contains(&factor), "factor must be 2, 3 or 4");
+        let size = size * factor;
+        Self {
+            factor,
+            size,
+            pixels: vec![Rgb888::WHITE; size.width as usize * size.height as usize],
+        }
+    }
+
+    #[must_use]
+    pub fn factor(&self) -> u32 {
+        self.factor
+    }
+
+    /// Average the blocks of the canvas and draw them into `target` with
+    /// the colors of `mapper`, e.g. `GrayDither` or `WarmDither`
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if drawing to `target` fails.
+    #[allow(clippy::cast_possible_truncation, clippy::cast_possible_wrap)]
+    pub fn downsample<D>(&self, target: &mut D, mapper: &impl ColorMapper) -> Result<(), D::Error>
+    where
+        D: DrawTarget<Color = TriColor>,
+    {
+        let factor = self.factor as usize;
+        let width = self.size.width as usize;
+        let (columns, rows) = (width / factor, self.size.height as usize / factor);
+        let samples = (factor * factor) as u32;
+        let pixels = (0..rows).flat_map(|row| (0..columns).map(move |column| (column, row)));
+        target.draw_iter(pixels.map(|(column, row)| {
+            let (mut r, mut g, mut b) = (0, 0, 0);
+            for y in row * factor..(row + 1) * factor {
+                for color in &self.pixels[y * width + column * factor..][..factor] {
+                    r += u32::from(color.r());
+                    g += u32::from(color.g());
+                    b += u32::from(color.b());
+                }
+            }
+            // rounded averages of values up to 255
+            let average = |sum: u32| ((sum + samples / 2) / samples) as u8;
+            let color = Rgb888::new(average(r), average(g), average(b));
+            let point = Point::new(column as i32, row as i32);
+            Pixel(point, mapper.map(color, point))
+        }))
+    }
+}
+
+impl OriginDimensions for Supersampled {
+    fn size(&self) -> Size {
+        self.size
+    }
+}
+
+impl DrawTarget for Supersampled {
+    type Color = Rgb888;
+    type Error = core::convert::Infallible;
+
+    #[allow(clippy::cast_sign_loss, clippy::cast_possible_wrap)]
+    fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Pixel<Self::Color>>,
+    {
+        let (width, height) = (self.size.width as i32, self.size.height as i32);
+        for Pixel(point, color) in pixels {
+            if (0..width).contains(&point.x) && (0..height).contains(&point.y) {
+                self.pixels[point.y as usize * width as usize + point.x as usize] = color;
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,223 @@
+//! Tables of text with column widths, alignment and rule lines
+
+use embedded_graphics::{
+    draw_target::{DrawTarget, DrawTargetExt},
+    geometry::{Dimensions, Point, Size},
+    primitives::{Line, Primitive, PrimitiveStyle, Rectangle},
+    text::{
+        renderer::{CharacterStyle, TextRenderer},
+        Alignment, Baseline, Text, TextStyleBuilder,
+    },
+    Drawable,
+};
+
+use crate::TriColor;
+
+/// Space between the text and the borders of a cell
+const PADDING: u32 = 2;
+
+/// Width and text alignment of a table column
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Column {
+    pub width: u32,
+    pub alignment: Alignment,
+}
+
+impl Column {
+    #[must_use]
+    pub const fn new(width: u32, alignment: Alignment) -> Self {
+        Self { width, alignment }
+    }
+}
+
+/// Table with an optional header row, drawn with one text style. The
+/// header is separated from the rows with a rule line. Text is clipped
+/// at the borders of its cell. Emphasized cells are drawn in red.
+pub struct Table<'a, S> {
+    top_left: Point,
+    columns: &'a [Column],
+    header: Option<&'a [&'a str]>,
+    rows: &'a [&'a [&'a str]],
+    style: S,
+    row_height: u32,
+    emphasis: &'a [(usize, usize)],
+    grid: bool,
+}
+
+impl<'a, S> Table<'a, S>
+where
+    S: TextRenderer<Color = TriColor> + CharacterStyle<Color = TriColor> + Clone,
+{
+    /// Table of `rows` at `top_left`, each row holds the text of its cells.
+    /// Cells beyond the number of `columns` are ignored.
+    pub fn new(
+        top_left: Point,
+        columns: &'a [Column],
+        rows: &'a [&'a [&'a str]],
+        style: S,
+    ) -> Self {
+        let row_height = style.line_height() + 2 * PADDING;
+        Self {
+            top_left,
+            columns,
+            header: None,
+            rows,
+            style,
+            row_height,
+            emphasis: &[],
+            grid: false,
+        }
+    }
+
+    /// Header row above the rows
+    #[must_use]
+    pub fn with_header(mut self, header: &'a [&'a str]) -> Self {
+        self.header = Some(header);
+        self
+    }
+
+    /// Height of each row, the default is the line height of the style
+    /// plus padding
+    #[must_use]
+    pub fn with_row_height(mut self, row_height: u32) -> Self {
+        self.row_height = row_height;
+        self
+    }
+
+    /// Draw the text of the given cells (row, column) in red. The row
+    /// index does not include the header.
+    #[must_use]
+    pub fn with_emphasis(mut self, cells: &'a [(usize, usize)]) -> Self {
+        self.emphasis = cells;
+        self
+    }
+
+    /// Draw rule lines between all rows and columns and around the table
+    #[must_use]
+    pub fn with_grid(mut self, grid: bool) -> Self {
+        self.grid = grid;
+        self
+    }
+
+    fn width(&self) -> u32 {
+        self.columns.iter().map(|column| column.width).sum()
+    }
+
+    fn line_count(&self) -> usize {
+        self.rows.len() + usize::from(self.header.is_some())
+    }
+
+    /// Top of the given line of the table (header included)
+    fn line_top(&self, line: usize) -> i32 {
+        let offset = u32::try_from(line).unwrap_or(u32::MAX) * self.row_height;
+        self.top_left.y + i32::try_from(offset).unwrap_or(i32::MAX)
+    }
+
+    /// Draw the cells of one line of the table
+    fn draw_line<D>(
+        &self,
+        target: &mut D,
+        cells: &[&str],
+        top: i32,
+        emphasized: impl Fn(usize) -> bool,
+    ) -> Result<(), D::Error>
+    where
+        D: DrawTarget<Color = TriColor>,
+    {
+        let mut left = self.top_left.x;
+        for (index, (column, text)) in self.columns.iter().zip(cells).enumerate() {
+            let cell = Rectangle::new(
+                Point::new(left, top),
+                Size::new(column.width, self.row_height),
+            );
+            let inner = cell.offset(-i32::try_from(PADDING).unwrap_or(0));
+            let x = match column.alignment {
+                Alignment::Left => inner.top_left.x,
+                Alignment::Center => inner.center().x,
+                Alignment::Right => inner.bottom_right().map_or(inner.top_left.x, |p| p.x),
+            };
+            let text_style = TextStyleBuilder::new()
+                .alignment(column.alignment)
+                .baseline(Baseline::Middle)
+                .build();
+            let mut style = self.style.clone();
+            if emphasized(index) {
+                style.set_text_color(Some(TriColor::Red));
+            }
+            let position = Point::new(x, cell.center().y);
+            Text::with_text_style(text, position, style, text_style)
+                .draw(&mut target.clipped(&inner))?;
+            left += i32::try_from(column.width).unwrap_or(i32::MAX);
+        }
+        Ok(())
+    }
+}
+
+impl<S> Dimensions for Table<'_, S>
+where
+    S: TextRenderer<Color = TriColor> + CharacterStyle<Color = TriColor> + Clone,
+{
+    fn bounding_box(&self) -> Rectangle {
+        let height = u32::try_from(self.line_count()).unwrap_or(u32::MAX) * self.row_height;
+        Rectangle::new(self.top_left, Size::new(self.width(), height))
+    }
+}
+
+impl<S> Drawable for Table<'_, S>
+where
+    S: TextRenderer<Color = TriColor> + CharacterStyle<Color = TriColor> + Clone,
+{
+    type Color = TriColor;
+    type Output = ();
+
+    fn draw<D>(&self, target: &mut D) -> Result<Self::Output, D::Error>
+    where
+        D: DrawTarget<Color = Self::Color>,
+    {
+        let mut line = 0;
+        if let Some(header) = self.header {
+            self.draw_line(target, header, self.line_top(0), |_| false)?;
+            line = 1;
+        }
+        for (row, cells) in self.rows.iter().enumerate() {
+            let emphasized = |column| self.emphasis.contains(&(row, column));
+            self.draw_line(target, cells, self.line_top(line + row), emphasized)?;
+        }
+
+        let Some(bottom_right) = self.bounding_box().bottom_right() else {
+            return Ok(());
+        };
+        let (left, right) = (self.top_left.x, bottom_right.x);
+        if self.header.is_some() && !self.grid {
+            let y = self.line_top(1) - 1;
+            rule(target, Point::new(left, y), Point::new(right, y))?;
+        }
+        if self.grid {
+            for line in 0..=self.line_count() {
+                let y = self.line_top(line).min(bottom_right.y);
+                rule(target, Point::new(left, y), Point::new(right, y))?;
+            }
+            let mut x = left;
+            for column in self.columns {
+                rule(
+                    target,
+                    Point::new(x, self.top_left.y),
+                    Point::new(x, bottom_right.y),
+                )?;
+                x += i32::try_from(column.width).unwrap_or(i32::MAX);
+            }
+            rule(target, Point::new(right, self.top_left.y), bottom_right)?;
+        }
+        Ok(())
+    }
+}
+
+/// Black horizontal or vertical rule line
+fn rule<D>(target: &mut D, start: Point, end: Point) -> Result<(), D::Error>
+where
+    D: DrawTarget<Color = TriColor>,
+{
+    Line::new(start, end)
+        .into_styled(PrimitiveStyle::with_stroke(TriColor::Black, 1))
+        .draw(target)
+}
@@ -0,0 +1,372 @@
+//! Test doubles for host tests. The SPI device and the pins created by a
+//! [`Recorder`] log every command and data byte the driver sends, so the
+//! emitted sequences can be checked against the application notes,
+//! e.g. with [`assert_sequence`].
+
+use core::{cell::RefCell, convert::Infallible};
+use std::{rc::Rc, vec::Vec};
+
+use embedded_hal::{
+    delay::DelayNs,
+    digital::{self, OutputPin},
+    spi::{self, Operation, SpiDevice},
+};
+
+use crate::{Command, NoBusyPin};
+
+/// One entry of the recorded log
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum Record {
+    /// command byte together with all data bytes sent after it
+    Command { command: u8, data: Vec<u8> },
+    /// change of the reset pin
+    Reset { high: bool },
+    /// bytes read from the COG, e.g. the status register
+    Read { len: usize },
+}
+
+/// Expected command for [`assert_sequence`]
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Cmd<'a> {
+    Psr(&'a [u8]),
+    InputTemperature(&'a [u8]),
+    ActiveTemperature(&'a [u8]),
+    PowerOn,
+    PowerOff,
+    Refresh,
+    /// black buffer with the given number of bytes
+    BufferBlack(usize),
+    /// red buffer with the given number of bytes
+    BufferRed(usize),
+    /// given command byte with arbitrary data
+    Any(u8),
+    /// given command byte with exactly the given data
+    Raw(u8, &'a [u8]),
+}
+
+impl Cmd<'_> {
+    fn matches(&self, command: u8, data: &[u8]) -> bool {
+        match *self {
+            Cmd::Psr(expected) => command == Command::Psr as u8 && data == expected,
+            Cmd::InputTemperature(expected) => {
+                command == Command::InputTemperature as u8 && data == expected
+            }
+            Cmd::ActiveTemperature(expected) => {
+                command == Command::ActiveTemperature as u8 && data == expected
+            }
+            Cmd::PowerOn => command == Command::PowerOn as u8,
+            Cmd::PowerOff => command == Command::PowerOff as u8,
+            Cmd::Refresh => command == Command::Refresh as u8,
+            Cmd::BufferBlack(len) => command == Command::BufferBlack as u8 && data.len() == len,
+            Cmd::BufferRed(len) => command == Command::BufferRed as u8 && data.len() == len,
+            Cmd::Any(expected) => command == expected,
+            Cmd::Raw(expected, expected_data) => command == expected && data == expected_data,
+        }
+    }
+}
+
+/// Assert that the commands in `records` match `expected` exactly.
+/// Changes of the reset pin and reads are ignored.
+///
+/// # Panics
+///
+/// Panics with a description of the first mismatch if the sequences differ.
+pub fn assert_sequence(records: &[Record], expected: &[Cmd<'_>]) {
+    let commands: Vec<(u8, &[u8])> = records
+        .iter()
+        .filter_map(|record| match record {
+            Record::Command { command, data } => Some((*command, data.as_slice())),
+            Record::Reset { .. } | Record::Read { .. } => None,
+        })
+        .collect();
+    for (index, cmd) in expected.iter().enumerate() {
+        let Some(&(command, data)) = commands.get(index) else {
+            panic!("command {index}: expected {cmd:?}, but the sequence ended");
+        };
+        assert!(
+            cmd.matches(command, data),
+            "command {index}: expected {cmd:?}, got 0x{command:02x} with {} data bytes {data:02x?}",
+            data.len()
+        );
+    }
+    assert!(
+        commands.len() == expected.len(),
+        "expected {} commands, got {}: next is 0x{:02x}",
+        expected.len(),
+        commands.len(),
+        commands[expected.len()].0
+    );
+}
+
+#[derive(Default)]
+struct State {
+    records: Vec<Record>,
+    dc_high: bool,
+    delay_ns: u64,
+    /// value of all bytes read from the COG
+    read_value: u8,
+}
+
+impl State {
+    fn write(&mut self, bytes: &[u8]) {
+        if self.dc_high {
+            // data bytes sent without a preceding command are dropped
+            if let Some(Record::Command { data, .. }) = self.records.last_mut() {
+                data.extend_from_slice(bytes);
+            }
+        } else {
+            for &command in bytes {
+                self.records.push(Record::Command {
+                    command,
+                    data: Vec::new(),
+                });
+            }
+        }
+    }
+
+    fn read(&mut self, bytes: &mut [u8]) {
+        bytes.fill(self.read_value);
+        self.records.push(Record::Read { len: bytes.len() });
+    }
+}
+
+/// Creates the recording test doubles and gives access to the shared log
+#[derive(Clone, Default)]
+pub struct Recorder {
+    state: Rc<RefCell<State>>,
+}
+
+impl Recorder {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    #[must_use]
+    pub fn spi(&self) -> RecordingSpi {
+        RecordingSpi {
+            state: self.state.clone(),
+        }
+    }
+
+    #[must_use]
+    pub fn dc(&self) -> RecordingDc {
+        RecordingDc {
+            state: self.state.clone(),
+        }
+    }
+
+    #[must_use]
+    pub fn rst(&self) -> RecordingRst {
+        RecordingRst {
+            state: self.state.clone(),
+        }
+    }
+
+    /// Busy pin which never reports busy
+    #[must_use]
+    pub fn busy(&self) -> NoBusyPin {
+        NoBusyPin
+    }
+
+    /// Set the value of all bytes read from the COG, 0 by default. E.g.
+    /// 0x01 reports an idle COG in the status register.
+    pub fn set_read_value(&self, value: u8) {
+        self.state.borrow_mut().read_value = value;
+    }
+
+    /// Delay which returns immediately and only sums up the requested time
+    #[must_use]
+    pub fn delay(&self) -> RecordingDelay {
+        RecordingDelay {
+            state: self.state.clone(),
+        }
+    }
+
+    /// All records logged so far
+    #[must_use]
+    pub fn records(&self) -> Vec<Record> {
+        self.state.borrow().records.clone()
+    }
+
+    /// Command bytes logged so far, without data, reset changes and reads
+    #[must_use]
+    pub fn commands(&self) -> Vec<u8> {
+        self.state
+            .borrow()
+            .records
+            .iter()
+            .filter_map(|record| match record {
+                Record::Command { command, .. } => Some(*command),
+                Record::Reset { .. } | Record::Read { .. } => None,
+            })
+            .collect()
+    }
+
+    /// Sum of all requested delays in nanoseconds
+    #[must_use]
+    pub fn delay_ns(&self) -> u64 {
+        self.state.borrow().delay_ns
+    }
+
+    /// Remove all records
+    pub fn clear(&self) {
+        self.state.borrow_mut().records.clear();
+    }
+}
+
+/// SPI device logging all written bytes and reads, reads return the value
+/// set with `Recorder::set_read_value`
+pub struct RecordingSpi {
+    state: Rc<RefCell<State>>,
+}
+
+impl spi::ErrorType for RecordingSpi {
+    type Error = Infallible;
+}
+
+impl SpiDevice for RecordingSpi {
+    fn transaction(&mut self, operations: &mut [Operation<'_, u8>]) -> Result<(), Self::Error> {
+        let mut state = self.state.borrow_mut();
+        for operation in operations {
+            match operation {
+                Operation::Write(bytes) => state.write(bytes),
+                Operation::Transfer(read, write) => {
+                    state.write(write);
+                    state.read(read);
+                }
+                Operation::TransferInPlace(bytes) => {
+                    state.write(bytes);
+                    state.read(bytes);
+                }
+                Operation::Read(bytes) => state.read(bytes),
+                Operation::DelayNs(ns) => state.delay_ns += u64::from(*ns),
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Data/command pin deciding whether written bytes are logged as command or data
+pub struct RecordingDc {
+    state: Rc<RefCell<State>>,
+}
+
+impl digital::ErrorType for RecordingDc {
+    type Error = Infallible;
+}
+
+impl OutputPin for RecordingDc {
+    fn set_low(&mut self) -> Result<(), Self::Error> {
+        self.state.borrow_mut().dc_high = false;
+        Ok(())
+    }
+
+    fn set_high(&mut self) -> Result<(), Self::Error> {
+        self.state.borrow_mut().dc_high = true;
+        Ok(())
+    }
+}
+
+/// Reset pin logging all changes
+pub struct RecordingRst {
+    state: Rc<RefCell<State>>,
+}
+
+impl digital::ErrorType for RecordingRst {
+    type Error = Infallible;
+}
+
+impl OutputPin for RecordingRst {
+    fn set_low(&mut self) -> Result<(), Self::Error> {
+        self.state
+            .borrow_mut()
+            .records
+            .push(Record::Reset { high: false });
+        Ok(())
+    }
+
+    fn set_high(&mut self) -> Result<(), Self::Error> {
+        self.state
+            .borrow_mut()
+            .records
+            .push(Record::Reset { high: true });
+        Ok(())
+    }
+}
+
+/// Delay which returns immediately and sums up the requested time
+pub struct RecordingDelay {
+    state: Rc<RefCell<State>>,
+}
+
+impl DelayNs for RecordingDelay {
+    fn delay_ns(&mut self, ns: u32) {
+        self.state.borrow_mut().delay_ns += u64::from(ns);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::vec;
+
+    #[test]
+    fn command_and_data_split_by_dc() {
+        let recorder = Recorder::new();
+        let (mut spi, mut dc, mut rst) = (recorder.spi(), recorder.dc(), recorder.rst());
+        rst.set_low().unwrap();
+        dc.set_low().unwrap();
+        spi.write(&[0x04]).unwrap();
+        dc.set_high().unwrap();
+        // data without a new command belongs to the last command
+        spi.write(&[0x01, 0x02]).unwrap();
+        spi.write(&[0x03]).unwrap();
+        dc.set_low().unwrap();
+        spi.write(&[0x12, 0x02]).unwrap();
+        assert_eq!(
+            recorder.records(),
+            vec![
+                Record::Reset { high: false },
+                Record::Command {
+                    command: 0x04,
+                    data: vec![0x01, 0x02, 0x03],
+                },
+                Record::Command {
+                    command: 0x12,
+                    data: vec![],
+                },
+                Record::Command {
+                    command: 0x02,
+                    data: vec![],
+                },
+            ]
+        );
+        assert_eq!(recorder.commands(), vec![0x04, 0x12, 0x02]);
+    }
+
+    #[test]
+    fn reads_are_recorded() {
+        let recorder = Recorder::new();
+        let (mut spi, mut dc) = (recorder.spi(), recorder.dc());
+        recorder.set_read_value(0x01);
+        dc.set_low().unwrap();
+        spi.write(&[Command::GetStatus as u8]).unwrap();
+        dc.set_high().unwrap();
+        let mut status = [0; 2];
+        spi.read(&mut status).unwrap();
+        assert_eq!(status, [0x01, 0x01]);
+        assert_eq!(
+            recorder.records(),
+            vec![
+                Record::Command {
+                    command: Command::GetStatus as u8,
+                    data: vec![],
+                },
+                Record::Read { len: 2 },
+            ]
+        );
+        // reads are not part of the command sequence
+        assert_sequence(&recorder.records(), &[Cmd::Any(Command::GetStatus as u8)]);
+    }
+}
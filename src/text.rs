@@ -0,0 +1,207 @@
+//! Minimal multi-line text drawing without additional crates
+
+use embedded_graphics::{
+    draw_target::DrawTarget,
+    geometry::Point,
+    primitives::Rectangle,
+    text::{renderer::TextRenderer, Baseline},
+    Drawable,
+};
+
+const ELLIPSIS: &str = "...";
+
+/// Text which is wrapped greedily at spaces to fit into `bounds`.
+/// Explicit line breaks (`\n`) are respected and words wider than
+/// `bounds` are broken between characters. Lines which do not fit
+/// into the height of `bounds` are omitted, optionally ending the
+/// last visible line with an ellipsis.
+pub struct WrappedText<'a, S> {
+    text: &'a str,
+    bounds: Rectangle,
+    style: S,
+    ellipsis: bool,
+}
+
+impl<'a, S: TextRenderer> WrappedText<'a, S> {
+    pub fn new(text: &'a str, bounds: Rectangle, style: S) -> Self {
+        Self {
+            text,
+            bounds,
+            style,
+            ellipsis: false,
+        }
+    }
+
+    /// End the last visible line with "..." if the text is truncated
+    #[must_use]
+    pub fn with_ellipsis(mut self, ellipsis: bool) -> Self {
+        self.ellipsis = ellipsis;
+        self
+    }
+
+    fn width(&self, text: &str) -> u32 {
+        self.style
+            .measure_string(text, Point::zero(), Baseline::Top)
+            .bounding_box
+            .size
+            .width
+    }
+
+    fn advance(&self, text: &str) -> u32 {
+        let metrics = self
+            .style
+            .measure_string(text, Point::zero(), Baseline::Top);
+        metrics.next_position.x.unsigned_abs()
+    }
+
+    /// Find the next line starting at `start`.
+    /// Returns the end of the line and the start of the following line.
+    fn next_line(&self, start: usize) -> (usize, usize) {
+        let rest = &self.text[start..];
+        let (limit, skip) = match rest.find('\n') {
+            Some(newline) => (newline, 1),
+            None => (rest.len(), 0),
+        };
+        let candidate = &rest[..limit];
+        if self.width(candidate) <= self.bounds.size.width {
+            return (start + limit, start + limit + skip);
+        }
+
+        // break at the last space which leaves a fitting line
+        let space = candidate
+            .char_indices()
+            .filter(|&(i, c)| c == ' ' && i > 0)
+            .map(|(i, _)| i)
+            .take_while(|&i| self.width(candidate[..i].trim_end()) <= self.bounds.size.width)
+            .last();
+        if let Some(i) = space {
+            return (start + i, start + i + 1);
+        }
+
+        // word is too long, break between characters (at least one character per line)
+        let end = candidate
+            .char_indices()
+            .skip(1)
+            .map(|(i, _)| i)
+            .take_while(|&i| self.width(&candidate[..i]) <= self.bounds.size.width)
+            .last()
+            .unwrap_or_else(|| candidate.chars().next().map_or(0, char::len_utf8));
+        (start + end, start + end)
+    }
+
+    /// Shorten `line` so that it fits into the bounds together with an ellipsis
+    fn truncate<'b>(&self, line: &'b str) -> &'b str {
+        let available = self.bounds.size.width.saturating_sub(self.width(ELLIPSIS));
+        let end = line
+            .char_indices()
+            .map(|(i, _)| i)
+            .chain(core::iter::once(line.len()))
+            .take_while(|&i| self.advance(&line[..i]) <= available)
+            .last()
+            .unwrap_or(0);
+        &line[..end]
+    }
+}
+
+impl<S: TextRenderer> Drawable for WrappedText<'_, S> {
+    type Color = S::Color;
+    type Output = ();
+
+    fn draw<D>(&self, target: &mut D) -> Result<Self::Output, D::Error>
+    where
+        D: DrawTarget<Color = Self::Color>,
+    {
+        let line_height = self.style.line_height();
+        if line_height == 0 {
+            return Ok(());
+        }
+        let max_lines = self.bounds.size.height / line_height;
+        let mut position = self.bounds.top_left;
+        let mut start = 0;
+        for line_number in 0..max_lines {
+            start += self.text[start..].len() - self.text[start..].trim_start_matches(' ').len();
+            if start >= self.text.len() {
+                break;
+            }
+            let (end, next) = self.next_line(start);
+            let line = self.text[start..end].trim_end();
+            let truncated = line_number + 1 == max_lines && !self.text[next..].trim().is_empty();
+            if self.ellipsis && truncated {
+                let line = self.truncate(line);
+                let next_position =
+                    self.style
+                        .draw_string(line, position, Baseline::Top, target)?;
+                self.style
+                    .draw_string(ELLIPSIS, next_position, Baseline::Top, target)?;
+            } else {
+                self.style
+                    .draw_string(line, position, Baseline::Top, target)?;
+            }
+            start = next;
+            position.y += i32::try_from(line_height).unwrap_or(i32::MAX);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use embedded_graphics::{
+        geometry::Size,
+        mock_display::MockDisplay,
+        mono_font::{ascii::FONT_6X10, MonoTextStyle},
+        pixelcolor::BinaryColor,
+        text::Text,
+    };
+
+    use super::*;
+
+    const STYLE: MonoTextStyle<'static, BinaryColor> =
+        MonoTextStyle::new(&FONT_6X10, BinaryColor::On);
+
+    /// Text with two lines of `chars` characters of 6 pixels
+    fn wrapped(text: &str, chars: u32) -> WrappedText<'_, MonoTextStyle<'static, BinaryColor>> {
+        WrappedText::new(
+            text,
+            Rectangle::new(Point::zero(), Size::new(chars * 6, 20)),
+            STYLE,
+        )
+    }
+
+    #[test]
+    fn word_longer_than_the_line_is_broken() {
+        let text = wrapped("abcdefghij", 5);
+        assert_eq!(text.next_line(0), (5, 5));
+        assert_eq!(text.next_line(5), (10, 10));
+    }
+
+    #[test]
+    fn explicit_line_break() {
+        let text = wrapped("ab\ncd", 5);
+        assert_eq!(text.next_line(0), (2, 3));
+        assert_eq!(text.next_line(3), (5, 5));
+    }
+
+    #[test]
+    fn trailing_spaces_do_not_wrap() {
+        let text = wrapped("hello world  ", 11);
+        assert_eq!(text.next_line(0), (12, 13));
+    }
+
+    #[test]
+    fn truncated_with_ellipsis() {
+        let mut display = MockDisplay::new();
+        wrapped("hello world foo", 5)
+            .with_ellipsis(true)
+            .draw(&mut display)
+            .unwrap();
+
+        let mut expected = MockDisplay::new();
+        for (line, y) in [("hello", 0), ("wo...", 10)] {
+            Text::with_baseline(line, Point::new(0, y), STYLE, Baseline::Top)
+                .draw(&mut expected)
+                .unwrap();
+        }
+        display.assert_eq(&expected);
+    }
+}
@@ -0,0 +1,439 @@
+//! Serial protocol for pushing frames from a host to a bridging MCU, e.g.
+//! over UART or USB-CDC
+//!
+//! A frame consists of an 8 byte header (magic `"ES"`, protocol version,
+//! panel id, length of one plane as `u32` little endian), the black and
+//! the red plane compressed with `PackBits` and a CRC-32 (IEEE) of all
+//! previous bytes, little endian.
+
+use crate::{DisplayBuffer, RawFrame};
+
+const MAGIC: [u8; 2] = *b"ES";
+const VERSION: u8 = 1;
+const HEADER_LEN: usize = 8;
+
+/// Error while receiving a frame with a [`Receiver`]
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum TransportError {
+    /// the protocol version of the frame is not supported
+    UnsupportedVersion(u8),
+    /// the frame is for another panel
+    WrongPanel { expected: u8, actual: u8 },
+    /// the length of the planes does not match the buffers
+    SizeMismatch { expected: usize, actual: usize },
+    /// the compressed data is longer than the planes
+    InvalidData,
+    /// the checksum does not match, the data was corrupted
+    Crc,
+}
+
+/// Encode `frame` for the panel with `panel_id` (see `DisplaySpec::PANEL_ID`)
+/// and pass the bytes to `write`, e.g. the write function of a serial port.
+/// An empty red plane is sent as all zeros.
+///
+/// # Panics
+///
+/// Panics if a plane is longer than `u32::MAX` bytes.
+pub fn encode_frame(panel_id: u8, frame: &impl DisplayBuffer, mut write: impl FnMut(u8)) {
+    let black = frame.get_buffer_black();
+    let red = frame.get_buffer_red();
+    let len = u32::try_from(black.len()).expect("plane too long");
+    let mut crc = Crc::new();
+    let mut emit = |byte| {
+        crc.update(byte);
+        write(byte);
+    };
+    for byte in MAGIC.into_iter().chain([VERSION, panel_id]) {
+        emit(byte);
+    }
+    len.to_le_bytes().into_iter().for_each(&mut emit);
+    packbits(black.len(), |i| black[i], &mut emit);
+    packbits(black.len(), |i| red.get(i).copied().unwrap_or(0), &mut emit);
+    let crc = crc.finish();
+    crc.to_le_bytes().into_iter().for_each(write);
+}
+
+/// Compress `len` bytes with `PackBits`: runs of at least 3 equal bytes are
+/// sent as repeat count and byte, everything else as literal blocks
+#[allow(clippy::cast_possible_truncation)]
+fn packbits(len: usize, byte_at: impl Fn(usize) -> u8, emit: &mut impl FnMut(u8)) {
+    let run_at = |start: usize| {
+        let byte = byte_at(start);
+        (start..len.min(start + 128))
+            .take_while(|&i| byte_at(i) == byte)
+            .count()
+    };
+    let mut i = 0;
+    while i < len {
+        let run = run_at(i);
+        if run >= 3 {
+            emit((257 - run) as u8);
+            emit(byte_at(i));
+            i += run;
+            continue;
+        }
+        let mut end = i + 1;
+        while end < len && end - i < 128 && run_at(end) < 3 {
+            end += 1;
+        }
+        emit((end - i - 1) as u8);
+        (i..end).for_each(|j| emit(byte_at(j)));
+        i = end;
+    }
+}
+
+/// Step of the receiver within a frame
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+enum State {
+    /// bytes of the header received so far
+    Header(usize),
+    /// waiting for the control byte of the next `PackBits` block
+    Control,
+    /// remaining bytes of a literal block
+    Literal(usize),
+    /// count of a repeated byte, waiting for the byte
+    Repeat(usize),
+    /// bytes of the checksum received so far
+    Crc(usize),
+}
+
+/// Device side of the serial protocol: decodes the received bytes into
+/// the planes of a display buffer. Feed all received bytes into `push`,
+/// once it returns `true` the frame can be shown with
+/// `epd.update(&receiver.frame(), ...)`. After an error the receiver
+/// waits for the start of the next frame.
+pub struct Receiver<'a> {
+    panel_id: u8,
+    black: &'a mut [u8],
+    red: &'a mut [u8],
+    state: State,
+    header: [u8; HEADER_LEN],
+    /// bytes of both planes written so far
+    written: usize,
+    crc: Crc,
+    received_crc: [u8; 4],
+}
+
+impl<'a> Receiver<'a> {
+    /// Receiver for frames of the panel with `panel_id` (see
+    /// `DisplaySpec::PANEL_ID`), decoding into the planes `black` and
+    /// `red` of equal length, e.g. the buffers of a `RawFrame`
+    pub fn new(panel_id: u8, black: &'a mut [u8], red: &'a mut [u8]) -> Self {
+        Self {
+            panel_id,
+            black,
+            red,
+            state: State::Header(0),
+            header: [0; HEADER_LEN],
+            written: 0,
+            crc: Crc::new(),
+            received_crc: [0; 4],
+        }
+    }
+
+    /// Planes of the last received frame. While a frame is received, they
+    /// hold parts of both frames.
+    #[must_use]
+    pub fn frame(&self) -> RawFrame<'_> {
+        RawFrame::new(self.black, self.red)
+    }
+
+    /// Process the next received byte. Returns `true` if it completed a
+    /// valid frame.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the frame is corrupted or does
+    /// not fit the panel. The frame is dropped in this case.
+    pub fn push(&mut self, byte: u8) -> Result<bool, TransportError> {
+        if !matches!(self.state, State::Crc(_)) {
+            self.crc.update(byte);
+        }
+        let result = self.process(byte);
+        if result.is_err() {
+            self.restart();
+        }
+        result
+    }
+
+    fn restart(&mut self) {
+        self.state = State::Header(0);
+        self.written = 0;
+        self.crc = Crc::new();
+    }
+
+    fn process(&mut self, byte: u8) -> Result<bool, TransportError> {
+        self.state = match self.state {
+            State::Header(pos) => {
+                // resynchronize on the magic bytes
+                if pos < MAGIC.len() && byte != MAGIC[pos] {
+                    self.restart();
+                    if byte == MAGIC[0] {
+                        self.crc.update(byte);
+                        self.header[0] = byte;
+                        self.state = State::Header(1);
+                    }
+                    return Ok(false);
+                }
+                self.header[pos] = byte;
+                if pos + 1 < HEADER_LEN {
+                    State::Header(pos + 1)
+                } else {
+                    self.check_header()?;
+                    self.next_block()
+                }
+            }
+            State::Control => match byte {
+                0..=127 => State::Literal(usize::from(byte) + 1),
+                128 => State::Control,
+                _ => State::Repeat(257 - usize::from(byte)),
+            },
+            State::Literal(remaining) => {
+                self.write(byte)?;
+                if remaining > 1 {
+                    State::Literal(remaining - 1)
+                } else {
+                    self.next_block()
+                }
+            }
+            State::Repeat(count) => {
+                for _ in 0..count {
+                    self.write(byte)?;
+                }
+                self.next_block()
+            }
+            State::Crc(pos) => {
+                self.received_crc[pos] = byte;
+                if pos < 3 {
+                    State::Crc(pos + 1)
+                } else {
+                    let valid = u32::from_le_bytes(self.received_crc) == self.crc.finish();
+                    self.restart();
+                    return if valid {
+                        Ok(true)
+                    } else {
+                        Err(TransportError::Crc)
+                    };
+                }
+            }
+        };
+        Ok(false)
+    }
+
+    fn check_header(&self) -> Result<(), TransportError> {
+        let [_, _, version, panel_id, len @ ..] = self.header;
+        if version != VERSION {
+            return Err(TransportError::UnsupportedVersion(version));
+        }
+        if panel_id != self.panel_id {
+            return Err(TransportError::WrongPanel {
+                expected: self.panel_id,
+                actual: panel_id,
+            });
+        }
+        let actual = usize::try_from(u32::from_le_bytes(len)).unwrap_or(usize::MAX);
+        let expected = self.black.len().min(self.red.len());
+        if actual != expected {
+            return Err(TransportError::SizeMismatch { expected, actual });
+        }
+        Ok(())
+    }
+
+    fn write(&mut self, byte: u8) -> Result<(), TransportError> {
+        let len = self.black.len();
+        let target = if self.written < len {
+            self.black.get_mut(self.written)
+        } else {
+            self.red.get_mut(self.written - len)
+        };
+        *target.ok_or(TransportError::InvalidData)? = byte;
+        self.written += 1;
+        Ok(())
+    }
+
+    /// State after a complete `PackBits` block
+    fn next_block(&self) -> State {
+        // no doubled length, it may overflow a 16-bit usize
+        if self.written >= self.black.len() && self.written - self.black.len() == self.black.len() {
+            State::Crc(0)
+        } else {
+            State::Control
+        }
+    }
+}
+
+/// CRC-32 (IEEE 802.3), computed with a table of 16 entries
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct Crc(u32);
+
+impl Crc {
+    const TABLE: [u32; 16] = [
+        0x0000_0000,
+        0x1db7_1064,
+        0x3b6e_20c8,
+        0x26d9_30ac,
+        0x76dc_4190,
+        0x6b6b_51f4,
+        0x4db2_6158,
+        0x5005_713c,
+        0xedb8_8320,
+        0xf00f_9344,
+        0xd6d6_a3e8,
+        0xcb61_b38c,
+        0x9b64_c2b0,
+        0x86d3_d2d4,
+        0xa00a_e278,
+        0xbdbd_f21c,
+    ];
+
+    pub(crate) fn new() -> Self {
+        Self(u32::MAX)
+    }
+
+    pub(crate) fn update(&mut self, byte: u8) {
+        let mut crc = self.0 ^ u32::from(byte);
+        crc = Self::TABLE[(crc & 0xf) as usize] ^ (crc >> 4);
+        crc = Self::TABLE[(crc & 0xf) as usize] ^ (crc >> 4);
+        self.0 = crc;
+    }
+
+    pub(crate) fn finish(self) -> u32 {
+        !self.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LEN: usize = 300;
+
+    /// Black plane with a run and a literal block longer than 128 bytes
+    #[allow(clippy::cast_possible_truncation)]
+    fn black_plane() -> [u8; LEN] {
+        let mut black = [0; LEN];
+        black[..130].fill(0xff);
+        for (i, byte) in black.iter_mut().enumerate().take(270).skip(130) {
+            *byte = i as u8;
+        }
+        black
+    }
+
+    /// Bytes written by `write`, collected in a fixed buffer
+    fn collect(write: impl FnOnce(&mut dyn FnMut(u8))) -> ([u8; 1024], usize) {
+        let mut out = [0; 1024];
+        let mut len = 0;
+        write(&mut |byte| {
+            out[len] = byte;
+            len += 1;
+        });
+        (out, len)
+    }
+
+    fn encode(panel_id: u8, black: &[u8], red: &[u8]) -> ([u8; 1024], usize) {
+        collect(|write| encode_frame(panel_id, &RawFrame::new(black, red), write))
+    }
+
+    /// Push `bytes` until the first error, result of the last pushed byte
+    fn push_all(receiver: &mut Receiver, bytes: &[u8]) -> Result<bool, TransportError> {
+        let mut result = Ok(false);
+        for &byte in bytes {
+            result = receiver.push(byte);
+            if result.is_err() {
+                break;
+            }
+        }
+        result
+    }
+
+    #[test]
+    #[allow(clippy::cast_possible_truncation)]
+    fn packbits_splits_blocks_at_128_bytes() {
+        let (out, len) = collect(|mut write| packbits(130, |_| 0x55, &mut write));
+        assert_eq!(out[..len], [129, 0x55, 1, 0x55, 0x55]);
+
+        let (out, len) = collect(|mut write| packbits(129, |i| i as u8, &mut write));
+        assert_eq!(len, 1 + 128 + 1 + 1);
+        assert_eq!(out[0], 127);
+        assert_eq!(out[129..len], [0, 128]);
+    }
+
+    #[test]
+    fn round_trip() {
+        let black = black_plane();
+        let mut red = [0; LEN];
+        red[LEN - 3..].fill(0x0f);
+        let (encoded, len) = encode(2, &black, &red);
+
+        let (mut rx_black, mut rx_red) = ([0; LEN], [0; LEN]);
+        let mut receiver = Receiver::new(2, &mut rx_black, &mut rx_red);
+        assert_eq!(push_all(&mut receiver, &encoded[..len - 1]), Ok(false));
+        assert_eq!(receiver.push(encoded[len - 1]), Ok(true));
+        assert_eq!(receiver.frame().black, black);
+        assert_eq!(receiver.frame().red, red);
+    }
+
+    #[test]
+    fn empty_red_plane_is_received_as_zeros() {
+        let (encoded, len) = encode(2, &black_plane(), &[]);
+        let (mut rx_black, mut rx_red) = ([0; LEN], [0xff; LEN]);
+        let mut receiver = Receiver::new(2, &mut rx_black, &mut rx_red);
+        assert_eq!(push_all(&mut receiver, &encoded[..len]), Ok(true));
+        assert_eq!(receiver.frame().red, [0; LEN]);
+    }
+
+    #[test]
+    fn corrupted_crc_is_rejected() {
+        let (mut encoded, len) = encode(2, &black_plane(), &[]);
+        encoded[len - 1] ^= 0x01;
+        let (mut rx_black, mut rx_red) = ([0; LEN], [0; LEN]);
+        let mut receiver = Receiver::new(2, &mut rx_black, &mut rx_red);
+        assert_eq!(
+            push_all(&mut receiver, &encoded[..len]),
+            Err(TransportError::Crc)
+        );
+
+        // the next valid frame is accepted
+        encoded[len - 1] ^= 0x01;
+        assert_eq!(push_all(&mut receiver, &encoded[..len]), Ok(true));
+    }
+
+    #[test]
+    fn resync_after_leading_garbage() {
+        let (encoded, len) = encode(2, &black_plane(), &[]);
+        let (mut rx_black, mut rx_red) = ([0; LEN], [0; LEN]);
+        let mut receiver = Receiver::new(2, &mut rx_black, &mut rx_red);
+        // a partial magic, the last byte is the start of a false magic
+        assert_eq!(push_all(&mut receiver, b"\x00E\x13SE"), Ok(false));
+        assert_eq!(push_all(&mut receiver, &encoded[..len]), Ok(true));
+        assert_eq!(receiver.frame().black, black_plane());
+    }
+
+    #[test]
+    fn frame_for_another_panel_is_rejected() {
+        let (encoded, len) = encode(3, &black_plane(), &[]);
+        let (mut rx_black, mut rx_red) = ([0; LEN], [0; LEN]);
+        let mut receiver = Receiver::new(2, &mut rx_black, &mut rx_red);
+        assert_eq!(
+            push_all(&mut receiver, &encoded[..len]),
+            Err(TransportError::WrongPanel {
+                expected: 2,
+                actual: 3
+            })
+        );
+    }
+
+    #[test]
+    fn frame_of_another_size_is_rejected() {
+        let (encoded, len) = encode(2, &black_plane(), &[]);
+        let (mut rx_black, mut rx_red) = ([0; LEN - 1], [0; LEN - 1]);
+        let mut receiver = Receiver::new(2, &mut rx_black, &mut rx_red);
+        assert_eq!(
+            push_all(&mut receiver, &encoded[..len]),
+            Err(TransportError::SizeMismatch {
+                expected: LEN - 1,
+                actual: LEN
+            })
+        );
+    }
+}
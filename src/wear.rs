@@ -0,0 +1,133 @@
+//! Refresh counter persisted in non-volatile memory, e.g. to schedule
+//! maintenance refreshes of signage and to report the age of the panel
+
+use embedded_storage::Storage;
+
+use crate::transport::Crc;
+
+const MAGIC: [u8; 2] = *b"EW";
+const VERSION: u8 = 1;
+/// Length of the record in the storage
+pub const USAGE_RECORD_LEN: usize = 20;
+
+/// Usage of a panel, see [`UsageTracker`]
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub struct UsageRecord {
+    /// number of all refreshes (full and partial)
+    pub refreshes: u32,
+    /// time of the last full refresh, in the unit of the application
+    /// (e.g. seconds of a RTC), `None` if there was none
+    pub last_full_refresh: Option<u64>,
+}
+
+impl UsageRecord {
+    /// Record as stored: magic, version, a flag for the timestamp, the
+    /// counter and the timestamp (little endian) and a CRC-32
+    fn to_bytes(self) -> [u8; USAGE_RECORD_LEN] {
+        let mut bytes = [0; USAGE_RECORD_LEN];
+        bytes[..2].copy_from_slice(&MAGIC);
+        bytes[2] = VERSION;
+        bytes[3] = u8::from(self.last_full_refresh.is_some());
+        bytes[4..8].copy_from_slice(&self.refreshes.to_le_bytes());
+        bytes[8..16].copy_from_slice(&self.last_full_refresh.unwrap_or(0).to_le_bytes());
+        let crc = checksum(&bytes[..16]);
+        bytes[16..].copy_from_slice(&crc.to_le_bytes());
+        bytes
+    }
+
+    /// Parse a stored record, `None` for erased or corrupted memory
+    fn from_bytes(bytes: &[u8; USAGE_RECORD_LEN]) -> Option<Self> {
+        let crc = u32::from_le_bytes(bytes[16..].try_into().ok()?);
+        if bytes[..2] != MAGIC || bytes[2] != VERSION || crc != checksum(&bytes[..16]) {
+            return None;
+        }
+        let timestamp = u64::from_le_bytes(bytes[8..16].try_into().ok()?);
+        Some(Self {
+            refreshes: u32::from_le_bytes(bytes[4..8].try_into().ok()?),
+            last_full_refresh: (bytes[3] != 0).then_some(timestamp),
+        })
+    }
+}
+
+fn checksum(bytes: &[u8]) -> u32 {
+    let mut crc = Crc::new();
+    for &byte in bytes {
+        crc.update(byte);
+    }
+    crc.finish()
+}
+
+/// Counter of the refreshes of a panel, stored at `offset` of an
+/// `embedded-storage` backend (e.g. the internal flash or an EEPROM).
+/// Call `record_refresh` or `record_full_refresh` after each successful
+/// update. Every call writes [`USAGE_RECORD_LEN`] bytes, for flash
+/// without wear leveling use a backend which spreads the writes.
+pub struct UsageTracker<S> {
+    storage: S,
+    offset: u32,
+    record: UsageRecord,
+}
+
+impl<S: Storage> UsageTracker<S> {
+    /// Load the record stored at `offset`. Erased or corrupted memory
+    /// starts a new record without refreshes.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the storage cannot be read.
+    pub fn load(mut storage: S, offset: u32) -> Result<Self, S::Error> {
+        let mut bytes = [0; USAGE_RECORD_LEN];
+        storage.read(offset, &mut bytes)?;
+        let record = UsageRecord::from_bytes(&bytes).unwrap_or_default();
+        Ok(Self {
+            storage,
+            offset,
+            record,
+        })
+    }
+
+    #[must_use]
+    pub fn record(&self) -> UsageRecord {
+        self.record
+    }
+
+    /// Count a partial refresh and store the record
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the storage cannot be written.
+    pub fn record_refresh(&mut self) -> Result<(), S::Error> {
+        self.record.refreshes = self.record.refreshes.saturating_add(1);
+        self.save()
+    }
+
+    /// Count a full refresh at `timestamp` and store the record
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the storage cannot be written.
+    pub fn record_full_refresh(&mut self, timestamp: u64) -> Result<(), S::Error> {
+        self.record.refreshes = self.record.refreshes.saturating_add(1);
+        self.record.last_full_refresh = Some(timestamp);
+        self.save()
+    }
+
+    /// A full refresh is due because there was none within `interval`
+    /// before `now` (or none at all), e.g. to clear ghosting of partial
+    /// refreshes once a day
+    #[must_use]
+    pub fn maintenance_due(&self, now: u64, interval: u64) -> bool {
+        self.record
+            .last_full_refresh
+            .map_or(true, |last| now.saturating_sub(last) >= interval)
+    }
+
+    /// Release the storage
+    pub fn into_storage(self) -> S {
+        self.storage
+    }
+
+    fn save(&mut self) -> Result<(), S::Error> {
+        self.storage.write(self.offset, &self.record.to_bytes())
+    }
+}
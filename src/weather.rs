@@ -0,0 +1,161 @@
+//! Scalable weather icons drawn with primitives
+
+use embedded_graphics::{
+    draw_target::DrawTarget,
+    geometry::{Angle, Dimensions, Point, Size},
+    primitives::{Arc, Circle, Line, Primitive, PrimitiveStyle, Rectangle},
+    Drawable,
+};
+
+use crate::TriColor;
+
+/// Weather conditions with an icon
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Weather {
+    Sun,
+    Cloud,
+    Rain,
+    Snow,
+    Wind,
+}
+
+/// Directions of the sun rays, scaled by 100
+const RAYS: [(i32, i32); 8] = [
+    (100, 0),
+    (71, 71),
+    (0, 100),
+    (-71, 71),
+    (-100, 0),
+    (-71, -71),
+    (0, -100),
+    (71, -71),
+];
+
+/// Square icon of a weather condition. The sun is drawn in red, everything
+/// else in black. The line width scales with the size of the icon.
+#[derive(Clone, Copy, Debug)]
+pub struct WeatherIcon {
+    weather: Weather,
+    top_left: Point,
+    size: u32,
+}
+
+impl WeatherIcon {
+    /// Icon of `weather` with `size` pixels width and height
+    #[must_use]
+    pub fn new(weather: Weather, top_left: Point, size: u32) -> Self {
+        Self {
+            weather,
+            top_left,
+            size,
+        }
+    }
+
+    /// Point given in units of 1/100 of the icon size
+    fn point(&self, x: i32, y: i32) -> Point {
+        let size = i32::try_from(self.size).unwrap_or(i32::MAX);
+        self.top_left + Point::new(x * size / 100, y * size / 100)
+    }
+
+    /// Length given in units of 1/100 of the icon size, at least one pixel
+    fn length(&self, units: u32) -> u32 {
+        (units * self.size / 100).max(1)
+    }
+
+    fn stroke(&self, color: TriColor) -> PrimitiveStyle<TriColor> {
+        PrimitiveStyle::with_stroke(color, self.length(6))
+    }
+
+    fn line<D>(&self, target: &mut D, start: (i32, i32), end: (i32, i32)) -> Result<(), D::Error>
+    where
+        D: DrawTarget<Color = TriColor>,
+    {
+        Line::new(self.point(start.0, start.1), self.point(end.0, end.1))
+            .into_styled(self.stroke(TriColor::Black))
+            .draw(target)
+    }
+
+    fn circle<D>(&self, target: &mut D, center: (i32, i32), diameter: u32) -> Result<(), D::Error>
+    where
+        D: DrawTarget<Color = TriColor>,
+    {
+        Circle::with_center(self.point(center.0, center.1), self.length(diameter))
+            .into_styled(PrimitiveStyle::with_fill(TriColor::Black))
+            .draw(target)
+    }
+
+    /// Cloud of the width of the icon, with its base at `bottom`
+    fn cloud<D>(&self, target: &mut D, bottom: i32) -> Result<(), D::Error>
+    where
+        D: DrawTarget<Color = TriColor>,
+    {
+        self.circle(target, (30, bottom - 17), 30)?;
+        self.circle(target, (55, bottom - 24), 42)?;
+        self.circle(target, (75, bottom - 14), 26)?;
+        Rectangle::with_corners(self.point(15, bottom - 15), self.point(85, bottom))
+            .into_styled(PrimitiveStyle::with_fill(TriColor::Black))
+            .draw(target)
+    }
+}
+
+impl Dimensions for WeatherIcon {
+    fn bounding_box(&self) -> Rectangle {
+        Rectangle::new(self.top_left, Size::new_equal(self.size))
+    }
+}
+
+impl Drawable for WeatherIcon {
+    type Color = TriColor;
+    type Output = ();
+
+    fn draw<D>(&self, target: &mut D) -> Result<Self::Output, D::Error>
+    where
+        D: DrawTarget<Color = Self::Color>,
+    {
+        match self.weather {
+            Weather::Sun => {
+                Circle::with_center(self.point(50, 50), self.length(44))
+                    .into_styled(PrimitiveStyle::with_fill(TriColor::Red))
+                    .draw(target)?;
+                for (x, y) in RAYS {
+                    let start = self.point(50 + x * 30 / 100, 50 + y * 30 / 100);
+                    let end = self.point(50 + x * 45 / 100, 50 + y * 45 / 100);
+                    Line::new(start, end)
+                        .into_styled(self.stroke(TriColor::Red))
+                        .draw(target)?;
+                }
+            }
+            Weather::Cloud => self.cloud(target, 75)?,
+            Weather::Rain => {
+                self.cloud(target, 60)?;
+                for x in [30, 50, 70] {
+                    self.line(target, (x, 70), (x - 8, 92))?;
+                }
+            }
+            Weather::Snow => {
+                self.cloud(target, 60)?;
+                for (x, y) in [(30, 74), (50, 86), (70, 74)] {
+                    self.circle(target, (x, y), 10)?;
+                }
+            }
+            Weather::Wind => {
+                self.line(target, (10, 35), (65, 35))?;
+                self.line(target, (10, 50), (85, 50))?;
+                self.line(target, (10, 65), (55, 65))?;
+                // curls at the ends of the upper and lower line
+                let curl = self.length(16);
+                for (center, start) in [((65, 27), 90.0), ((55, 73), -90.0)] {
+                    Arc::with_center(
+                        self.point(center.0, center.1),
+                        curl,
+                        Angle::from_degrees(start),
+                        Angle::from_degrees(-start * 2.0),
+                    )
+                    .into_styled(self.stroke(TriColor::Black))
+                    .draw(target)?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
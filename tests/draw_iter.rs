@@ -0,0 +1,120 @@
+//! Property tests of the pixel packing of `Display::draw_iter` for all
+//! display sizes and rotations, including points outside of the display.
+//! `cargo test --features="std" --test draw_iter`, set `PROPTEST_CASES`
+//! for more cases.
+
+use std::collections::HashMap;
+
+use embedded_graphics::{draw_target::DrawTarget, geometry::Point, Pixel};
+use epd_spectra::{
+    Display1in54, Display2in13, Display2in66, Display2in71, Display2in87, Display3in70,
+    Display4in17, Display4in37, DisplayBuffer, DisplayRotation, TriColor,
+};
+use proptest::prelude::*;
+
+fn rotation() -> impl Strategy<Value = DisplayRotation> {
+    prop_oneof![
+        Just(DisplayRotation::Rotate0),
+        Just(DisplayRotation::Rotate90),
+        Just(DisplayRotation::Rotate180),
+        Just(DisplayRotation::Rotate270),
+    ]
+}
+
+fn color() -> impl Strategy<Value = TriColor> {
+    prop_oneof![
+        Just(TriColor::White),
+        Just(TriColor::Black),
+        Just(TriColor::Red)
+    ]
+}
+
+/// Pixels mostly on the display of `width` × `height` (rotated size),
+/// with some far outside and some just beyond the borders
+fn pixels(width: i32, height: i32) -> impl Strategy<Value = Vec<Pixel<TriColor>>> {
+    let coordinate = |len: i32| {
+        prop_oneof![
+            8 => 0..len,
+            1 => -2..len + 2,
+            1 => any::<i32>(),
+        ]
+    };
+    let pixel = (coordinate(width), coordinate(height), color())
+        .prop_map(|(x, y, color)| Pixel(Point::new(x, y), color));
+    prop::collection::vec(pixel, 0..400)
+}
+
+/// Pixel in panel coordinates, i.e. the index of the byte and the bit in
+/// the planes, of a point in rotated coordinates
+fn panel_position(
+    (size_v, size_h): (i32, i32),
+    rotation: DisplayRotation,
+    point: Point,
+) -> Option<(usize, u8)> {
+    let (x, y) = match rotation {
+        DisplayRotation::Rotate0 => (point.x, point.y),
+        DisplayRotation::Rotate90 => (size_h - 1 - point.y, point.x),
+        DisplayRotation::Rotate180 => (size_h - 1 - point.x, size_v - 1 - point.y),
+        DisplayRotation::Rotate270 => (point.y, size_v - 1 - point.x),
+    };
+    if !(0..size_h).contains(&x) || !(0..size_v).contains(&y) {
+        return None;
+    }
+    let index = usize::try_from(y * size_h / 8 + x / 8).ok()?;
+    Some((index, 0x80 >> (x % 8)))
+}
+
+macro_rules! draw_iter_tests {
+    ($($name:ident: $display:ty, $size_v:literal, $size_h:literal;)*) => {$(
+        proptest! {
+            #[test]
+            fn $name(rotation in rotation(), pixels in pixels($size_h.max($size_v), $size_h.max($size_v))) {
+                let mut display = <$display>::default();
+                display.set_rotation(rotation);
+                display.draw_iter(pixels.iter().copied()).unwrap();
+
+                // last color drawn to each bit of the panel
+                let mut expected = HashMap::new();
+                for Pixel(point, color) in &pixels {
+                    let position = panel_position(($size_v, $size_h), rotation, *point);
+                    prop_assert_eq!(position.is_some(), display.get_pixel(*point).is_some());
+                    if let Some(position) = position {
+                        expected.insert(position, *color);
+                    }
+                }
+
+                let len = $size_v * $size_h / 8;
+                let (mut black, mut red) = (vec![0; len], vec![0; len]);
+                for (&(index, mask), color) in &expected {
+                    match color {
+                        TriColor::White => {}
+                        TriColor::Black => black[index] |= mask,
+                        TriColor::Red => red[index] |= mask,
+                    }
+                }
+                prop_assert_eq!(display.get_buffer_black(), &black[..]);
+                prop_assert_eq!(display.get_buffer_red(), &red[..]);
+                for (index, (black, red)) in display.black_rows().flatten().zip(display.red_rows().flatten()).enumerate() {
+                    prop_assert_eq!(black & red, 0, "black and red set in byte {}", index);
+                }
+
+                for Pixel(point, _) in &pixels {
+                    if let Some(position) = panel_position(($size_v, $size_h), rotation, *point) {
+                        prop_assert_eq!(display.get_pixel(*point), Some(expected[&position]));
+                    }
+                }
+            }
+        }
+    )*};
+}
+
+draw_iter_tests! {
+    draw_iter_1in54: Display1in54, 152, 152;
+    draw_iter_2in13: Display2in13, 212, 104;
+    draw_iter_2in66: Display2in66, 296, 152;
+    draw_iter_2in71: Display2in71, 264, 176;
+    draw_iter_2in87: Display2in87, 296, 128;
+    draw_iter_3in70: Display3in70, 416, 240;
+    draw_iter_4in17: Display4in17, 300, 400;
+    draw_iter_4in37: Display4in37, 480, 176;
+}
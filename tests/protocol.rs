@@ -0,0 +1,273 @@
+//! Regression tests of the command sequences sent by the driver, checked
+//! with the recording test doubles.
+//! `cargo test --features="testing" --test protocol`
+
+use core::sync::atomic::{AtomicU8, Ordering};
+
+use embedded_graphics::{
+    prelude::*,
+    primitives::{PrimitiveStyle, Rectangle},
+};
+use epd_spectra::{
+    testing::{assert_sequence, Cmd, Record, Recorder, RecordingDc, RecordingRst},
+    Active, BitPolarity, BusySource, Command, Display2in66, DisplayRotation, DisplaySpec,
+    DoubleDisplay, Epd, Inactive, NoBusyPin, Panel2in66, PanelVariant, Plane, TriColor,
+};
+
+const LEN: usize = Display2in66::BUFFER_LEN;
+
+/// Commands sent by `init` without optional registers
+const INIT: [Cmd<'static>; 4] = [
+    Cmd::Psr(&[0x0e]),
+    Cmd::InputTemperature(&[0x19]),
+    Cmd::ActiveTemperature(&[0x02]),
+    Cmd::Psr(&[0xcf, 0x8d]),
+];
+
+type Driver<STATE> = Epd<STATE, NoBusyPin, RecordingDc, RecordingRst>;
+
+/// Driver initialized with the recording doubles, after `configure`
+fn init(recorder: &Recorder, configure: impl FnOnce(&mut Driver<Inactive>)) -> Driver<Active> {
+    let mut epd = Epd::new(recorder.busy(), recorder.dc(), recorder.rst(), 0);
+    configure(&mut epd);
+    epd.init(&mut recorder.spi(), &mut recorder.delay())
+        .unwrap()
+}
+
+#[test]
+fn init_update_power_off() {
+    let recorder = Recorder::new();
+    let mut spi = recorder.spi();
+    let mut delay = recorder.delay();
+    let mut epd = init(&recorder, |_| {});
+    epd.update(&Display2in66::default(), &mut spi, &mut delay)
+        .unwrap();
+    epd.power_off(&mut spi, &mut delay).unwrap();
+    let mut expected = INIT.to_vec();
+    expected.extend([
+        Cmd::BufferBlack(LEN),
+        Cmd::BufferRed(LEN),
+        Cmd::PowerOn,
+        Cmd::Refresh,
+        Cmd::PowerOff,
+    ]);
+    assert_sequence(&recorder.records(), &expected);
+}
+
+#[test]
+fn update_changed_sends_window() {
+    let recorder = Recorder::new();
+    let mut spi = recorder.spi();
+    let mut delay = recorder.delay();
+    let mut epd = init(&recorder, |_| {});
+    recorder.clear();
+
+    let mut display: DoubleDisplay<296, 152, LEN> = DoubleDisplay::default();
+    display.current_mut().set_rotation(DisplayRotation::Rotate0);
+    Rectangle::new(Point::new(8, 10), Size::new(16, 4))
+        .into_styled(PrimitiveStyle::with_fill(TriColor::Black))
+        .draw(display.current_mut())
+        .unwrap();
+    assert!(epd
+        .update_changed(&mut display, &mut spi, &mut delay)
+        .unwrap());
+    // bytes 1 and 2 of rows 10 to 13
+    assert_sequence(
+        &recorder.records(),
+        &[
+            Cmd::Raw(Command::PartialIn as u8, &[]),
+            Cmd::Raw(
+                Command::PartialWindow as u8,
+                &[0, 8, 0, 23, 0, 10, 0, 13, 0x01],
+            ),
+            Cmd::BufferBlack(8),
+            Cmd::BufferRed(8),
+            Cmd::PowerOn,
+            Cmd::Refresh,
+            Cmd::Raw(Command::PartialOut as u8, &[]),
+        ],
+    );
+
+    // nothing changed since the last update
+    recorder.clear();
+    assert!(!epd
+        .update_changed(&mut display, &mut spi, &mut delay)
+        .unwrap());
+    assert!(recorder.commands().is_empty());
+}
+
+#[test]
+fn inverted_polarity() {
+    let recorder = Recorder::new();
+    let mut spi = recorder.spi();
+    let mut delay = recorder.delay();
+    let mut epd = init(&recorder, |epd| {
+        epd.set_polarity(Plane::Black, BitPolarity::Inverted);
+    });
+    recorder.clear();
+    let mut display = Display2in66::default();
+    display.clear(TriColor::Black).unwrap();
+    epd.update(&display, &mut spi, &mut delay).unwrap();
+    // black pixels are sent as cleared bits, the red plane is not inverted
+    let zeros = vec![0; LEN];
+    assert_sequence(
+        &recorder.records(),
+        &[
+            Cmd::Raw(Command::BufferBlack as u8, &zeros),
+            Cmd::Raw(Command::BufferRed as u8, &zeros),
+            Cmd::PowerOn,
+            Cmd::Refresh,
+        ],
+    );
+}
+
+#[test]
+fn busy_from_status_register() {
+    let recorder = Recorder::new();
+    let mut spi = recorder.spi();
+    let mut delay = recorder.delay();
+    // the status register reports an idle COG
+    recorder.set_read_value(0x01);
+    let mut epd = init(&recorder, |epd| epd.set_busy_source(BusySource::Status));
+    epd.update(&Display2in66::default(), &mut spi, &mut delay)
+        .unwrap();
+    let status = Cmd::Any(Command::GetStatus as u8);
+    assert_sequence(
+        &recorder.records(),
+        &[
+            Cmd::Psr(&[0x0e]),
+            status,
+            Cmd::InputTemperature(&[0x19]),
+            Cmd::ActiveTemperature(&[0x02]),
+            Cmd::Psr(&[0xcf, 0x8d]),
+            Cmd::BufferBlack(LEN),
+            Cmd::BufferRed(LEN),
+            Cmd::PowerOn,
+            status,
+            Cmd::Refresh,
+            status,
+        ],
+    );
+    let reads = recorder
+        .records()
+        .iter()
+        .filter(|record| matches!(record, Record::Read { len: 1 }))
+        .count();
+    assert_eq!(reads, 3);
+}
+
+#[test]
+fn internal_temperature_is_measured_once() {
+    let recorder = Recorder::new();
+    recorder.set_read_value(0x19);
+    init(&recorder, |epd| epd.set_internal_temperature(true));
+    let measure = Command::TemperatureCalibration as u8;
+    assert_sequence(
+        &recorder.records(),
+        &[
+            Cmd::Psr(&[0x0e]),
+            Cmd::Any(measure),
+            Cmd::InputTemperature(&[0x19]),
+            Cmd::ActiveTemperature(&[0x02]),
+            Cmd::Psr(&[0xcf, 0x8d]),
+        ],
+    );
+    let measurements = recorder
+        .commands()
+        .iter()
+        .filter(|&&command| command == measure)
+        .count();
+    assert_eq!(measurements, 1);
+    assert!(recorder.records().contains(&Record::Read { len: 2 }));
+}
+
+#[test]
+fn internal_temperature_is_clamped_by_the_panel_table() {
+    let recorder = Recorder::new();
+    // 45 °C, above the range of the Spectra panels
+    recorder.set_read_value(45);
+    init(&recorder, |epd| epd.set_internal_temperature(true));
+    assert_sequence(
+        &recorder.records(),
+        &[
+            Cmd::Psr(&[0x0e]),
+            Cmd::Any(Command::TemperatureCalibration as u8),
+            Cmd::InputTemperature(&[40]),
+            Cmd::ActiveTemperature(&[0x02]),
+            Cmd::Psr(&[0xcf, 0x8d]),
+        ],
+    );
+}
+
+#[test]
+fn wide_temperature_variant() {
+    let recorder = Recorder::new();
+    // -20 °C, below the range of the wide temperature panels
+    recorder.set_read_value((-20i8).to_be_bytes()[0]);
+    init(&recorder, |epd| {
+        epd.set_panel::<Display2in66>();
+        epd.set_variant(PanelVariant::WideTemperature);
+        epd.set_internal_temperature(true);
+    });
+    assert_sequence(
+        &recorder.records(),
+        &[
+            Cmd::Psr(&[0x0e]),
+            Cmd::Any(Command::TemperatureCalibration as u8),
+            Cmd::InputTemperature(&[(-15i8).to_be_bytes()[0]]),
+            Cmd::ActiveTemperature(&[0x02]),
+            Cmd::Psr(&Display2in66::WIDE_TEMPERATURE_PSR),
+        ],
+    );
+}
+
+#[test]
+fn panel_marker_matches_the_display_type() {
+    assert_eq!(Panel2in66::PANEL_ID, Display2in66::PANEL_ID);
+    assert_eq!(Panel2in66::BUFFER_LEN, Display2in66::BUFFER_LEN);
+    assert_eq!(
+        Panel2in66::NATURAL_ROTATION,
+        Display2in66::default().rotation()
+    );
+    let recorder = Recorder::new();
+    init(&recorder, |epd| epd.set_panel::<Panel2in66>());
+    assert_sequence(&recorder.records(), &[Cmd::Psr(&Display2in66::PSR)]);
+}
+
+#[test]
+fn explicit_tcon_overrides_the_panel() {
+    let recorder = Recorder::new();
+    init(&recorder, |epd| {
+        epd.set_panel::<Display2in66>();
+        epd.set_tcon(Some(0x22));
+    });
+    assert_sequence(
+        &recorder.records(),
+        &[
+            Cmd::Psr(&Display2in66::PSR),
+            Cmd::Raw(Command::Tcon as u8, &[0x22]),
+        ],
+    );
+}
+
+#[test]
+fn refresh_handle_reports_progress() {
+    static LAST_PROGRESS: AtomicU8 = AtomicU8::new(u8::MAX);
+    static REPORTS: AtomicU8 = AtomicU8::new(0);
+    fn report(progress: u8) {
+        LAST_PROGRESS.store(progress, Ordering::Relaxed);
+        REPORTS.fetch_add(1, Ordering::Relaxed);
+    }
+
+    let recorder = Recorder::new();
+    let mut spi = recorder.spi();
+    let mut delay = recorder.delay();
+    let mut epd = init(&recorder, |_| {});
+    epd.set_progress_hook(Some(report), 20_000);
+    epd.start_refresh(&mut spi, &mut delay)
+        .unwrap()
+        .wait(&mut delay)
+        .unwrap();
+    assert_eq!(LAST_PROGRESS.load(Ordering::Relaxed), 100);
+    assert!(REPORTS.load(Ordering::Relaxed) >= 2);
+}